@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the APPEND command.
+#[derive(Debug)]
+pub struct Append {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl Append {
+    /// Creates a new Append instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<Append, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("append"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let value = parse_bytes(&args[1])?;
+
+        Ok(Append { key, value })
+    }
+
+    /// Executes the APPEND command, replying with the new length of the string.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.append(self.key.clone(), self.value.clone()) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
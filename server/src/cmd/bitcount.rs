@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::bitmap::BitRange;
+use crate::storage::db::DB;
+
+/// Represents the BITCOUNT command.
+#[derive(Debug)]
+pub struct BitCount {
+    key: Bytes,
+    range: BitRange,
+}
+
+impl BitCount {
+    /// Creates a new BitCount instance from the given args.
+    /// Syntax: BITCOUNT key [start end [BYTE | BIT]]
+    pub fn with_args(args: Vec<RespType>) -> Result<BitCount, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("bitcount"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let range = match args.len() {
+            1 => BitRange::default(),
+            3 | 4 => BitRange {
+                start: parse_i64(&args[1])?,
+                end: Some(parse_i64(&args[2])?),
+                bit: args.get(3).map(parse_bit_unit).transpose()?.unwrap_or(false),
+            },
+            _ => return Err(CommandError::Syntax),
+        };
+
+        Ok(BitCount { key, range })
+    }
+
+    /// Executes the BITCOUNT command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.bitcount(&self.key, self.range) {
+            Ok(count) => RespType::Integer(count as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the unit of a bit range, returning true for BIT and false for BYTE.
+pub fn parse_bit_unit(arg: &RespType) -> Result<bool, CommandError> {
+    match parse_option(arg)?.as_str() {
+        "byte" => Ok(false),
+        "bit" => Ok(true),
+        _ => Err(CommandError::Syntax),
+    }
+}
@@ -0,0 +1,134 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::bitmap::{BitFieldOp, BitFieldType, Overflow};
+use crate::storage::db::DB;
+use crate::storage::string::MAX_STRING_LEN;
+
+/// Represents the BITFIELD and BITFIELD_RO commands.
+#[derive(Debug)]
+pub struct BitField {
+    key: Bytes,
+    ops: Vec<BitFieldOp>,
+}
+
+impl BitField {
+    /// Creates a new BitField instance from the given args.
+    /// BITFIELD_RO (`read_only`) only accepts the GET subcommand.
+    /// Syntax: BITFIELD key [GET encoding offset | [OVERFLOW WRAP | SAT | FAIL]
+    ///   SET encoding offset value | INCRBY encoding offset increment ...]
+    pub fn with_args(args: Vec<RespType>, read_only: bool) -> Result<BitField, CommandError> {
+        if args.is_empty() {
+            let name = if read_only { "bitfield_ro" } else { "bitfield" };
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let mut ops = Vec::new();
+        let mut overflow = Overflow::default();
+
+        let mut i = 1;
+        while i < args.len() {
+            let opt = parse_option(&args[i])?;
+            let remaining = args.len() - i - 1;
+
+            match opt.as_str() {
+                "get" if remaining >= 2 => {
+                    let ty = parse_type(&args[i + 1])?;
+                    let offset = parse_offset(&args[i + 2], ty)?;
+                    ops.push(BitFieldOp::Get { ty, offset });
+                    i += 3;
+                }
+                "set" | "incrby" if read_only => {
+                    return Err(CommandError::Other(String::from(
+                        "BITFIELD_RO only supports the GET subcommand",
+                    )));
+                }
+                "set" if remaining >= 3 => {
+                    let ty = parse_type(&args[i + 1])?;
+                    let offset = parse_offset(&args[i + 2], ty)?;
+                    let value = parse_i64(&args[i + 3])?;
+                    ops.push(BitFieldOp::Set { ty, offset, value, overflow });
+                    i += 4;
+                }
+                "incrby" if remaining >= 3 => {
+                    let ty = parse_type(&args[i + 1])?;
+                    let offset = parse_offset(&args[i + 2], ty)?;
+                    let incr = parse_i64(&args[i + 3])?;
+                    ops.push(BitFieldOp::IncrBy { ty, offset, incr, overflow });
+                    i += 4;
+                }
+                "overflow" if read_only => {
+                    return Err(CommandError::Other(String::from(
+                        "BITFIELD_RO only supports the GET subcommand",
+                    )));
+                }
+                "overflow" if remaining >= 1 => {
+                    overflow = match parse_option(&args[i + 1])?.as_str() {
+                        "wrap" => Overflow::Wrap,
+                        "sat" => Overflow::Sat,
+                        "fail" => Overflow::Fail,
+                        _ => return Err(CommandError::Other(String::from(
+                            "Invalid OVERFLOW type specified",
+                        ))),
+                    };
+                    i += 2;
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        Ok(BitField { key, ops })
+    }
+
+    /// Executes the command, replying with the result of each GET, SET and INCRBY.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.bitfield(self.key.clone(), &self.ops) {
+            Ok(results) => RespType::Array(
+                results
+                    .into_iter()
+                    .map(|result| match result {
+                        Some(value) => RespType::Integer(value),
+                        None => RespType::NullBulkString,
+                    })
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses an integer type such as `i8` or `u16`.
+fn parse_type(arg: &RespType) -> Result<BitFieldType, CommandError> {
+    let ty = parse_bytes(arg)?;
+    let bits = ty.get(1..).and_then(crate::utils::parse_i64);
+
+    let signed = match (ty.first(), bits) {
+        (Some(b'i' | b'I'), Some(1..=64)) => true,
+        (Some(b'u' | b'U'), Some(1..=63)) => false,
+        _ => return Err(CommandError::Other(String::from(
+            "Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.",
+        ))),
+    };
+
+    Ok(BitFieldType { signed, bits: bits.unwrap_or_default() as u32 })
+}
+
+/// Parses a bit offset. An offset prefixed with `#` is multiplied by the width of the type.
+fn parse_offset(arg: &RespType, ty: BitFieldType) -> Result<u64, CommandError> {
+    let offset = parse_bytes(arg)?;
+    let offset = match offset.strip_prefix(b"#") {
+        Some(index) => crate::utils::parse_i64(index).and_then(|i| i.checked_mul(ty.bits as i64)),
+        None => crate::utils::parse_i64(&offset),
+    };
+
+    match offset {
+        Some(offset) if offset >= 0 && (offset as u64) < MAX_STRING_LEN as u64 * 8 => {
+            Ok(offset as u64)
+        }
+        _ => Err(CommandError::Other(String::from(
+            "bit offset is not an integer or out of range",
+        ))),
+    }
+}
@@ -0,0 +1,49 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::bitmap::BitOp as Op;
+use crate::storage::db::DB;
+
+/// Represents the BITOP command.
+#[derive(Debug)]
+pub struct BitOp {
+    op: Op,
+    dest: Bytes,
+    keys: Vec<Bytes>,
+}
+
+impl BitOp {
+    /// Creates a new BitOp instance from the given args.
+    /// Syntax: BITOP AND | OR | XOR | NOT destkey key [key ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<BitOp, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("bitop"));
+        }
+
+        let op = match parse_option(&args[0])?.as_str() {
+            "and" => Op::And,
+            "or" => Op::Or,
+            "xor" => Op::Xor,
+            "not" => Op::Not,
+            _ => return Err(CommandError::Syntax),
+        };
+        let dest = parse_bytes(&args[1])?;
+        let keys: Vec<Bytes> = args[2..].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+        if matches!(op, Op::Not) && keys.len() != 1 {
+            return Err(CommandError::Other(String::from(
+                "BITOP NOT must be called with a single source key.",
+            )));
+        }
+
+        Ok(BitOp { op, dest, keys })
+    }
+
+    /// Executes the BITOP command, replying with the length of the result.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.bitop(self.op, self.dest.clone(), &self.keys) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::bitcount::parse_bit_unit;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::bitmap::BitRange;
+use crate::storage::db::DB;
+
+/// Represents the BITPOS command.
+#[derive(Debug)]
+pub struct BitPos {
+    key: Bytes,
+    bit: bool,
+    range: BitRange,
+}
+
+impl BitPos {
+    /// Creates a new BitPos instance from the given args.
+    /// Syntax: BITPOS key bit [start [end [BYTE | BIT]]]
+    pub fn with_args(args: Vec<RespType>) -> Result<BitPos, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("bitpos"));
+        }
+        if args.len() > 5 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let bit = match parse_i64(&args[1])? {
+            0 => false,
+            1 => true,
+            _ => return Err(CommandError::Other(String::from(
+                "The bit argument must be 1 or 0.",
+            ))),
+        };
+        let range = BitRange {
+            start: args.get(2).map(parse_i64).transpose()?.unwrap_or(0),
+            end: args.get(3).map(parse_i64).transpose()?,
+            bit: args.get(4).map(parse_bit_unit).transpose()?.unwrap_or(false),
+        };
+
+        Ok(BitPos { key, bit, range })
+    }
+
+    /// Executes the BITPOS command, replying -1 if no such bit is found.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.bitpos(&self.key, self.bit, self.range) {
+            Ok(Some(pos)) => RespType::Integer(pos as i64),
+            Ok(None) => RespType::Integer(-1),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
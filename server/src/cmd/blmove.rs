@@ -0,0 +1,68 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_list_end, parse_timeout};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the BLMOVE command.
+#[derive(Debug)]
+pub struct BLMove {
+    /// The source list, the only key waited for.
+    src: [Bytes; 1],
+    dest: Bytes,
+    from: ListEnd,
+    to: ListEnd,
+    timeout: Option<Duration>,
+}
+
+impl BLMove {
+    /// Creates a new BLMove instance from the given args.
+    /// Syntax: BLMOVE source destination LEFT | RIGHT LEFT | RIGHT timeout
+    pub fn with_args(args: Vec<RespType>) -> Result<BLMove, CommandError> {
+        if args.len() != 5 {
+            return Err(CommandError::WrongArity("blmove"));
+        }
+
+        let src = parse_bytes(&args[0])?;
+        let dest = parse_bytes(&args[1])?;
+        let from = parse_list_end(&args[2])?;
+        let to = parse_list_end(&args[3])?;
+        let timeout = parse_timeout(&args[4])?;
+
+        Ok(BLMove { src: [src], dest, from, to, timeout })
+    }
+
+    /// Executes the command without blocking.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop_first(&self.src, &self.op()) {
+            Ok(served) => self.reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for BLMove {
+    fn keys(&self) -> &[Bytes] {
+        &self.src
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::Move { from: self.from, dest: self.dest.clone(), to: self.to }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the moved element.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        match served.and_then(|mut served| served.elems.pop()) {
+            Some(elem) => RespType::BulkString(elem),
+            None => RespType::NullBulkString,
+        }
+    }
+}
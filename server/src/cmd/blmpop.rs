@@ -0,0 +1,105 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_list_end, parse_option, parse_timeout};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the BLMPOP command.
+#[derive(Debug)]
+pub struct BLMPop {
+    keys: Vec<Bytes>,
+    end: ListEnd,
+    count: usize,
+    timeout: Option<Duration>,
+}
+
+impl BLMPop {
+    /// Creates a new BLMPop instance from the given args.
+    /// Syntax: BLMPOP timeout numkeys key [key ...] LEFT | RIGHT [COUNT count]
+    pub fn with_args(args: Vec<RespType>) -> Result<BLMPop, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity("blmpop"));
+        }
+
+        let timeout = parse_timeout(&args[0])?;
+        let (keys, end, count) = parse_mpop_args(&args[1..], parse_list_end)?;
+
+        Ok(BLMPop { keys, end, count, timeout })
+    }
+
+    /// Executes the command without blocking.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop_first(&self.keys, &self.op()) {
+            Ok(served) => self.reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for BLMPop {
+    fn keys(&self) -> &[Bytes] {
+        &self.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::Pop { end: self.end, count: self.count }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the key and the popped elements.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        match served {
+            Some(Served { key, elems, .. }) => RespType::Array(vec![
+                RespType::BulkString(key),
+                RespType::Array(elems.into_iter().map(RespType::BulkString).collect()),
+            ]),
+            None => RespType::NullArray,
+        }
+    }
+}
+
+/// Parses the args shared by LMPOP and BLMPOP: `numkeys key [key ...] LEFT | RIGHT [COUNT count]`,
+/// or by ZMPOP and BZMPOP with `MIN | MAX` instead, parsed by `parse_end`.
+/// Returns the keys, the end to pop from and the number of elements to pop.
+pub fn parse_mpop_args<T>(
+    args: &[RespType],
+    parse_end: fn(&RespType) -> Result<T, CommandError>,
+) -> Result<(Vec<Bytes>, T, usize), CommandError> {
+    let numkeys = match parse_i64(&args[0]) {
+        Ok(numkeys) if numkeys > 0 => numkeys as usize,
+        _ => return Err(CommandError::Other(String::from("numkeys should be greater than 0"))),
+    };
+    // the keys must be followed by the end of the lists
+    if numkeys >= args.len() - 1 {
+        return Err(CommandError::Syntax);
+    }
+
+    let keys = args[1..=numkeys].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+    let end = parse_end(&args[numkeys + 1])?;
+
+    let mut count = None;
+    let mut i = numkeys + 2;
+    while i < args.len() {
+        match parse_option(&args[i])?.as_str() {
+            "count" if count.is_none() && i + 1 < args.len() => {
+                count = match parse_i64(&args[i + 1]) {
+                    Ok(count) if count > 0 => Some(count as usize),
+                    _ => return Err(CommandError::Other(String::from(
+                        "count should be greater than 0",
+                    ))),
+                };
+                i += 2;
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    Ok((keys, end, count.unwrap_or(1)))
+}
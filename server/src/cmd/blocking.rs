@@ -0,0 +1,29 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+
+/// A command which blocks the client until one of its keys holds a value to pop from, a
+/// list or a sorted set, or a stream with new entries, or the timeout elapses. Inside a
+/// transaction it doesn't block, and replies as if it timed out when no key is ready.
+pub trait Blocking: Sync {
+    /// The keys to wait for, in the order they are checked.
+    fn keys(&self) -> &[Bytes];
+
+    /// The operation run on the first key holding a value to pop from.
+    fn op(&self) -> BlockingOp;
+
+    /// How long to wait, `None` to wait forever.
+    fn timeout(&self) -> Option<Duration>;
+
+    /// The reply given the elements served, or `None` if the timeout elapsed.
+    fn reply(&self, served: Option<Served>) -> RespType;
+
+    /// The reply if the command doesn't have to block, checked before running the
+    /// operation. Used by XREAD and XREADGROUP, which reply with all the streams having
+    /// new entries rather than the first one. By default the operation decides.
+    fn ready(&self, _db: &DB) -> Option<RespType> {
+        None
+    }
+}
@@ -0,0 +1,69 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_timeout};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the BLPOP and BRPOP commands.
+#[derive(Debug)]
+pub struct BLPop {
+    keys: Vec<Bytes>,
+    end: ListEnd,
+    timeout: Option<Duration>,
+}
+
+impl BLPop {
+    /// Creates a new BLPop instance popping from the given end of the lists.
+    /// Syntax: BLPOP key [key ...] timeout
+    pub fn with_args(end: ListEnd, args: Vec<RespType>) -> Result<BLPop, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(match end {
+                ListEnd::Left => "blpop",
+                ListEnd::Right => "brpop",
+            }));
+        }
+
+        let (timeout, keys) = args.split_last().unwrap();
+        let keys = keys.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+        let timeout = parse_timeout(timeout)?;
+
+        Ok(BLPop { keys, end, timeout })
+    }
+
+    /// Executes the command without blocking.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop_first(&self.keys, &self.op()) {
+            Ok(served) => self.reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for BLPop {
+    fn keys(&self) -> &[Bytes] {
+        &self.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::Pop { end: self.end, count: 1 }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the key and the popped element.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        match served {
+            Some(Served { key, mut elems, .. }) => match elems.pop() {
+                Some(elem) => RespType::Array(vec![RespType::BulkString(key), RespType::BulkString(elem)]),
+                None => RespType::NullArray,
+            },
+            None => RespType::NullArray,
+        }
+    }
+}
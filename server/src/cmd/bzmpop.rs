@@ -0,0 +1,62 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blmpop::parse_mpop_args;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::zmpop;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_timeout, parse_zset_end};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZSetEnd;
+
+/// Represents the BZMPOP command.
+#[derive(Debug)]
+pub struct BZMPop {
+    keys: Vec<Bytes>,
+    end: ZSetEnd,
+    count: usize,
+    timeout: Option<Duration>,
+}
+
+impl BZMPop {
+    /// Creates a new BZMPop instance from the given args.
+    /// Syntax: BZMPOP timeout numkeys key [key ...] MIN | MAX [COUNT count]
+    pub fn with_args(args: Vec<RespType>) -> Result<BZMPop, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity("bzmpop"));
+        }
+
+        let timeout = parse_timeout(&args[0])?;
+        let (keys, end, count) = parse_mpop_args(&args[1..], parse_zset_end)?;
+
+        Ok(BZMPop { keys, end, count, timeout })
+    }
+
+    /// Executes the command without blocking.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop_first(&self.keys, &self.op()) {
+            Ok(served) => self.reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for BZMPop {
+    fn keys(&self) -> &[Bytes] {
+        &self.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::ZPop { end: self.end, count: self.count }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the key and the popped members with their scores.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        zmpop::reply(served)
+    }
+}
@@ -0,0 +1,73 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_timeout};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZSetEnd;
+
+/// Represents the BZPOPMIN and BZPOPMAX commands.
+#[derive(Debug)]
+pub struct BZPopMin {
+    keys: Vec<Bytes>,
+    end: ZSetEnd,
+    timeout: Option<Duration>,
+}
+
+impl BZPopMin {
+    /// Creates a new BZPopMin instance popping from the given end of the sorted sets.
+    /// Syntax: BZPOPMIN key [key ...] timeout
+    pub fn with_args(end: ZSetEnd, args: Vec<RespType>) -> Result<BZPopMin, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(match end {
+                ZSetEnd::Min => "bzpopmin",
+                ZSetEnd::Max => "bzpopmax",
+            }));
+        }
+
+        let (timeout, keys) = args.split_last().unwrap();
+        let keys = keys.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+        let timeout = parse_timeout(timeout)?;
+
+        Ok(BZPopMin { keys, end, timeout })
+    }
+
+    /// Executes the command without blocking.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop_first(&self.keys, &self.op()) {
+            Ok(served) => self.reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for BZPopMin {
+    fn keys(&self) -> &[Bytes] {
+        &self.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::ZPop { end: self.end, count: 1 }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the key, the popped member and its score.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        match served {
+            Some(Served { key, mut elems, mut scores, .. }) => match (elems.pop(), scores.pop()) {
+                (Some(member), Some(score)) => RespType::Array(vec![
+                    RespType::BulkString(key),
+                    RespType::BulkString(member),
+                    RespType::Double(score),
+                ]),
+                _ => RespType::NullArray,
+            },
+            None => RespType::NullArray,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::expire::ExpireCondition;
+use crate::utils::now_ms;
+
+/// Represents the EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT commands,
+/// which only differ in the unit and the reference of the given time.
+#[derive(Debug)]
+pub struct Expire {
+    key: Bytes,
+    time: ExpireTime,
+    cond: ExpireCondition,
+}
+
+/// A time to live given to a command. It's converted to a deadline only when the
+/// command is executed, so that commands queued by MULTI expire relative to EXEC.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpireTime {
+    kind: ExpireKind,
+    time: i64,
+}
+
+/// The variants of the EXPIRE command.
+#[derive(Debug, Clone, Copy)]
+pub enum ExpireKind {
+    /// EXPIRE key seconds
+    Expire,
+    /// PEXPIRE key milliseconds
+    PExpire,
+    /// EXPIREAT key unix-time-seconds
+    ExpireAt,
+    /// PEXPIREAT key unix-time-milliseconds
+    PExpireAt,
+}
+
+impl ExpireKind {
+    /// The lowercase name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExpireKind::Expire => "expire",
+            ExpireKind::PExpire => "pexpire",
+            ExpireKind::ExpireAt => "expireat",
+            ExpireKind::PExpireAt => "pexpireat",
+        }
+    }
+
+    /// Converts the time given to the command into a unix time in milliseconds,
+    /// relative to `now` for the relative variants.
+    /// Returns `None` if the conversion overflows.
+    fn deadline(&self, time: i64, now: u64) -> Option<u64> {
+        let ms = match self {
+            ExpireKind::Expire | ExpireKind::ExpireAt => time.checked_mul(1000)?,
+            ExpireKind::PExpire | ExpireKind::PExpireAt => time,
+        };
+        let ms = match self {
+            ExpireKind::Expire | ExpireKind::PExpire => ms.checked_add(now as i64)?,
+            ExpireKind::ExpireAt | ExpireKind::PExpireAt => ms,
+        };
+        // a deadline in the past expires the key immediately
+        Some(ms.max(0) as u64)
+    }
+}
+
+impl ExpireTime {
+    /// Creates a new time to live, checking that it can be converted to a deadline.
+    /// `cmd` is the name of the command reported in the error.
+    pub fn new(kind: ExpireKind, time: i64, cmd: &str) -> Result<ExpireTime, CommandError> {
+        match kind.deadline(time, now_ms()) {
+            Some(_) => Ok(ExpireTime { kind, time }),
+            None => Err(CommandError::Other(format!("invalid expire time in '{}' command", cmd))),
+        }
+    }
+
+    /// Returns the unix time in milliseconds at which the time to live elapses.
+    pub fn deadline(&self) -> u64 {
+        self.kind.deadline(self.time, now_ms()).unwrap_or(u64::MAX)
+    }
+}
+
+impl Expire {
+    /// Creates a new Expire instance from the given args.
+    /// Syntax: EXPIRE key time [NX | XX | GT | LT]
+    pub fn with_args(kind: ExpireKind, args: Vec<RespType>) -> Result<Expire, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(kind.name()));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let time = ExpireTime::new(kind, parse_i64(&args[1])?, kind.name())?;
+
+        let cond = parse_condition(&args[2..])?;
+
+        Ok(Expire { key, time, cond })
+    }
+
+    /// Executes the EXPIRE command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.expire_at(&self.key, self.time.deadline(), self.cond) {
+            Ok(updated) => RespType::Integer(updated as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the NX, XX, GT and LT flags shared by the commands updating TTLs.
+pub fn parse_condition(args: &[RespType]) -> Result<ExpireCondition, CommandError> {
+    let mut cond = ExpireCondition::default();
+    for arg in args {
+        match parse_option(arg)?.as_str() {
+            "nx" => cond.nx = true,
+            "xx" => cond.xx = true,
+            "gt" => cond.gt = true,
+            "lt" => cond.lt = true,
+            opt => return Err(CommandError::Other(format!("Unsupported option {}", opt))),
+        }
+    }
+
+    if cond.nx && (cond.xx || cond.gt || cond.lt) {
+        return Err(CommandError::Other(String::from(
+            "NX and XX, GT or LT options at the same time are not compatible",
+        )));
+    }
+    if cond.gt && cond.lt {
+        return Err(CommandError::Other(String::from(
+            "GT and LT options at the same time are not compatible",
+        )));
+    }
+    Ok(cond)
+}
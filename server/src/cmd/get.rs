@@ -1,4 +1,6 @@
+use bytes::Bytes;
 use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
 use crate::resp::types::RespType;
 use crate::storage::db::DB;
 
@@ -6,7 +8,7 @@ use crate::storage::db::DB;
 #[derive(Debug)]
 pub struct Get {
     /// The key to get the value for.
-    key: String,
+    key: Bytes,
 }
 
 impl Get {
@@ -14,31 +16,21 @@ impl Get {
     /// Create a new instance of the get command from the given args.
     pub fn with_args(args: Vec<RespType>) -> Result<Self, CommandError> {
         if args.len() != 1 {
-            return Err(CommandError::Other(String::from(
-                "Wrong number of arguments specified for 'GET' command",
-            )));
+            return Err(CommandError::WrongArity("get"));
         }
 
         // parse key
-        let key = &args[0];
-        let key = match key {
-            RespType::BulkString(key) => key,
-            _ => return Err(CommandError::Other(String::from(
-                "Invalid argument.Key must be a bulk string",
-            ))),
-        };
+        let key = parse_bytes(&args[0])?;
 
-        Ok(Self {
-            key: key.to_string(),
-        })
+        Ok(Self { key })
     }
 
     pub fn apply(&self, db: &DB) -> RespType {
-        match db.get(self.key.as_str()) {
-            Ok(Some(value)) => RespType::BulkString(value.to_string()),
+        match db.get(&self.key) {
+            Ok(Some(value)) => RespType::BulkString(value),
             Ok(None) => RespType::NullBulkString,
             Err(e) => RespType::SimpleError(format!("{}", e)),
         }
     }
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::setbit::parse_bit_offset;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the GETBIT command.
+#[derive(Debug)]
+pub struct GetBit {
+    key: Bytes,
+    offset: u64,
+}
+
+impl GetBit {
+    /// Creates a new GetBit instance from the given args.
+    /// Syntax: GETBIT key offset
+    pub fn with_args(args: Vec<RespType>) -> Result<GetBit, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("getbit"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let offset = parse_bit_offset(&args[1])?;
+
+        Ok(GetBit { key, offset })
+    }
+
+    /// Executes the GETBIT command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.getbit(&self.key, self.offset) {
+            Ok(bit) => RespType::Integer(bit as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the GETDEL command.
+#[derive(Debug)]
+pub struct GetDel {
+    key: Bytes,
+}
+
+impl GetDel {
+    /// Creates a new GetDel instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<GetDel, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("getdel"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(GetDel { key })
+    }
+
+    /// Executes the GETDEL command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.getdel(&self.key) {
+            Ok(Some(value)) => RespType::BulkString(value),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::expire::{ExpireKind, ExpireTime};
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::string::GetExExpiry;
+
+/// Represents the GETEX command.
+#[derive(Debug)]
+pub struct GetEx {
+    key: Bytes,
+    /// The new TTL of the key (EX, PX, EXAT or PXAT).
+    expire: Option<ExpireTime>,
+    /// Remove the TTL of the key (PERSIST).
+    persist: bool,
+}
+
+impl GetEx {
+    /// Creates a new GetEx instance from the given args.
+    /// Syntax: GETEX key [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+    ///   PXAT unix-time-milliseconds | PERSIST]
+    pub fn with_args(args: Vec<RespType>) -> Result<GetEx, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("getex"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let mut getex = GetEx { key, expire: None, persist: false };
+
+        let mut i = 1;
+        while i < args.len() {
+            let opt = parse_option(&args[i])?;
+            let expire_kind = match opt.as_str() {
+                "ex" => Some(ExpireKind::Expire),
+                "px" => Some(ExpireKind::PExpire),
+                "exat" => Some(ExpireKind::ExpireAt),
+                "pxat" => Some(ExpireKind::PExpireAt),
+                _ => None,
+            };
+            let unset = getex.expire.is_none() && !getex.persist;
+
+            match (opt.as_str(), expire_kind) {
+                ("persist", _) if unset => getex.persist = true,
+                (_, Some(kind)) if unset && i + 1 < args.len() => {
+                    let time = parse_i64(&args[i + 1])?;
+                    if time <= 0 {
+                        return Err(CommandError::Other(String::from(
+                            "invalid expire time in 'getex' command",
+                        )));
+                    }
+                    getex.expire = Some(ExpireTime::new(kind, time, "getex")?);
+                    i += 1;
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+            i += 1;
+        }
+
+        Ok(getex)
+    }
+
+    /// Executes the GETEX command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let expiry = match (self.expire, self.persist) {
+            (Some(expire), _) => GetExExpiry::At(expire.deadline()),
+            (None, true) => GetExExpiry::Persist,
+            (None, false) => GetExExpiry::Keep,
+        };
+
+        match db.getex(&self.key, expiry) {
+            Ok(Some(value)) => RespType::BulkString(value),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the GETRANGE command.
+#[derive(Debug)]
+pub struct GetRange {
+    key: Bytes,
+    start: i64,
+    end: i64,
+}
+
+impl GetRange {
+    /// Creates a new GetRange instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<GetRange, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("getrange"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let start = parse_i64(&args[1])?;
+        let end = parse_i64(&args[2])?;
+
+        Ok(GetRange { key, start, end })
+    }
+
+    /// Executes the GETRANGE command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.getrange(&self.key, self.start, self.end) {
+            Ok(s) => RespType::BulkString(s),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
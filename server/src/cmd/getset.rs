@@ -0,0 +1,40 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::string::SetOptions;
+
+/// Represents the GETSET command, the legacy form of `SET key value GET`.
+#[derive(Debug)]
+pub struct GetSet {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl GetSet {
+    /// Creates a new GetSet instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<GetSet, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("getset"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let value = parse_bytes(&args[1])?;
+
+        Ok(GetSet { key, value })
+    }
+
+    /// Executes the GETSET command. The TTL of the key is discarded.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let opts = SetOptions { get: true, ..SetOptions::default() };
+
+        match db.set_with_options(self.key.clone(), self.value.clone(), opts) {
+            Ok(res) => match res.previous {
+                Some(previous) => RespType::BulkString(previous),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
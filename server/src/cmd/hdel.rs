@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HDEL command.
+#[derive(Debug)]
+pub struct HDel {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl HDel {
+    /// Creates a new HDel instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HDel, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("hdel"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let fields = parse_values(args)?;
+
+        Ok(HDel { key, fields })
+    }
+
+    /// Executes the HDEL command, replying with the number of deleted fields.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hdel(&self.key, &self.fields) {
+            Ok(deleted) => RespType::Integer(deleted as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_option};
+use crate::resp::types::RespType;
+use crate::resp::ProtocolVersion;
+
+/// Represents the HELLO command.
+#[derive(Debug)]
+pub struct Hello {
+    /// The protocol version requested by the client. `None` keeps the current one.
+    protocol: Option<ProtocolVersion>,
+    /// The connection name passed with SETNAME.
+    client_name: Option<Bytes>,
+}
+
+impl Hello {
+    /// Creates a new HELLO instance from the given args.
+    /// Syntax: HELLO [protover [AUTH username password] [SETNAME clientname]]
+    pub fn with_args(args: Vec<RespType>) -> Result<Hello, CommandError> {
+        if args.is_empty() {
+            return Ok(Hello { protocol: None, client_name: None });
+        }
+
+        let protocol = parse_bytes(&args[0])?;
+        let protocol = match crate::utils::parse_i64(&protocol) {
+            Some(protocol) => protocol,
+            None => return Err(CommandError::Other(String::from(
+                "Protocol version is not an integer or out of range",
+            ))),
+        };
+        let protocol = ProtocolVersion::from_number(protocol).ok_or(CommandError::NoProto)?;
+
+        let mut client_name = None;
+        let mut i = 1;
+        while i < args.len() {
+            let opt = parse_option(&args[i])?;
+
+            match opt.as_str() {
+                "auth" if i + 2 < args.len() => {
+                    // tiny-redis has no ACL, only the password-less default user exists
+                    if parse_bytes(&args[i + 1])?.as_ref() != b"default" {
+                        return Err(CommandError::WrongPass);
+                    }
+                    i += 3;
+                }
+                "setname" if i + 1 < args.len() => {
+                    let name = parse_bytes(&args[i + 1])?;
+                    if name.iter().any(|c| *c <= b' ' || *c > b'~') {
+                        return Err(CommandError::Other(String::from(
+                            "Client names cannot contain spaces, newlines or special characters.",
+                        )));
+                    }
+                    client_name = Some(name);
+                    i += 2;
+                }
+                _ => return Err(CommandError::Other(format!(
+                    "Syntax error in HELLO option '{}'",
+                    opt
+                ))),
+            }
+        }
+
+        Ok(Hello { protocol: Some(protocol), client_name })
+    }
+
+    /// Returns the protocol version requested by the client, if any.
+    pub fn protocol(&self) -> Option<ProtocolVersion> {
+        self.protocol
+    }
+
+    /// Returns the connection name passed with SETNAME, if any.
+    pub fn client_name(&self) -> Option<&Bytes> {
+        self.client_name.as_ref()
+    }
+
+    /// Executes the HELLO command, replying with the server and connection info.
+    pub fn apply(&self, client_id: u64, protocol: ProtocolVersion) -> RespType {
+        let field = |s: &'static str| RespType::BulkString(Bytes::from_static(s.as_bytes()));
+
+        RespType::Map(vec![
+            (field("server"), field("tiny-redis")),
+            (field("version"), field(env!("CARGO_PKG_VERSION"))),
+            (field("proto"), RespType::Integer(protocol.number())),
+            (field("id"), RespType::Integer(client_id as i64)),
+            (field("mode"), field("standalone")),
+            (field("role"), field("master")),
+            (field("modules"), RespType::Array(vec![])),
+        ])
+    }
+}
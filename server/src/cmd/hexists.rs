@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HEXISTS command.
+#[derive(Debug)]
+pub struct HExists {
+    key: Bytes,
+    field: [Bytes; 1],
+}
+
+impl HExists {
+    /// Creates a new HExists instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HExists, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("hexists"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+
+        Ok(HExists { key, field: [field] })
+    }
+
+    /// Executes the HEXISTS command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hmget(&self.key, &self.field) {
+            Ok(values) => RespType::Integer(values[0].is_some() as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::expire::{parse_condition, ExpireKind, ExpireTime};
+use crate::cmd::utils::{parse_bytes, parse_hash_fields, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::expire::ExpireCondition;
+use crate::storage::hash::FieldExpiry;
+
+/// Represents the HEXPIRE, HPEXPIRE, HEXPIREAT and HPEXPIREAT commands,
+/// which set the TTL of hash fields.
+#[derive(Debug)]
+pub struct HExpire {
+    key: Bytes,
+    time: ExpireTime,
+    cond: ExpireCondition,
+    fields: Vec<Bytes>,
+}
+
+impl HExpire {
+    /// Creates a new HExpire instance from the given args.
+    /// Syntax: HEXPIRE key time [NX | XX | GT | LT] FIELDS numfields field [field ...]
+    pub fn with_args(kind: ExpireKind, args: Vec<RespType>) -> Result<HExpire, CommandError> {
+        let name = match kind {
+            ExpireKind::Expire => "hexpire",
+            ExpireKind::PExpire => "hpexpire",
+            ExpireKind::ExpireAt => "hexpireat",
+            ExpireKind::PExpireAt => "hpexpireat",
+        };
+        if args.len() < 5 {
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let time = ExpireTime::new(kind, parse_i64(&args[1])?, name)?;
+
+        // the condition is optional, and comes before FIELDS
+        let (cond, fields) = match parse_option(&args[2])?.as_str() {
+            "fields" => (ExpireCondition::default(), &args[2..]),
+            _ => (parse_condition(&args[2..3])?, &args[3..]),
+        };
+        let fields = parse_hash_fields(fields)?;
+
+        Ok(HExpire { key, time, cond, fields })
+    }
+
+    /// Executes the command, replying for each field -2 if it doesn't exist, 0 if the
+    /// condition isn't met, 1 if the TTL was set and 2 if the field was deleted.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hexpire_at(&self.key, &self.fields, self.time.deadline(), self.cond) {
+            Ok(results) => RespType::Array(
+                results
+                    .into_iter()
+                    .map(|result| RespType::Integer(match result {
+                        FieldExpiry::NoField => -2,
+                        FieldExpiry::NotMet => 0,
+                        FieldExpiry::Updated => 1,
+                        FieldExpiry::Deleted => 2,
+                    }))
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HGET command.
+#[derive(Debug)]
+pub struct HGet {
+    key: Bytes,
+    field: [Bytes; 1],
+}
+
+impl HGet {
+    /// Creates a new HGet instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HGet, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("hget"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+
+        Ok(HGet { key, field: [field] })
+    }
+
+    /// Executes the HGET command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hmget(&self.key, &self.field) {
+            Ok(mut values) => match values.pop().flatten() {
+                Some(value) => RespType::BulkString(value),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
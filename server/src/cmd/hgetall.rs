@@ -0,0 +1,55 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HGETALL, HKEYS and HVALS commands.
+#[derive(Debug)]
+pub struct HGetAll {
+    key: Bytes,
+    kind: HGetAllKind,
+}
+
+/// The variants of the HGETALL command.
+#[derive(Debug, Clone, Copy)]
+pub enum HGetAllKind {
+    /// HGETALL key, the fields and their values.
+    All,
+    /// HKEYS key, the fields only.
+    Keys,
+    /// HVALS key, the values only.
+    Vals,
+}
+
+impl HGetAll {
+    /// Creates a new HGetAll instance from the given args.
+    pub fn with_args(kind: HGetAllKind, args: Vec<RespType>) -> Result<HGetAll, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity(match kind {
+                HGetAllKind::All => "hgetall",
+                HGetAllKind::Keys => "hkeys",
+                HGetAllKind::Vals => "hvals",
+            }));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(HGetAll { key, kind })
+    }
+
+    /// Executes the command. HGETALL replies with a map, HKEYS and HVALS with an array.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let pairs = match db.hgetall(&self.key) {
+            Ok(pairs) => pairs,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+
+        let pairs = pairs.into_iter().map(|(field, value)| (RespType::BulkString(field), RespType::BulkString(value)));
+        match self.kind {
+            HGetAllKind::All => RespType::Map(pairs.collect()),
+            HGetAllKind::Keys => RespType::Array(pairs.map(|(field, _)| field).collect()),
+            HGetAllKind::Vals => RespType::Array(pairs.map(|(_, value)| value).collect()),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_f64, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HINCRBY command.
+#[derive(Debug)]
+pub struct HIncrBy {
+    key: Bytes,
+    field: Bytes,
+    delta: i64,
+}
+
+/// Represents the HINCRBYFLOAT command.
+#[derive(Debug)]
+pub struct HIncrByFloat {
+    key: Bytes,
+    field: Bytes,
+    delta: f64,
+}
+
+impl HIncrBy {
+    /// Creates a new HIncrBy instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HIncrBy, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("hincrby"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+        let delta = parse_i64(&args[2])?;
+
+        Ok(HIncrBy { key, field, delta })
+    }
+
+    /// Executes the HINCRBY command, replying with the new value.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hincrby(self.key.clone(), self.field.clone(), self.delta) {
+            Ok(value) => RespType::Integer(value),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl HIncrByFloat {
+    /// Creates a new HIncrByFloat instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HIncrByFloat, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("hincrbyfloat"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+        let delta = parse_f64(&args[2])?;
+
+        Ok(HIncrByFloat { key, field, delta })
+    }
+
+    /// Executes the HINCRBYFLOAT command, replying with the new value as a bulk string.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hincrbyfloat(self.key.clone(), self.field.clone(), self.delta) {
+            Ok(value) => RespType::BulkString(value),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
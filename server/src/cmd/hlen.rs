@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HLEN command.
+#[derive(Debug)]
+pub struct HLen {
+    key: Bytes,
+}
+
+impl HLen {
+    /// Creates a new HLen instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HLen, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("hlen"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(HLen { key })
+    }
+
+    /// Executes the HLEN command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hlen(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
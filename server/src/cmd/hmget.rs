@@ -0,0 +1,42 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HMGET command.
+#[derive(Debug)]
+pub struct HMGet {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl HMGet {
+    /// Creates a new HMGet instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HMGet, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("hmget"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let fields = parse_values(args)?;
+
+        Ok(HMGet { key, fields })
+    }
+
+    /// Executes the HMGET command. Missing fields are replied as nil.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hmget(&self.key, &self.fields) {
+            Ok(values) => RespType::Array(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        Some(value) => RespType::BulkString(value),
+                        None => RespType::NullBulkString,
+                    })
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
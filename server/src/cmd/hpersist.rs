@@ -0,0 +1,45 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_hash_fields};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HPERSIST command.
+#[derive(Debug)]
+pub struct HPersist {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl HPersist {
+    /// Creates a new HPersist instance from the given args.
+    /// Syntax: HPERSIST key FIELDS numfields field [field ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<HPersist, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity("hpersist"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let fields = parse_hash_fields(&args[1..])?;
+
+        Ok(HPersist { key, fields })
+    }
+
+    /// Executes the HPERSIST command, replying for each field -2 if it doesn't exist,
+    /// -1 if it has no TTL and 1 if its TTL was removed.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hpersist(&self.key, &self.fields) {
+            Ok(results) => RespType::Array(
+                results
+                    .into_iter()
+                    .map(|result| RespType::Integer(match result {
+                        None => -2,
+                        Some(false) => -1,
+                        Some(true) => 1,
+                    }))
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
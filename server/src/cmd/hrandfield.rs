@@ -0,0 +1,59 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_option, parse_random_count};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HRANDFIELD command.
+#[derive(Debug)]
+pub struct HRandField {
+    key: Bytes,
+    /// The number of fields to return. Without it a single field is replied, not an array.
+    count: Option<i64>,
+    with_values: bool,
+}
+
+impl HRandField {
+    /// Creates a new HRandField instance from the given args.
+    /// Syntax: HRANDFIELD key [count [WITHVALUES]]
+    pub fn with_args(args: Vec<RespType>) -> Result<HRandField, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("hrandfield"));
+        }
+        if args.len() > 3 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = args.get(1).map(parse_random_count).transpose()?;
+        let with_values = match args.get(2) {
+            Some(arg) if parse_option(arg)? == "withvalues" => true,
+            Some(_) => return Err(CommandError::Syntax),
+            None => false,
+        };
+
+        Ok(HRandField { key, count, with_values })
+    }
+
+    /// Executes the HRANDFIELD command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let pairs = match db.hrandfield(&self.key, self.count.unwrap_or(1)) {
+            Ok(pairs) => pairs,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+
+        let mut pairs = pairs.into_iter();
+        match self.count {
+            None => match pairs.next() {
+                Some((field, _)) => RespType::BulkString(field),
+                None => RespType::NullBulkString,
+            },
+            Some(_) if self.with_values => RespType::Pairs(
+                pairs
+                    .map(|(field, value)| (RespType::BulkString(field), RespType::BulkString(value)))
+                    .collect(),
+            ),
+            Some(_) => RespType::Array(pairs.map(|(field, _)| RespType::BulkString(field)).collect()),
+        }
+    }
+}
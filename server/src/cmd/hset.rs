@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HSET command.
+#[derive(Debug)]
+pub struct HSet {
+    key: Bytes,
+    pairs: Vec<(Bytes, Bytes)>,
+}
+
+impl HSet {
+    /// Creates a new HSet instance from the given args.
+    /// Syntax: HSET key field value [field value ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<HSet, CommandError> {
+        if args.len() < 3 || args.len().is_multiple_of(2) {
+            return Err(CommandError::WrongArity("hset"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let pairs = args[1..]
+            .chunks(2)
+            .map(|pair| Ok((parse_bytes(&pair[0])?, parse_bytes(&pair[1])?)))
+            .collect::<Result<_, CommandError>>()?;
+
+        Ok(HSet { key, pairs })
+    }
+
+    /// Executes the HSET command, replying with the number of fields added.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hset(self.key.clone(), self.pairs.clone()) {
+            Ok(added) => RespType::Integer(added as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
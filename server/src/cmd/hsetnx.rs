@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HSETNX command.
+#[derive(Debug)]
+pub struct HSetNx {
+    key: Bytes,
+    field: Bytes,
+    value: Bytes,
+}
+
+impl HSetNx {
+    /// Creates a new HSetNx instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HSetNx, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("hsetnx"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+        let value = parse_bytes(&args[2])?;
+
+        Ok(HSetNx { key, field, value })
+    }
+
+    /// Executes the HSETNX command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hsetnx(self.key.clone(), self.field.clone(), self.value.clone()) {
+            Ok(set) => RespType::Integer(set as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
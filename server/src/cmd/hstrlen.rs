@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HSTRLEN command.
+#[derive(Debug)]
+pub struct HStrLen {
+    key: Bytes,
+    field: [Bytes; 1],
+}
+
+impl HStrLen {
+    /// Creates a new HStrLen instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<HStrLen, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("hstrlen"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let field = parse_bytes(&args[1])?;
+
+        Ok(HStrLen { key, field: [field] })
+    }
+
+    /// Executes the HSTRLEN command. A missing field has a length of zero.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hmget(&self.key, &self.field) {
+            Ok(values) => RespType::Integer(values[0].as_ref().map_or(0, Bytes::len) as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
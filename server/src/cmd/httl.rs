@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::ttl::TtlKind;
+use crate::cmd::utils::{parse_bytes, parse_hash_fields};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the HTTL, HPTTL, HEXPIRETIME and HPEXPIRETIME commands,
+/// which get the TTL of hash fields.
+#[derive(Debug)]
+pub struct HTtl {
+    key: Bytes,
+    kind: TtlKind,
+    fields: Vec<Bytes>,
+}
+
+impl HTtl {
+    /// Creates a new HTtl instance from the given args.
+    /// Syntax: HTTL key FIELDS numfields field [field ...]
+    pub fn with_args(kind: TtlKind, args: Vec<RespType>) -> Result<HTtl, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity(match kind {
+                TtlKind::Ttl => "httl",
+                TtlKind::PTtl => "hpttl",
+                TtlKind::ExpireTime => "hexpiretime",
+                TtlKind::PExpireTime => "hpexpiretime",
+            }));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let fields = parse_hash_fields(&args[1..])?;
+
+        Ok(HTtl { key, kind, fields })
+    }
+
+    /// Executes the command. Replies -2 for each field which doesn't exist
+    /// and -1 for each field without TTL.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.hexpires_at(&self.key, &self.fields) {
+            Ok(deadlines) => RespType::Array(
+                deadlines
+                    .into_iter()
+                    .map(|deadline| RespType::Integer(match deadline {
+                        None => -2,
+                        Some(None) => -1,
+                        Some(Some(deadline)) => self.kind.reply(deadline),
+                    }))
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_f64, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the INCR, DECR, INCRBY and DECRBY commands.
+#[derive(Debug)]
+pub struct Incr {
+    key: Bytes,
+    delta: i64,
+}
+
+/// The variants of the INCR command.
+#[derive(Debug, Clone, Copy)]
+pub enum IncrKind {
+    /// INCR key
+    Incr,
+    /// DECR key
+    Decr,
+    /// INCRBY key increment
+    IncrBy,
+    /// DECRBY key decrement
+    DecrBy,
+}
+
+impl Incr {
+    /// Creates a new Incr instance from the given args.
+    pub fn with_args(kind: IncrKind, args: Vec<RespType>) -> Result<Incr, CommandError> {
+        let (name, arity) = match kind {
+            IncrKind::Incr => ("incr", 1),
+            IncrKind::Decr => ("decr", 1),
+            IncrKind::IncrBy => ("incrby", 2),
+            IncrKind::DecrBy => ("decrby", 2),
+        };
+        if args.len() != arity {
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        let delta = match kind {
+            IncrKind::Incr => 1,
+            IncrKind::Decr => -1,
+            IncrKind::IncrBy => parse_i64(&args[1])?,
+            IncrKind::DecrBy => parse_i64(&args[1])?
+                .checked_neg()
+                .ok_or_else(|| CommandError::Other(String::from("decrement would overflow")))?,
+        };
+
+        Ok(Incr { key, delta })
+    }
+
+    /// Executes the command, replying with the new value.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.incr_by(self.key.clone(), self.delta) {
+            Ok(value) => RespType::Integer(value),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Represents the INCRBYFLOAT command.
+#[derive(Debug)]
+pub struct IncrByFloat {
+    key: Bytes,
+    delta: f64,
+}
+
+impl IncrByFloat {
+    /// Creates a new IncrByFloat instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<IncrByFloat, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("incrbyfloat"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let delta = parse_f64(&args[1])?;
+
+        Ok(IncrByFloat { key, delta })
+    }
+
+    /// Executes the command, replying with the new value as a bulk string.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.incr_by_float(self.key.clone(), self.delta) {
+            Ok(value) => RespType::BulkString(value),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LINDEX command.
+#[derive(Debug)]
+pub struct LIndex {
+    key: Bytes,
+    index: i64,
+}
+
+impl LIndex {
+    /// Creates a new LIndex instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<LIndex, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("lindex"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let index = parse_i64(&args[1])?;
+
+        Ok(LIndex { key, index })
+    }
+
+    /// Executes the LINDEX command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.lindex(&self.key, self.index) {
+            Ok(Some(elem)) => RespType::BulkString(elem),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
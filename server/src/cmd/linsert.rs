@@ -0,0 +1,44 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LINSERT command.
+#[derive(Debug)]
+pub struct LInsert {
+    key: Bytes,
+    /// Insert before the pivot (BEFORE) rather than after it (AFTER).
+    before: bool,
+    pivot: Bytes,
+    value: Bytes,
+}
+
+impl LInsert {
+    /// Creates a new LInsert instance from the given args.
+    /// Syntax: LINSERT key BEFORE | AFTER pivot element
+    pub fn with_args(args: Vec<RespType>) -> Result<LInsert, CommandError> {
+        if args.len() != 4 {
+            return Err(CommandError::WrongArity("linsert"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let before = match parse_option(&args[1])?.as_str() {
+            "before" => true,
+            "after" => false,
+            _ => return Err(CommandError::Syntax),
+        };
+        let pivot = parse_bytes(&args[2])?;
+        let value = parse_bytes(&args[3])?;
+
+        Ok(LInsert { key, before, pivot, value })
+    }
+
+    /// Executes the LINSERT command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.linsert(&self.key, self.before, &self.pivot, self.value.clone()) {
+            Ok(len) => RespType::Integer(len),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LLEN command.
+#[derive(Debug)]
+pub struct LLen {
+    key: Bytes,
+}
+
+impl LLen {
+    /// Creates a new LLen instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<LLen, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("llen"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(LLen { key })
+    }
+
+    /// Executes the LLEN command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.llen(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
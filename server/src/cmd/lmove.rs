@@ -0,0 +1,55 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_list_end};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the LMOVE and RPOPLPUSH commands.
+#[derive(Debug)]
+pub struct LMove {
+    src: Bytes,
+    dest: Bytes,
+    from: ListEnd,
+    to: ListEnd,
+}
+
+impl LMove {
+    /// Creates a new LMove instance from the given args.
+    /// Syntax: LMOVE source destination LEFT | RIGHT LEFT | RIGHT
+    pub fn with_args(args: Vec<RespType>) -> Result<LMove, CommandError> {
+        if args.len() != 4 {
+            return Err(CommandError::WrongArity("lmove"));
+        }
+
+        let src = parse_bytes(&args[0])?;
+        let dest = parse_bytes(&args[1])?;
+        let from = parse_list_end(&args[2])?;
+        let to = parse_list_end(&args[3])?;
+
+        Ok(LMove { src, dest, from, to })
+    }
+
+    /// Creates a new LMove instance from the args of RPOPLPUSH,
+    /// which is the same as `LMOVE source destination RIGHT LEFT`.
+    /// Syntax: RPOPLPUSH source destination
+    pub fn with_rpoplpush_args(args: Vec<RespType>) -> Result<LMove, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("rpoplpush"));
+        }
+
+        let src = parse_bytes(&args[0])?;
+        let dest = parse_bytes(&args[1])?;
+
+        Ok(LMove { src, dest, from: ListEnd::Right, to: ListEnd::Left })
+    }
+
+    /// Executes the command, replying with the moved element.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.lmove(&self.src, self.dest.clone(), self.from, self.to) {
+            Ok(Some(elem)) => RespType::BulkString(elem),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use bytes::Bytes;
+use crate::cmd::blmpop::parse_mpop_args;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_list_end;
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the LMPOP command.
+#[derive(Debug)]
+pub struct LMPop {
+    keys: Vec<Bytes>,
+    end: ListEnd,
+    count: usize,
+}
+
+impl LMPop {
+    /// Creates a new LMPop instance from the given args.
+    /// Syntax: LMPOP numkeys key [key ...] LEFT | RIGHT [COUNT count]
+    pub fn with_args(args: Vec<RespType>) -> Result<LMPop, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("lmpop"));
+        }
+
+        let (keys, end, count) = parse_mpop_args(&args, parse_list_end)?;
+
+        Ok(LMPop { keys, end, count })
+    }
+
+    /// Executes the command, popping from the first non empty list.
+    /// Replies with the key and the popped elements.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let op = BlockingOp::Pop { end: self.end, count: self.count };
+
+        match db.pop_first(&self.keys, &op) {
+            Ok(Some(Served { key, elems, .. })) => RespType::Array(vec![
+                RespType::BulkString(key),
+                RespType::Array(elems.into_iter().map(RespType::BulkString).collect()),
+            ]),
+            Ok(None) => RespType::NullArray,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+
+/// Represents the LPOP and RPOP commands.
+#[derive(Debug)]
+pub struct LPop {
+    key: Bytes,
+    end: ListEnd,
+    /// The number of elements to pop. Without it a single element is replied, not an array.
+    count: Option<usize>,
+}
+
+impl LPop {
+    /// Creates a new LPop instance popping from the given end of the list.
+    /// Syntax: LPOP key [count]
+    pub fn with_args(end: ListEnd, args: Vec<RespType>) -> Result<LPop, CommandError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(CommandError::WrongArity(match end {
+                ListEnd::Left => "lpop",
+                ListEnd::Right => "rpop",
+            }));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = match args.get(1) {
+            Some(count) => match parse_i64(count) {
+                Ok(count) if count >= 0 => Some(count as usize),
+                _ => return Err(CommandError::Other(String::from(
+                    "value is out of range, must be positive",
+                ))),
+            },
+            None => None,
+        };
+
+        Ok(LPop { key, end, count })
+    }
+
+    /// Executes the command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pop(&self.key, self.end, self.count.unwrap_or(1)) {
+            Ok(Some(elems)) if self.count.is_some() => {
+                RespType::Array(elems.into_iter().map(RespType::BulkString).collect())
+            }
+            Ok(Some(mut elems)) => match elems.pop() {
+                Some(elem) => RespType::BulkString(elem),
+                None => RespType::NullBulkString,
+            },
+            Ok(None) if self.count.is_some() => RespType::NullArray,
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LPOS command.
+#[derive(Debug)]
+pub struct LPos {
+    key: Bytes,
+    value: Bytes,
+    /// The first match to return, negative to search from the tail (RANK).
+    rank: i64,
+    /// The number of matches to return, zero for all of them (COUNT).
+    /// Without it a single index is replied, not an array.
+    count: Option<usize>,
+    /// The number of elements to compare, zero for all of them (MAXLEN).
+    maxlen: usize,
+}
+
+impl LPos {
+    /// Creates a new LPos instance from the given args.
+    /// Syntax: LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]
+    pub fn with_args(args: Vec<RespType>) -> Result<LPos, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("lpos"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let value = parse_bytes(&args[1])?;
+        let mut lpos = LPos { key, value, rank: 1, count: None, maxlen: 0 };
+
+        let mut i = 2;
+        while i < args.len() {
+            let opt = parse_option(&args[i])?;
+            if !matches!(opt.as_str(), "rank" | "count" | "maxlen") {
+                return Err(CommandError::Syntax);
+            }
+            let arg = match args.get(i + 1) {
+                Some(arg) => parse_i64(arg)?,
+                None => return Err(CommandError::Syntax),
+            };
+
+            match opt.as_str() {
+                "rank" if arg == 0 => return Err(CommandError::Other(String::from(
+                    "RANK can't be zero: use 1 to start from the first match, \
+                     2 from the second ... or use negative to start from the last match",
+                ))),
+                "rank" if arg == i64::MIN => return Err(CommandError::Other(format!(
+                    "value is out of range, value must between {} and {}",
+                    -i64::MAX,
+                    i64::MAX,
+                ))),
+                "rank" => lpos.rank = arg,
+                "count" if arg < 0 => return Err(CommandError::Other(String::from(
+                    "COUNT can't be negative",
+                ))),
+                "count" => lpos.count = Some(arg as usize),
+                "maxlen" if arg < 0 => return Err(CommandError::Other(String::from(
+                    "MAXLEN can't be negative",
+                ))),
+                _ => lpos.maxlen = arg as usize,
+            }
+            i += 2;
+        }
+
+        Ok(lpos)
+    }
+
+    /// Executes the LPOS command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let count = self.count.unwrap_or(1);
+
+        match db.lpos(&self.key, &self.value, self.rank, count, self.maxlen) {
+            Ok(indexes) if self.count.is_some() => RespType::Array(
+                indexes.into_iter().map(|i| RespType::Integer(i as i64)).collect(),
+            ),
+            Ok(indexes) => match indexes.first() {
+                Some(i) => RespType::Integer(*i as i64),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -1,13 +1,14 @@
+use bytes::Bytes;
 use crate::cmd::CommandError;
-use crate::cmd::utils::parse_values;
+use crate::cmd::utils::{parse_bytes, parse_values};
 use crate::resp::types::RespType;
 use crate::storage::db::DB;
 
 /// Represents the LPUSH command
 #[derive(Debug, Clone)]
 pub struct LPush {
-    key: String,
-    values: Vec<String>,
+    key: Bytes,
+    values: Vec<Bytes>,
 }
 
 
@@ -16,16 +17,11 @@ impl LPush {
     /// Creates a new LPush instance from the given args.
     pub fn with_args(args: Vec<RespType>) -> Result<LPush, CommandError> {
         if args.len() < 2 {
-            return Err(CommandError::Other(String::from(
-                "Wrong number of arguments specified for 'LPUSH' command",
-            )));
+            return Err(CommandError::WrongArity("lpush"));
         }
 
         // parse key
-        let key = match &args[0] {
-            RespType::BulkString(s) => s.to_string(),
-            _ => return Err(CommandError::Other(String::from("Invalid key"))),
-        };
+        let key = parse_bytes(&args[0])?;
 
         // parse values
         let values = parse_values(args)?;
@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LREM command.
+#[derive(Debug)]
+pub struct LRem {
+    key: Bytes,
+    count: i64,
+    value: Bytes,
+}
+
+impl LRem {
+    /// Creates a new LRem instance from the given args.
+    /// Syntax: LREM key count element
+    pub fn with_args(args: Vec<RespType>) -> Result<LRem, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("lrem"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = parse_i64(&args[1])?;
+        let value = parse_bytes(&args[2])?;
+
+        Ok(LRem { key, count, value })
+    }
+
+    /// Executes the LREM command, replying with the number of removed elements.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.lrem(&self.key, self.count, &self.value) {
+            Ok(removed) => RespType::Integer(removed as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
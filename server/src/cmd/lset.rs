@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LSET command.
+#[derive(Debug)]
+pub struct LSet {
+    key: Bytes,
+    index: i64,
+    value: Bytes,
+}
+
+impl LSet {
+    /// Creates a new LSet instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<LSet, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("lset"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let index = parse_i64(&args[1])?;
+        let value = parse_bytes(&args[2])?;
+
+        Ok(LSet { key, index, value })
+    }
+
+    /// Executes the LSET command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.lset(&self.key, self.index, self.value.clone()) {
+            Ok(()) => RespType::SimpleString(String::from("OK")),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the LTRIM command.
+#[derive(Debug)]
+pub struct LTrim {
+    key: Bytes,
+    start: i64,
+    stop: i64,
+}
+
+impl LTrim {
+    /// Creates a new LTrim instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<LTrim, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("ltrim"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let start = parse_i64(&args[1])?;
+        let stop = parse_i64(&args[2])?;
+
+        Ok(LTrim { key, start, stop })
+    }
+
+    /// Executes the LTRIM command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.ltrim(&self.key, self.start, self.stop) {
+            Ok(()) => RespType::SimpleString(String::from("OK")),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the MGET command.
+#[derive(Debug)]
+pub struct MGet {
+    keys: Vec<Bytes>,
+}
+
+impl MGet {
+    /// Creates a new MGet instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<MGet, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("mget"));
+        }
+
+        let keys = args.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(MGet { keys })
+    }
+
+    /// Executes the MGET command. Keys which don't hold a string are replied as nil.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.mget(&self.keys) {
+            Ok(values) => RespType::Array(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        Some(value) => RespType::BulkString(value),
+                        None => RespType::NullBulkString,
+                    })
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -1,21 +1,207 @@
+use crate::cmd::append::Append;
+use crate::cmd::bitcount::BitCount;
+use crate::cmd::bitfield::BitField;
+use crate::cmd::bitop::BitOp;
+use crate::cmd::bitpos::BitPos;
+use crate::cmd::blmove::BLMove;
+use crate::cmd::blmpop::BLMPop;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::blpop::BLPop;
+use crate::cmd::bzmpop::BZMPop;
+use crate::cmd::bzpopmin::BZPopMin;
+use crate::cmd::expire::{Expire, ExpireKind};
 use crate::cmd::get::Get;
+use crate::cmd::getbit::GetBit;
+use crate::cmd::getdel::GetDel;
+use crate::cmd::getex::GetEx;
+use crate::cmd::getrange::GetRange;
+use crate::cmd::getset::GetSet;
+use crate::cmd::hdel::HDel;
+use crate::cmd::hello::Hello;
+use crate::cmd::hexists::HExists;
+use crate::cmd::hexpire::HExpire;
+use crate::cmd::hget::HGet;
+use crate::cmd::hgetall::{HGetAll, HGetAllKind};
+use crate::cmd::hincrby::{HIncrBy, HIncrByFloat};
+use crate::cmd::hlen::HLen;
+use crate::cmd::hmget::HMGet;
+use crate::cmd::hpersist::HPersist;
+use crate::cmd::hrandfield::HRandField;
+use crate::cmd::hset::HSet;
+use crate::cmd::hsetnx::HSetNx;
+use crate::cmd::hstrlen::HStrLen;
+use crate::cmd::httl::HTtl;
+use crate::cmd::incr::{Incr, IncrByFloat, IncrKind};
+use crate::cmd::lindex::LIndex;
+use crate::cmd::linsert::LInsert;
+use crate::cmd::llen::LLen;
+use crate::cmd::lmove::LMove;
+use crate::cmd::lmpop::LMPop;
+use crate::cmd::lpop::LPop;
+use crate::cmd::lpos::LPos;
 use crate::cmd::lpush::LPush;
 use crate::cmd::lrange::LRange;
+use crate::cmd::lrem::LRem;
+use crate::cmd::lset::LSet;
+use crate::cmd::ltrim::LTrim;
+use crate::cmd::mget::MGet;
+use crate::cmd::mset::MSet;
+use crate::cmd::persist::Persist;
+use crate::cmd::pfadd::PfAdd;
+use crate::cmd::pfcount::PfCount;
+use crate::cmd::pfmerge::PfMerge;
 use crate::cmd::ping::Ping;
 use crate::cmd::rpush::RPush;
+use crate::cmd::sadd::SAdd;
+use crate::cmd::scard::SCard;
 use crate::cmd::set::Set;
+use crate::cmd::setbit::SetBit;
+use crate::cmd::setex::SetEx;
+use crate::cmd::setnx::SetNx;
+use crate::cmd::setrange::SetRange;
+use crate::cmd::sinter::{SInter, SInterStore};
+use crate::cmd::sintercard::SInterCard;
+use crate::cmd::sismember::{SIsMember, SMIsMember};
+use crate::cmd::smembers::SMembers;
+use crate::cmd::smove::SMove;
+use crate::cmd::spop::SPop;
+use crate::cmd::srandmember::SRandMember;
+use crate::cmd::srem::SRem;
+use crate::cmd::strlen::StrLen;
+use crate::cmd::ttl::{Ttl, TtlKind};
+use crate::cmd::tx::TransactionError;
+use crate::cmd::xack::XAck;
+use crate::cmd::xadd::XAdd;
+use crate::cmd::xautoclaim::XAutoClaim;
+use crate::cmd::xclaim::XClaim;
+use crate::cmd::xdel::XDel;
+use crate::cmd::xgroup::XGroup;
+use crate::cmd::xinfo::XInfo;
+use crate::cmd::xlen::XLen;
+use crate::cmd::xpending::XPending;
+use crate::cmd::xrange::XRange;
+use crate::cmd::xread::{XRead, XReadGroup};
+use crate::cmd::xtrim::XTrim;
+use crate::cmd::zadd::ZAdd;
+use crate::cmd::zcard::ZCard;
+use crate::cmd::zcount::ZCount;
+use crate::cmd::zincrby::ZIncrBy;
+use crate::cmd::zinter::{ZInter, ZInterStore};
+use crate::cmd::zintercard::ZInterCard;
+use crate::cmd::zmpop::ZMPop;
+use crate::cmd::zpopmin::ZPopMin;
+use crate::cmd::zrange::ZRange;
+use crate::cmd::zrangestore::ZRangeStore;
+use crate::cmd::zrank::ZRank;
+use crate::cmd::zrem::ZRem;
+use crate::cmd::zremrange::{ZRemRange, ZRemRangeKind};
+use crate::cmd::zscore::{ZMScore, ZScore};
 use crate::resp::types::RespType;
 use crate::storage::db::DB;
+use crate::storage::list::ListEnd;
+use crate::storage::set::SetOp;
+use crate::storage::sorted_set::ZSetEnd;
 use core::fmt;
 
+mod append;
+mod bitcount;
+mod bitfield;
+mod bitop;
+mod bitpos;
+mod blmove;
+mod blmpop;
+pub mod blocking;
+mod blpop;
+mod bzmpop;
+mod bzpopmin;
+mod expire;
 mod get;
+mod getbit;
+mod getdel;
+mod getex;
+mod getrange;
+mod getset;
+mod hdel;
+pub mod hello;
+mod hexists;
+mod hexpire;
+mod hget;
+mod hgetall;
+mod hincrby;
+mod hlen;
+mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hset;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+mod incr;
+mod lindex;
+mod linsert;
+mod llen;
+mod lmove;
+mod lmpop;
+mod lpop;
+mod lpos;
 mod lpush;
 mod lrange;
+mod lrem;
+mod lset;
+mod ltrim;
+mod mget;
+mod mset;
+mod persist;
+mod pfadd;
+mod pfcount;
+mod pfmerge;
 pub mod ping;
 mod rpush;
+mod sadd;
+mod scard;
 mod set;
+mod setbit;
+mod setex;
+mod setnx;
+mod setrange;
+mod sinter;
+mod sintercard;
+mod sismember;
+mod smembers;
+mod smove;
+mod spop;
+mod srandmember;
+mod srem;
+mod strlen;
+mod ttl;
 pub mod tx;
 mod utils;
+mod xack;
+mod xadd;
+mod xautoclaim;
+mod xclaim;
+mod xdel;
+mod xgroup;
+mod xinfo;
+mod xlen;
+mod xpending;
+mod xrange;
+mod xread;
+mod xtrim;
+mod zadd;
+mod zcard;
+mod zcount;
+mod zincrby;
+mod zinter;
+mod zintercard;
+mod zmpop;
+mod zpopmin;
+mod zrange;
+mod zrangestore;
+mod zrank;
+mod zrem;
+mod zremrange;
+mod zscore;
 
 /// Represents a command.
 #[derive(Debug)]
@@ -38,18 +224,212 @@ pub enum Command {
     Exec,
     /// The Discard command.
     Discard,
+    /// The HELLO command.
+    Hello(Hello),
+    /// The EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT commands.
+    Expire(Expire),
+    /// The TTL, PTTL, EXPIRETIME and PEXPIRETIME commands.
+    Ttl(Ttl),
+    /// The PERSIST command.
+    Persist(Persist),
+    /// The INCR, DECR, INCRBY and DECRBY commands.
+    Incr(Incr),
+    /// The INCRBYFLOAT command.
+    IncrByFloat(IncrByFloat),
+    /// The APPEND command.
+    Append(Append),
+    /// The STRLEN command.
+    StrLen(StrLen),
+    /// The GETRANGE command.
+    GetRange(GetRange),
+    /// The SETRANGE command.
+    SetRange(SetRange),
+    /// The MGET command.
+    MGet(MGet),
+    /// The MSET and MSETNX commands.
+    MSet(MSet),
+    /// The GETSET command.
+    GetSet(GetSet),
+    /// The GETDEL command.
+    GetDel(GetDel),
+    /// The GETEX command.
+    GetEx(GetEx),
+    /// The SETNX command.
+    SetNx(SetNx),
+    /// The SETEX and PSETEX commands.
+    SetEx(SetEx),
+    /// The SETBIT command.
+    SetBit(SetBit),
+    /// The GETBIT command.
+    GetBit(GetBit),
+    /// The BITCOUNT command.
+    BitCount(BitCount),
+    /// The BITPOS command.
+    BitPos(BitPos),
+    /// The BITOP command.
+    BitOp(BitOp),
+    /// The BITFIELD and BITFIELD_RO commands.
+    BitField(BitField),
+    /// The LPOP and RPOP commands.
+    LPop(LPop),
+    /// The LLEN command.
+    LLen(LLen),
+    /// The LINDEX command.
+    LIndex(LIndex),
+    /// The LSET command.
+    LSet(LSet),
+    /// The LINSERT command.
+    LInsert(LInsert),
+    /// The LREM command.
+    LRem(LRem),
+    /// The LTRIM command.
+    LTrim(LTrim),
+    /// The LPOS command.
+    LPos(LPos),
+    /// The BLPOP and BRPOP commands.
+    BLPop(BLPop),
+    /// The BLMOVE command.
+    BLMove(BLMove),
+    /// The BLMPOP command.
+    BLMPop(BLMPop),
+    /// The LMOVE and RPOPLPUSH commands.
+    LMove(LMove),
+    /// The LMPOP command.
+    LMPop(LMPop),
+    /// The HSET command.
+    HSet(HSet),
+    /// The HSETNX command.
+    HSetNx(HSetNx),
+    /// The HGET command.
+    HGet(HGet),
+    /// The HMGET command.
+    HMGet(HMGet),
+    /// The HDEL command.
+    HDel(HDel),
+    /// The HEXISTS command.
+    HExists(HExists),
+    /// The HLEN command.
+    HLen(HLen),
+    /// The HGETALL, HKEYS and HVALS commands.
+    HGetAll(HGetAll),
+    /// The HINCRBY command.
+    HIncrBy(HIncrBy),
+    /// The HINCRBYFLOAT command.
+    HIncrByFloat(HIncrByFloat),
+    /// The HSTRLEN command.
+    HStrLen(HStrLen),
+    /// The HRANDFIELD command.
+    HRandField(HRandField),
+    /// The HEXPIRE, HPEXPIRE, HEXPIREAT and HPEXPIREAT commands.
+    HExpire(HExpire),
+    /// The HTTL, HPTTL, HEXPIRETIME and HPEXPIRETIME commands.
+    HTtl(HTtl),
+    /// The HPERSIST command.
+    HPersist(HPersist),
+    /// The SADD command.
+    SAdd(SAdd),
+    /// The SREM command.
+    SRem(SRem),
+    /// The SMEMBERS command.
+    SMembers(SMembers),
+    /// The SISMEMBER command.
+    SIsMember(SIsMember),
+    /// The SMISMEMBER command.
+    SMIsMember(SMIsMember),
+    /// The SCARD command.
+    SCard(SCard),
+    /// The SPOP command.
+    SPop(SPop),
+    /// The SRANDMEMBER command.
+    SRandMember(SRandMember),
+    /// The SMOVE command.
+    SMove(SMove),
+    /// The SINTER, SUNION and SDIFF commands.
+    SInter(SInter),
+    /// The SINTERSTORE, SUNIONSTORE and SDIFFSTORE commands.
+    SInterStore(SInterStore),
+    /// The SINTERCARD command.
+    SInterCard(SInterCard),
+    /// The ZADD command.
+    ZAdd(ZAdd),
+    /// The ZINCRBY command.
+    ZIncrBy(ZIncrBy),
+    /// The ZSCORE command.
+    ZScore(ZScore),
+    /// The ZMSCORE command.
+    ZMScore(ZMScore),
+    /// The ZRANK and ZREVRANK commands.
+    ZRank(ZRank),
+    /// The ZREM command.
+    ZRem(ZRem),
+    /// The ZCARD command.
+    ZCard(ZCard),
+    /// The ZCOUNT command.
+    ZCount(ZCount),
+    /// The ZRANGE command.
+    ZRange(ZRange),
+    /// The ZRANGESTORE command.
+    ZRangeStore(ZRangeStore),
+    /// The ZREMRANGEBYRANK, ZREMRANGEBYSCORE and ZREMRANGEBYLEX commands.
+    ZRemRange(ZRemRange),
+    /// The ZINTER, ZUNION and ZDIFF commands.
+    ZInter(ZInter),
+    /// The ZINTERSTORE, ZUNIONSTORE and ZDIFFSTORE commands.
+    ZInterStore(ZInterStore),
+    /// The ZINTERCARD command.
+    ZInterCard(ZInterCard),
+    /// The ZPOPMIN and ZPOPMAX commands.
+    ZPopMin(ZPopMin),
+    /// The ZMPOP command.
+    ZMPop(ZMPop),
+    /// The BZPOPMIN and BZPOPMAX commands.
+    BZPopMin(BZPopMin),
+    /// The BZMPOP command.
+    BZMPop(BZMPop),
+    /// The XADD command.
+    XAdd(XAdd),
+    /// The XLEN command.
+    XLen(XLen),
+    /// The XRANGE and XREVRANGE commands.
+    XRange(XRange),
+    /// The XDEL command.
+    XDel(XDel),
+    /// The XTRIM command.
+    XTrim(XTrim),
+    /// The XREAD command.
+    XRead(XRead),
+    /// The XGROUP command and its subcommands.
+    XGroup(XGroup),
+    /// The XREADGROUP command.
+    XReadGroup(XReadGroup),
+    /// The XACK command.
+    XAck(XAck),
+    /// The XPENDING command.
+    XPending(XPending),
+    /// The XCLAIM command.
+    XClaim(XClaim),
+    /// The XAUTOCLAIM command.
+    XAutoClaim(XAutoClaim),
+    /// The XINFO command and its subcommands.
+    XInfo(XInfo),
+    /// The PFADD command.
+    PfAdd(PfAdd),
+    /// The PFCOUNT command.
+    PfCount(PfCount),
+    /// The PFMERGE command.
+    PfMerge(PfMerge),
 }
 
 impl Command {
     /// Attempts to create a Command from the given RESP command frame.
     pub fn from_resp_command_frame(frame: Vec<RespType>) -> Result<Command, CommandError> {
-        if frame.len() == 0 {
+        if frame.is_empty() {
             return Err(CommandError::InvalidFormat);
         }
 
         let (cmd_name, args) = frame.split_at(1);
         let cmd_name = match &cmd_name[0] {
-            RespType::BulkString(s) => s.clone(),
+            RespType::BulkString(s) => String::from_utf8_lossy(s).to_string(),
             _ => return Err(CommandError::InvalidFormat),
         };
 
@@ -63,10 +443,153 @@ impl Command {
             "multi" => Command::Multi,
             "exec" => Command::Exec,
             "discard" => Command::Discard,
+            "hello" => Command::Hello(Hello::with_args(args.to_vec())?),
+            "expire" => Command::Expire(Expire::with_args(ExpireKind::Expire, args.to_vec())?),
+            "pexpire" => Command::Expire(Expire::with_args(ExpireKind::PExpire, args.to_vec())?),
+            "expireat" => Command::Expire(Expire::with_args(ExpireKind::ExpireAt, args.to_vec())?),
+            "pexpireat" => Command::Expire(Expire::with_args(ExpireKind::PExpireAt, args.to_vec())?),
+            "ttl" => Command::Ttl(Ttl::with_args(TtlKind::Ttl, args.to_vec())?),
+            "pttl" => Command::Ttl(Ttl::with_args(TtlKind::PTtl, args.to_vec())?),
+            "expiretime" => Command::Ttl(Ttl::with_args(TtlKind::ExpireTime, args.to_vec())?),
+            "pexpiretime" => Command::Ttl(Ttl::with_args(TtlKind::PExpireTime, args.to_vec())?),
+            "persist" => Command::Persist(Persist::with_args(args.to_vec())?),
+            "incr" => Command::Incr(Incr::with_args(IncrKind::Incr, args.to_vec())?),
+            "decr" => Command::Incr(Incr::with_args(IncrKind::Decr, args.to_vec())?),
+            "incrby" => Command::Incr(Incr::with_args(IncrKind::IncrBy, args.to_vec())?),
+            "decrby" => Command::Incr(Incr::with_args(IncrKind::DecrBy, args.to_vec())?),
+            "incrbyfloat" => Command::IncrByFloat(IncrByFloat::with_args(args.to_vec())?),
+            "append" => Command::Append(Append::with_args(args.to_vec())?),
+            "strlen" => Command::StrLen(StrLen::with_args(args.to_vec())?),
+            "getrange" => Command::GetRange(GetRange::with_args(args.to_vec())?),
+            "setrange" => Command::SetRange(SetRange::with_args(args.to_vec())?),
+            "mget" => Command::MGet(MGet::with_args(args.to_vec())?),
+            "mset" => Command::MSet(MSet::with_args(args.to_vec(), false)?),
+            "msetnx" => Command::MSet(MSet::with_args(args.to_vec(), true)?),
+            "getset" => Command::GetSet(GetSet::with_args(args.to_vec())?),
+            "getdel" => Command::GetDel(GetDel::with_args(args.to_vec())?),
+            "getex" => Command::GetEx(GetEx::with_args(args.to_vec())?),
+            "setnx" => Command::SetNx(SetNx::with_args(args.to_vec())?),
+            "setex" => Command::SetEx(SetEx::with_args(ExpireKind::Expire, args.to_vec())?),
+            "psetex" => Command::SetEx(SetEx::with_args(ExpireKind::PExpire, args.to_vec())?),
+            "setbit" => Command::SetBit(SetBit::with_args(args.to_vec())?),
+            "getbit" => Command::GetBit(GetBit::with_args(args.to_vec())?),
+            "bitcount" => Command::BitCount(BitCount::with_args(args.to_vec())?),
+            "bitpos" => Command::BitPos(BitPos::with_args(args.to_vec())?),
+            "bitop" => Command::BitOp(BitOp::with_args(args.to_vec())?),
+            "bitfield" => Command::BitField(BitField::with_args(args.to_vec(), false)?),
+            "bitfield_ro" => Command::BitField(BitField::with_args(args.to_vec(), true)?),
+            "lpop" => Command::LPop(LPop::with_args(ListEnd::Left, args.to_vec())?),
+            "rpop" => Command::LPop(LPop::with_args(ListEnd::Right, args.to_vec())?),
+            "llen" => Command::LLen(LLen::with_args(args.to_vec())?),
+            "lindex" => Command::LIndex(LIndex::with_args(args.to_vec())?),
+            "lset" => Command::LSet(LSet::with_args(args.to_vec())?),
+            "linsert" => Command::LInsert(LInsert::with_args(args.to_vec())?),
+            "lrem" => Command::LRem(LRem::with_args(args.to_vec())?),
+            "ltrim" => Command::LTrim(LTrim::with_args(args.to_vec())?),
+            "lpos" => Command::LPos(LPos::with_args(args.to_vec())?),
+            "blpop" => Command::BLPop(BLPop::with_args(ListEnd::Left, args.to_vec())?),
+            "brpop" => Command::BLPop(BLPop::with_args(ListEnd::Right, args.to_vec())?),
+            "blmove" => Command::BLMove(BLMove::with_args(args.to_vec())?),
+            "blmpop" => Command::BLMPop(BLMPop::with_args(args.to_vec())?),
+            "lmove" => Command::LMove(LMove::with_args(args.to_vec())?),
+            "rpoplpush" => Command::LMove(LMove::with_rpoplpush_args(args.to_vec())?),
+            "lmpop" => Command::LMPop(LMPop::with_args(args.to_vec())?),
+            "hset" => Command::HSet(HSet::with_args(args.to_vec())?),
+            "hsetnx" => Command::HSetNx(HSetNx::with_args(args.to_vec())?),
+            "hget" => Command::HGet(HGet::with_args(args.to_vec())?),
+            "hmget" => Command::HMGet(HMGet::with_args(args.to_vec())?),
+            "hdel" => Command::HDel(HDel::with_args(args.to_vec())?),
+            "hexists" => Command::HExists(HExists::with_args(args.to_vec())?),
+            "hlen" => Command::HLen(HLen::with_args(args.to_vec())?),
+            "hgetall" => Command::HGetAll(HGetAll::with_args(HGetAllKind::All, args.to_vec())?),
+            "hkeys" => Command::HGetAll(HGetAll::with_args(HGetAllKind::Keys, args.to_vec())?),
+            "hvals" => Command::HGetAll(HGetAll::with_args(HGetAllKind::Vals, args.to_vec())?),
+            "hincrby" => Command::HIncrBy(HIncrBy::with_args(args.to_vec())?),
+            "hincrbyfloat" => Command::HIncrByFloat(HIncrByFloat::with_args(args.to_vec())?),
+            "hstrlen" => Command::HStrLen(HStrLen::with_args(args.to_vec())?),
+            "hrandfield" => Command::HRandField(HRandField::with_args(args.to_vec())?),
+            "hexpire" => Command::HExpire(HExpire::with_args(ExpireKind::Expire, args.to_vec())?),
+            "hpexpire" => Command::HExpire(HExpire::with_args(ExpireKind::PExpire, args.to_vec())?),
+            "hexpireat" => Command::HExpire(HExpire::with_args(ExpireKind::ExpireAt, args.to_vec())?),
+            "hpexpireat" => Command::HExpire(HExpire::with_args(ExpireKind::PExpireAt, args.to_vec())?),
+            "httl" => Command::HTtl(HTtl::with_args(TtlKind::Ttl, args.to_vec())?),
+            "hpttl" => Command::HTtl(HTtl::with_args(TtlKind::PTtl, args.to_vec())?),
+            "hexpiretime" => Command::HTtl(HTtl::with_args(TtlKind::ExpireTime, args.to_vec())?),
+            "hpexpiretime" => Command::HTtl(HTtl::with_args(TtlKind::PExpireTime, args.to_vec())?),
+            "hpersist" => Command::HPersist(HPersist::with_args(args.to_vec())?),
+            "sadd" => Command::SAdd(SAdd::with_args(args.to_vec())?),
+            "srem" => Command::SRem(SRem::with_args(args.to_vec())?),
+            "smembers" => Command::SMembers(SMembers::with_args(args.to_vec())?),
+            "sismember" => Command::SIsMember(SIsMember::with_args(args.to_vec())?),
+            "smismember" => Command::SMIsMember(SMIsMember::with_args(args.to_vec())?),
+            "scard" => Command::SCard(SCard::with_args(args.to_vec())?),
+            "spop" => Command::SPop(SPop::with_args(args.to_vec())?),
+            "srandmember" => Command::SRandMember(SRandMember::with_args(args.to_vec())?),
+            "smove" => Command::SMove(SMove::with_args(args.to_vec())?),
+            "sinter" => Command::SInter(SInter::with_args(SetOp::Inter, args.to_vec())?),
+            "sunion" => Command::SInter(SInter::with_args(SetOp::Union, args.to_vec())?),
+            "sdiff" => Command::SInter(SInter::with_args(SetOp::Diff, args.to_vec())?),
+            "sinterstore" => Command::SInterStore(SInterStore::with_args(SetOp::Inter, args.to_vec())?),
+            "sunionstore" => Command::SInterStore(SInterStore::with_args(SetOp::Union, args.to_vec())?),
+            "sdiffstore" => Command::SInterStore(SInterStore::with_args(SetOp::Diff, args.to_vec())?),
+            "sintercard" => Command::SInterCard(SInterCard::with_args(args.to_vec())?),
+            "zadd" => Command::ZAdd(ZAdd::with_args(args.to_vec())?),
+            "zincrby" => Command::ZIncrBy(ZIncrBy::with_args(args.to_vec())?),
+            "zscore" => Command::ZScore(ZScore::with_args(args.to_vec())?),
+            "zmscore" => Command::ZMScore(ZMScore::with_args(args.to_vec())?),
+            "zrank" => Command::ZRank(ZRank::with_args(false, args.to_vec())?),
+            "zrevrank" => Command::ZRank(ZRank::with_args(true, args.to_vec())?),
+            "zrem" => Command::ZRem(ZRem::with_args(args.to_vec())?),
+            "zcard" => Command::ZCard(ZCard::with_args(args.to_vec())?),
+            "zcount" => Command::ZCount(ZCount::with_args(args.to_vec())?),
+            "zrange" => Command::ZRange(ZRange::with_args(args.to_vec())?),
+            "zrangestore" => Command::ZRangeStore(ZRangeStore::with_args(args.to_vec())?),
+            "zremrangebyrank" => Command::ZRemRange(ZRemRange::with_args(ZRemRangeKind::Rank, args.to_vec())?),
+            "zremrangebyscore" => Command::ZRemRange(ZRemRange::with_args(ZRemRangeKind::Score, args.to_vec())?),
+            "zremrangebylex" => Command::ZRemRange(ZRemRange::with_args(ZRemRangeKind::Lex, args.to_vec())?),
+            "zinter" => Command::ZInter(ZInter::with_args(SetOp::Inter, args.to_vec())?),
+            "zunion" => Command::ZInter(ZInter::with_args(SetOp::Union, args.to_vec())?),
+            "zdiff" => Command::ZInter(ZInter::with_args(SetOp::Diff, args.to_vec())?),
+            "zinterstore" => Command::ZInterStore(ZInterStore::with_args(SetOp::Inter, args.to_vec())?),
+            "zunionstore" => Command::ZInterStore(ZInterStore::with_args(SetOp::Union, args.to_vec())?),
+            "zdiffstore" => Command::ZInterStore(ZInterStore::with_args(SetOp::Diff, args.to_vec())?),
+            "zintercard" => Command::ZInterCard(ZInterCard::with_args(args.to_vec())?),
+            "zpopmin" => Command::ZPopMin(ZPopMin::with_args(ZSetEnd::Min, args.to_vec())?),
+            "zpopmax" => Command::ZPopMin(ZPopMin::with_args(ZSetEnd::Max, args.to_vec())?),
+            "zmpop" => Command::ZMPop(ZMPop::with_args(args.to_vec())?),
+            "bzpopmin" => Command::BZPopMin(BZPopMin::with_args(ZSetEnd::Min, args.to_vec())?),
+            "bzpopmax" => Command::BZPopMin(BZPopMin::with_args(ZSetEnd::Max, args.to_vec())?),
+            "bzmpop" => Command::BZMPop(BZMPop::with_args(args.to_vec())?),
+            "xadd" => Command::XAdd(XAdd::with_args(args.to_vec())?),
+            "xlen" => Command::XLen(XLen::with_args(args.to_vec())?),
+            "xrange" => Command::XRange(XRange::with_args(false, args.to_vec())?),
+            "xrevrange" => Command::XRange(XRange::with_args(true, args.to_vec())?),
+            "xdel" => Command::XDel(XDel::with_args(args.to_vec())?),
+            "xtrim" => Command::XTrim(XTrim::with_args(args.to_vec())?),
+            "xread" => Command::XRead(XRead::with_args(args.to_vec())?),
+            "xgroup" => Command::XGroup(XGroup::with_args(args.to_vec())?),
+            "xreadgroup" => Command::XReadGroup(XReadGroup::with_args(args.to_vec())?),
+            "xack" => Command::XAck(XAck::with_args(args.to_vec())?),
+            "xpending" => Command::XPending(XPending::with_args(args.to_vec())?),
+            "xclaim" => Command::XClaim(XClaim::with_args(args.to_vec())?),
+            "xautoclaim" => Command::XAutoClaim(XAutoClaim::with_args(args.to_vec())?),
+            "xinfo" => Command::XInfo(XInfo::with_args(args.to_vec())?),
+            "pfadd" => Command::PfAdd(PfAdd::with_args(args.to_vec())?),
+            "pfcount" => Command::PfCount(PfCount::with_args(args.to_vec())?),
+            "pfmerge" => Command::PfMerge(PfMerge::with_args(args.to_vec())?),
             _ => {
+                // like Redis, echo back at most the first 128 bytes of each argument
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        RespType::BulkString(s) => String::from_utf8_lossy(&s[..s.len().min(128)]).to_string(),
+                        _ => String::new(),
+                    })
+                    .collect();
                 return Err(CommandError::UnknownCommand(ErrUnknownCommand {
                     cmd: cmd_name.to_string(),
-                }))
+                    args,
+                }));
             }
         };
 
@@ -85,20 +608,153 @@ impl Command {
             Command::Multi => RespType::SimpleString(String::from("OK")),
             Command::Exec => RespType::NullBulkString,
             Command::Discard => RespType::SimpleString(String::from("OK")),
+            // HELLO depends on the connection state and is answered by the frame handler,
+            // which refuses to queue it in a transaction.
+            Command::Hello(_) => RespType::SimpleError(TransactionError::NotAllowed.to_string()),
+            Command::Expire(expire) => expire.apply(db),
+            Command::Ttl(ttl) => ttl.apply(db),
+            Command::Persist(persist) => persist.apply(db),
+            Command::Incr(incr) => incr.apply(db),
+            Command::IncrByFloat(incr) => incr.apply(db),
+            Command::Append(append) => append.apply(db),
+            Command::StrLen(strlen) => strlen.apply(db),
+            Command::GetRange(getrange) => getrange.apply(db),
+            Command::SetRange(setrange) => setrange.apply(db),
+            Command::MGet(mget) => mget.apply(db),
+            Command::MSet(mset) => mset.apply(db),
+            Command::GetSet(getset) => getset.apply(db),
+            Command::GetDel(getdel) => getdel.apply(db),
+            Command::GetEx(getex) => getex.apply(db),
+            Command::SetNx(setnx) => setnx.apply(db),
+            Command::SetEx(setex) => setex.apply(db),
+            Command::SetBit(setbit) => setbit.apply(db),
+            Command::GetBit(getbit) => getbit.apply(db),
+            Command::BitCount(bitcount) => bitcount.apply(db),
+            Command::BitPos(bitpos) => bitpos.apply(db),
+            Command::BitOp(bitop) => bitop.apply(db),
+            Command::BitField(bitfield) => bitfield.apply(db),
+            Command::LPop(lpop) => lpop.apply(db),
+            Command::LLen(llen) => llen.apply(db),
+            Command::LIndex(lindex) => lindex.apply(db),
+            Command::LSet(lset) => lset.apply(db),
+            Command::LInsert(linsert) => linsert.apply(db),
+            Command::LRem(lrem) => lrem.apply(db),
+            Command::LTrim(ltrim) => ltrim.apply(db),
+            Command::LPos(lpos) => lpos.apply(db),
+            Command::BLPop(blpop) => blpop.apply(db),
+            Command::BLMove(blmove) => blmove.apply(db),
+            Command::BLMPop(blmpop) => blmpop.apply(db),
+            Command::LMove(lmove) => lmove.apply(db),
+            Command::LMPop(lmpop) => lmpop.apply(db),
+            Command::HSet(hset) => hset.apply(db),
+            Command::HSetNx(hsetnx) => hsetnx.apply(db),
+            Command::HGet(hget) => hget.apply(db),
+            Command::HMGet(hmget) => hmget.apply(db),
+            Command::HDel(hdel) => hdel.apply(db),
+            Command::HExists(hexists) => hexists.apply(db),
+            Command::HLen(hlen) => hlen.apply(db),
+            Command::HGetAll(hgetall) => hgetall.apply(db),
+            Command::HIncrBy(hincrby) => hincrby.apply(db),
+            Command::HIncrByFloat(hincrbyfloat) => hincrbyfloat.apply(db),
+            Command::HStrLen(hstrlen) => hstrlen.apply(db),
+            Command::HRandField(hrandfield) => hrandfield.apply(db),
+            Command::HExpire(hexpire) => hexpire.apply(db),
+            Command::HTtl(httl) => httl.apply(db),
+            Command::HPersist(hpersist) => hpersist.apply(db),
+            Command::SAdd(sadd) => sadd.apply(db),
+            Command::SRem(srem) => srem.apply(db),
+            Command::SMembers(smembers) => smembers.apply(db),
+            Command::SIsMember(sismember) => sismember.apply(db),
+            Command::SMIsMember(smismember) => smismember.apply(db),
+            Command::SCard(scard) => scard.apply(db),
+            Command::SPop(spop) => spop.apply(db),
+            Command::SRandMember(srandmember) => srandmember.apply(db),
+            Command::SMove(smove) => smove.apply(db),
+            Command::SInter(sinter) => sinter.apply(db),
+            Command::SInterStore(sinterstore) => sinterstore.apply(db),
+            Command::SInterCard(sintercard) => sintercard.apply(db),
+            Command::ZAdd(zadd) => zadd.apply(db),
+            Command::ZIncrBy(zincrby) => zincrby.apply(db),
+            Command::ZScore(zscore) => zscore.apply(db),
+            Command::ZMScore(zmscore) => zmscore.apply(db),
+            Command::ZRank(zrank) => zrank.apply(db),
+            Command::ZRem(zrem) => zrem.apply(db),
+            Command::ZCard(zcard) => zcard.apply(db),
+            Command::ZCount(zcount) => zcount.apply(db),
+            Command::ZRange(zrange) => zrange.apply(db),
+            Command::ZRangeStore(zrangestore) => zrangestore.apply(db),
+            Command::ZRemRange(zremrange) => zremrange.apply(db),
+            Command::ZInter(zinter) => zinter.apply(db),
+            Command::ZInterStore(zinterstore) => zinterstore.apply(db),
+            Command::ZInterCard(zintercard) => zintercard.apply(db),
+            Command::ZPopMin(zpopmin) => zpopmin.apply(db),
+            Command::ZMPop(zmpop) => zmpop.apply(db),
+            Command::BZPopMin(bzpopmin) => bzpopmin.apply(db),
+            Command::BZMPop(bzmpop) => bzmpop.apply(db),
+            Command::XAdd(xadd) => xadd.apply(db),
+            Command::XLen(xlen) => xlen.apply(db),
+            Command::XRange(xrange) => xrange.apply(db),
+            Command::XDel(xdel) => xdel.apply(db),
+            Command::XTrim(xtrim) => xtrim.apply(db),
+            Command::XRead(xread) => xread.apply(db),
+            Command::XGroup(xgroup) => xgroup.apply(db),
+            Command::XReadGroup(xreadgroup) => xreadgroup.apply(db),
+            Command::XAck(xack) => xack.apply(db),
+            Command::XPending(xpending) => xpending.apply(db),
+            Command::XClaim(xclaim) => xclaim.apply(db),
+            Command::XAutoClaim(xautoclaim) => xautoclaim.apply(db),
+            Command::XInfo(xinfo) => xinfo.apply(db),
+            Command::PfAdd(pfadd) => pfadd.apply(db),
+            Command::PfCount(pfcount) => pfcount.apply(db),
+            Command::PfMerge(pfmerge) => pfmerge.apply(db),
+        }
+    }
+
+    /// Returns the command as a blocking command, if it can block the client.
+    pub fn as_blocking(&self) -> Option<&dyn Blocking> {
+        match self {
+            Command::BLPop(blpop) => Some(blpop),
+            Command::BLMove(blmove) => Some(blmove),
+            Command::BLMPop(blmpop) => Some(blmpop),
+            Command::BZPopMin(bzpopmin) => Some(bzpopmin),
+            Command::BZMPop(bzmpop) => Some(bzmpop),
+            Command::XRead(xread) if xread.is_blocking() => Some(xread),
+            Command::XReadGroup(xreadgroup) if xreadgroup.is_blocking() => Some(xreadgroup),
+            _ => None,
         }
     }
 }
 
+/// Represents errors that can occur while parsing a command.
+/// Every error renders the message Redis replies with, including its prefix (e.g. `ERR`),
+/// since client libraries rely on the first word to classify the error.
 #[derive(Debug)]
 pub enum CommandError {
+    /// The command frame isn't an array of bulk strings.
     InvalidFormat,
+    /// The command isn't supported.
     UnknownCommand(ErrUnknownCommand),
+    /// The command was called with the wrong number of arguments.
+    /// Holds the lowercase name of the command.
+    WrongArity(&'static str),
+    /// The arguments don't match the syntax of the command.
+    Syntax,
+    /// An argument was expected to be an integer.
+    NotInteger,
+    /// An argument was expected to be a floating point number.
+    NotFloat,
+    /// HELLO was called with a protocol version that isn't supported.
+    NoProto,
+    /// Authentication failed.
+    WrongPass,
+    /// Any other error, rendered with the generic `ERR` prefix.
     Other(String),
 }
 
 #[derive(Debug)]
 pub struct ErrUnknownCommand {
     pub cmd: String,
+    pub args: Vec<String>,
 }
 
 impl std::error::Error for CommandError {}
@@ -106,9 +762,22 @@ impl std::error::Error for CommandError {}
 impl fmt::Display for CommandError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CommandError::InvalidFormat => "Invalid command format".fmt(f),
-            CommandError::UnknownCommand(e) => write!(f, "Unknown command: {}", e.cmd),
-            CommandError::Other(msg) => msg.as_str().fmt(f),
+            CommandError::InvalidFormat => "ERR Protocol error: expected an array of bulk strings".fmt(f),
+            CommandError::UnknownCommand(e) => {
+                write!(f, "ERR unknown command '{}', with args beginning with: ", e.cmd)?;
+                e.args.iter().try_for_each(|arg| write!(f, "'{}' ", arg))
+            }
+            CommandError::WrongArity(cmd) => {
+                write!(f, "ERR wrong number of arguments for '{}' command", cmd)
+            }
+            CommandError::Syntax => "ERR syntax error".fmt(f),
+            CommandError::NotInteger => "ERR value is not an integer or out of range".fmt(f),
+            CommandError::NotFloat => "ERR value is not a valid float".fmt(f),
+            CommandError::NoProto => "NOPROTO unsupported protocol version".fmt(f),
+            CommandError::WrongPass => {
+                "WRONGPASS invalid username-password pair or user is disabled.".fmt(f)
+            }
+            CommandError::Other(msg) => write!(f, "ERR {}", msg),
         }
     }
 }
@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the MSET and MSETNX commands.
+#[derive(Debug)]
+pub struct MSet {
+    pairs: Vec<(Bytes, Bytes)>,
+    /// Set the keys only if none of them exists (MSETNX).
+    nx: bool,
+}
+
+impl MSet {
+    /// Creates a new MSet instance from the given args.
+    pub fn with_args(args: Vec<RespType>, nx: bool) -> Result<MSet, CommandError> {
+        if args.is_empty() || !args.len().is_multiple_of(2) {
+            return Err(CommandError::WrongArity(if nx { "msetnx" } else { "mset" }));
+        }
+
+        let pairs = args
+            .chunks(2)
+            .map(|pair| Ok((parse_bytes(&pair[0])?, parse_bytes(&pair[1])?)))
+            .collect::<Result<_, CommandError>>()?;
+
+        Ok(MSet { pairs, nx })
+    }
+
+    /// Executes the command. MSET always replies OK, MSETNX replies 1 if the keys were set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.mset(self.pairs.clone(), self.nx) {
+            Ok(set) if self.nx => RespType::Integer(set as i64),
+            Ok(_) => RespType::SimpleString(String::from("OK")),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
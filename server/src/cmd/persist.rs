@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the PERSIST command.
+#[derive(Debug)]
+pub struct Persist {
+    key: Bytes,
+}
+
+impl Persist {
+    /// Creates a new Persist instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<Persist, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("persist"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(Persist { key })
+    }
+
+    /// Executes the PERSIST command. Replies 1 if the TTL was removed.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.persist(&self.key) {
+            Ok(removed) => RespType::Integer(removed as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
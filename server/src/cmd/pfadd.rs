@@ -0,0 +1,35 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the PFADD command.
+#[derive(Debug)]
+pub struct PfAdd {
+    key: Bytes,
+    elements: Vec<Bytes>,
+}
+
+impl PfAdd {
+    /// Creates a new PfAdd instance from the given args.
+    /// Syntax: PFADD key [element [element ...]]
+    pub fn with_args(args: Vec<RespType>) -> Result<PfAdd, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("pfadd"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let elements = args[1..].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(PfAdd { key, elements })
+    }
+
+    /// Executes the PFADD command, replying 1 if the HyperLogLog was created or altered.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pfadd(self.key.clone(), &self.elements) {
+            Ok(updated) => RespType::Integer(updated as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the PFCOUNT command.
+#[derive(Debug)]
+pub struct PfCount {
+    keys: Vec<Bytes>,
+}
+
+impl PfCount {
+    /// Creates a new PfCount instance from the given args.
+    /// Syntax: PFCOUNT key [key ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<PfCount, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("pfcount"));
+        }
+
+        let keys = args.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(PfCount { keys })
+    }
+
+    /// Executes the PFCOUNT command, replying with the estimated number of unique
+    /// elements of the HyperLogLog, or of the union of the HyperLogLogs.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pfcount(&self.keys) {
+            Ok(count) => RespType::Integer(count as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
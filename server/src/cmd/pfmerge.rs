@@ -0,0 +1,35 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the PFMERGE command.
+#[derive(Debug)]
+pub struct PfMerge {
+    dest: Bytes,
+    keys: Vec<Bytes>,
+}
+
+impl PfMerge {
+    /// Creates a new PfMerge instance from the given args.
+    /// Syntax: PFMERGE destkey [sourcekey [sourcekey ...]]
+    pub fn with_args(args: Vec<RespType>) -> Result<PfMerge, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("pfmerge"));
+        }
+
+        let dest = parse_bytes(&args[0])?;
+        let keys = args[1..].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(PfMerge { dest, keys })
+    }
+
+    /// Executes the PFMERGE command, replying OK.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.pfmerge(self.dest.clone(), &self.keys) {
+            Ok(()) => RespType::SimpleString(String::from("OK")),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
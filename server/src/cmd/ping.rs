@@ -1,31 +1,30 @@
+use bytes::Bytes;
 use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
 use crate::resp::types::RespType;
 
 /// Represents the PING command.
 #[derive(Debug)]
 pub struct Ping {
-    msg: Option<String>
+    msg: Option<Bytes>
 }
 
 impl Ping {
     /// Creates a new PING instance from the given args.
     pub fn with_args(args: Vec<RespType>) -> Result<Ping, CommandError> {
-        if args.len() == 0 {
+        if args.is_empty() {
             Ok(Ping { msg: None })
         } else if args.len() == 1 {
-            match &args[0] {
-                RespType::BulkString(s) => Ok(Ping { msg: Some(s.clone()) }),
-                _ => Err(CommandError::Other(String::from("Invalid message"))),
-            }
+            Ok(Ping { msg: Some(parse_bytes(&args[0])?) })
         } else {
-            Err(CommandError::InvalidFormat)
+            Err(CommandError::WrongArity("ping"))
         }
     }
 
     /// Executes the PING command.
     pub fn apply(&self) -> RespType {
         match &self.msg {
-            Some(msg) => RespType::BulkString(msg.to_string()),
+            Some(msg) => RespType::BulkString(msg.clone()),
             None => RespType::SimpleString(String::from("PONG")),
         }
     }
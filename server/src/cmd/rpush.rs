@@ -1,29 +1,25 @@
+use bytes::Bytes;
 use crate::cmd::CommandError;
-use crate::cmd::utils::parse_values;
+use crate::cmd::utils::{parse_bytes, parse_values};
 use crate::resp::types::RespType;
 use crate::storage::db::DB;
 
 /// Represents the RPUSH command
 #[derive(Debug, Clone)]
 pub struct RPush {
-    key: String,
-    values: Vec<String>,
+    key: Bytes,
+    values: Vec<Bytes>,
 }
 
 impl RPush {
     /// Creates a new RPush instance from the given args.
     pub fn with_args(args: Vec<RespType>) -> Result<RPush, CommandError> {
         if args.len() < 2 {
-            return Err(CommandError::Other(String::from(
-                "Wrong number of arguments specified for 'RPUSH' command",
-            )));
+            return Err(CommandError::WrongArity("rpush"));
         }
 
         // parse key
-        let key = match &args[0] {
-            RespType::BulkString(s) => s.to_string(),
-            _ => return Err(CommandError::Other(String::from("Invalid key"))),
-        };
+        let key = parse_bytes(&args[0])?;
 
         // parse values
         let values = parse_values(args)?;
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SADD command.
+#[derive(Debug)]
+pub struct SAdd {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl SAdd {
+    /// Creates a new SAdd instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SAdd, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("sadd"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let members = parse_values(args)?;
+
+        Ok(SAdd { key, members })
+    }
+
+    /// Executes the SADD command, replying with the number of members added.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.sadd(self.key.clone(), self.members.clone()) {
+            Ok(added) => RespType::Integer(added as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SCARD command.
+#[derive(Debug)]
+pub struct SCard {
+    key: Bytes,
+}
+
+impl SCard {
+    /// Creates a new SCard instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SCard, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("scard"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(SCard { key })
+    }
+
+    /// Executes the SCARD command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.scard(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
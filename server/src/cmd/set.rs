@@ -1,53 +1,163 @@
+use bytes::Bytes;
 use crate::cmd::CommandError;
+use crate::cmd::expire::{ExpireKind, ExpireTime};
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
 use crate::resp::types::RespType;
-use crate::storage::db::{Value, DB};
+use crate::storage::db::DB;
+use crate::storage::string::SetOptions;
 
 /// Represents the set command.
 #[derive(Debug)]
 pub struct Set {
-    key: String,
-    value: String,
+    key: Bytes,
+    value: Bytes,
+    /// Only set the key if it doesn't exist (NX).
+    nx: bool,
+    /// Only set the key if it already exists (XX).
+    xx: bool,
+    /// Reply with the previous value (GET).
+    get: bool,
+    /// Retain the TTL of the previous value (KEEPTTL).
+    keep_ttl: bool,
+    /// The TTL of the new value (EX, PX, EXAT or PXAT).
+    expire: Option<ExpireTime>,
 }
 
 impl Set {
 
     /// Creates a new instance of the set command from the given args.
+    /// Syntax: SET key value [NX | XX] [GET] [EX seconds | PX milliseconds |
+    ///   EXAT unix-time-seconds | PXAT unix-time-milliseconds | KEEPTTL]
     pub fn with_args(args: Vec<RespType>) -> Result<Self, CommandError> {
-        if args.len() != 2 {
-            return Err(CommandError::Other(String::from(
-                "Wrong number of arguments specified for 'SET' command",
-            )));
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("set"));
         }
 
         // parse key
-        let key = &args[0];
-        let key = match key {
-            RespType::BulkString(key) => key,
-            _ => return Err(CommandError::Other(String::from(
-                "Invalid argument.Key must be a bulk string",
-            ))),
-        };
+        let key = parse_bytes(&args[0])?;
 
         // parse value
-        let value = &args[1];
-        let value = match value {
-            RespType::BulkString(value) => value,
-            _ => return Err(CommandError::Other(String::from(
-                "Invalid argument.Value must be a bulk string",
-            ))),
+        let value = parse_bytes(&args[1])?;
+
+        let mut set = Self {
+            key,
+            value,
+            nx: false,
+            xx: false,
+            get: false,
+            keep_ttl: false,
+            expire: None,
         };
 
-        Ok(Self {
-            key: key.to_string(),
-            value: value.to_string(),
-        })
+        // parse options
+        let mut i = 2;
+        while i < args.len() {
+            let opt = parse_option(&args[i])?;
+            let expire_kind = match opt.as_str() {
+                "ex" => Some(ExpireKind::Expire),
+                "px" => Some(ExpireKind::PExpire),
+                "exat" => Some(ExpireKind::ExpireAt),
+                "pxat" => Some(ExpireKind::PExpireAt),
+                _ => None,
+            };
+
+            match (opt.as_str(), expire_kind) {
+                ("nx", _) if !set.xx => set.nx = true,
+                ("xx", _) if !set.nx => set.xx = true,
+                ("get", _) => set.get = true,
+                ("keepttl", _) if set.expire.is_none() => set.keep_ttl = true,
+                (_, Some(kind)) if set.expire.is_none() && !set.keep_ttl && i + 1 < args.len() => {
+                    let time = parse_i64(&args[i + 1])?;
+                    if time <= 0 {
+                        return Err(CommandError::Other(String::from(
+                            "invalid expire time in 'set' command",
+                        )));
+                    }
+                    set.expire = Some(ExpireTime::new(kind, time, "set")?);
+                    i += 1;
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+            i += 1;
+        }
+
+        Ok(set)
     }
 
     /// Execute the set command.
     pub fn apply(&self, db: &DB) -> RespType {
-        match db.set(self.key.clone(), Value::String(self.value.clone())) {
-            Ok(_) => RespType::BulkString("OK".to_string()),
+        let opts = SetOptions {
+            nx: self.nx,
+            xx: self.xx,
+            get: self.get,
+            keep_ttl: self.keep_ttl,
+            expires_at: self.expire.map(|expire| expire.deadline()),
+        };
+
+        match db.set_with_options(self.key.clone(), self.value.clone(), opts) {
+            // with GET the reply is the previous value, whether the key was set or not
+            Ok(res) if self.get => match res.previous {
+                Some(previous) => RespType::BulkString(previous),
+                None => RespType::NullBulkString,
+            },
+            Ok(res) if res.applied => RespType::SimpleString(String::from("OK")),
+            Ok(_) => RespType::NullBulkString,
             Err(e) => RespType::SimpleError(format!("{}", e)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Set, CommandError> {
+        let args = args.split(' ').map(|arg| RespType::BulkString(Bytes::from(arg.to_string())));
+        Set::with_args(args.collect())
+    }
+
+    fn error(args: &str) -> String {
+        parse(args).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_the_options() {
+        let set = parse("k v nx get ex 10").unwrap();
+        assert!(set.nx && set.get && !set.xx && !set.keep_ttl);
+        assert!(set.expire.is_some());
+
+        let set = parse("k v XX KEEPTTL GET").unwrap();
+        assert!(set.xx && set.keep_ttl && set.get && set.expire.is_none());
+
+        for args in ["k v px 100", "k v exat 1700000000", "k v pxat 1700000000000", "k v nx nx"] {
+            assert!(parse(args).is_ok(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn rejects_conflicting_options() {
+        for args in [
+            "k v nx xx",
+            "k v xx nx",
+            "k v ex 10 px 100",
+            "k v px 100 exat 1700000000",
+            "k v ex 10 ex 10",
+            "k v ex 10 keepttl",
+            "k v keepttl pxat 1700000000000",
+            "k v ex",
+            "k v get ex",
+            "k v nope",
+        ] {
+            assert_eq!(error(args), "ERR syntax error", "{}", args);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expire_times() {
+        for args in ["k v ex 0", "k v px -1", "k v ex 9223372036854775807", "k v exat 0"] {
+            assert_eq!(error(args), "ERR invalid expire time in 'set' command", "{}", args);
+        }
+        assert_eq!(error("k v ex ten"), "ERR value is not an integer or out of range");
+        assert_eq!(error("k"), "ERR wrong number of arguments for 'set' command");
+    }
+}
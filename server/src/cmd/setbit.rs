@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::string::MAX_STRING_LEN;
+
+/// Represents the SETBIT command.
+#[derive(Debug)]
+pub struct SetBit {
+    key: Bytes,
+    offset: u64,
+    on: bool,
+}
+
+impl SetBit {
+    /// Creates a new SetBit instance from the given args.
+    /// Syntax: SETBIT key offset value
+    pub fn with_args(args: Vec<RespType>) -> Result<SetBit, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("setbit"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let offset = parse_bit_offset(&args[1])?;
+        let on = match parse_bytes(&args[2])?.as_ref() {
+            b"0" => false,
+            b"1" => true,
+            _ => return Err(CommandError::Other(String::from(
+                "bit is not an integer or out of range",
+            ))),
+        };
+
+        Ok(SetBit { key, offset, on })
+    }
+
+    /// Executes the SETBIT command, replying with the previous value of the bit.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.setbit(self.key.clone(), self.offset, self.on) {
+            Ok(previous) => RespType::Integer(previous as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the offset of a bit, which must address a bit of a string of maximum length.
+pub fn parse_bit_offset(arg: &RespType) -> Result<u64, CommandError> {
+    let offset = crate::utils::parse_i64(&parse_bytes(arg)?);
+    match offset {
+        Some(offset) if offset >= 0 && (offset as u64) < MAX_STRING_LEN as u64 * 8 => {
+            Ok(offset as u64)
+        }
+        _ => Err(CommandError::Other(String::from(
+            "bit offset is not an integer or out of range",
+        ))),
+    }
+}
@@ -0,0 +1,54 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::expire::{ExpireKind, ExpireTime};
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::string::SetOptions;
+
+/// Represents the SETEX and PSETEX commands, the legacy forms of
+/// `SET key value EX seconds` and `SET key value PX milliseconds`.
+#[derive(Debug)]
+pub struct SetEx {
+    key: Bytes,
+    value: Bytes,
+    expire: ExpireTime,
+}
+
+impl SetEx {
+    /// Creates a new SetEx instance from the given args. The kind is either
+    /// `ExpireKind::Expire` for SETEX or `ExpireKind::PExpire` for PSETEX.
+    /// Syntax: SETEX key seconds value
+    pub fn with_args(kind: ExpireKind, args: Vec<RespType>) -> Result<SetEx, CommandError> {
+        let name = match kind {
+            ExpireKind::PExpire => "psetex",
+            _ => "setex",
+        };
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let time = parse_i64(&args[1])?;
+        if time <= 0 {
+            return Err(CommandError::Other(format!("invalid expire time in '{}' command", name)));
+        }
+        let expire = ExpireTime::new(kind, time, name)?;
+        let value = parse_bytes(&args[2])?;
+
+        Ok(SetEx { key, value, expire })
+    }
+
+    /// Executes the command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let opts = SetOptions {
+            expires_at: Some(self.expire.deadline()),
+            ..SetOptions::default()
+        };
+
+        match db.set_with_options(self.key.clone(), self.value.clone(), opts) {
+            Ok(_) => RespType::SimpleString(String::from("OK")),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::string::SetOptions;
+
+/// Represents the SETNX command, the legacy form of `SET key value NX`.
+#[derive(Debug)]
+pub struct SetNx {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl SetNx {
+    /// Creates a new SetNx instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SetNx, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("setnx"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let value = parse_bytes(&args[1])?;
+
+        Ok(SetNx { key, value })
+    }
+
+    /// Executes the SETNX command, replying 1 if the key was set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let opts = SetOptions { nx: true, ..SetOptions::default() };
+
+        match db.set_with_options(self.key.clone(), self.value.clone(), opts) {
+            Ok(res) => RespType::Integer(res.applied as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SETRANGE command.
+#[derive(Debug)]
+pub struct SetRange {
+    key: Bytes,
+    offset: usize,
+    value: Bytes,
+}
+
+impl SetRange {
+    /// Creates a new SetRange instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SetRange, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("setrange"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let offset = parse_i64(&args[1])?;
+        if offset < 0 {
+            return Err(CommandError::Other(String::from("offset is out of range")));
+        }
+        let value = parse_bytes(&args[2])?;
+
+        Ok(SetRange { key, offset: offset as usize, value })
+    }
+
+    /// Executes the SETRANGE command, replying with the new length of the string.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.setrange(self.key.clone(), self.offset, self.value.clone()) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
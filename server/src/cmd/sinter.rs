@@ -0,0 +1,78 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::set::SetOp;
+
+/// Represents the SINTER, SUNION and SDIFF commands.
+#[derive(Debug)]
+pub struct SInter {
+    op: SetOp,
+    keys: Vec<Bytes>,
+}
+
+/// Represents the SINTERSTORE, SUNIONSTORE and SDIFFSTORE commands.
+#[derive(Debug)]
+pub struct SInterStore {
+    op: SetOp,
+    dest: Bytes,
+    keys: Vec<Bytes>,
+}
+
+/// The lowercase name of the command combining sets with `op`.
+fn name(op: SetOp, store: bool) -> &'static str {
+    match (op, store) {
+        (SetOp::Inter, false) => "sinter",
+        (SetOp::Union, false) => "sunion",
+        (SetOp::Diff, false) => "sdiff",
+        (SetOp::Inter, true) => "sinterstore",
+        (SetOp::Union, true) => "sunionstore",
+        (SetOp::Diff, true) => "sdiffstore",
+    }
+}
+
+impl SInter {
+    /// Creates a new SInter instance from the given args.
+    /// Syntax: SINTER key [key ...]
+    pub fn with_args(op: SetOp, args: Vec<RespType>) -> Result<SInter, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity(name(op, false)));
+        }
+
+        let keys = args.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(SInter { op, keys })
+    }
+
+    /// Executes the command, replying with the members of the resulting set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.set_op(self.op, &self.keys) {
+            Ok(members) => RespType::Set(members.into_iter().map(RespType::BulkString).collect()),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl SInterStore {
+    /// Creates a new SInterStore instance from the given args.
+    /// Syntax: SINTERSTORE destination key [key ...]
+    pub fn with_args(op: SetOp, args: Vec<RespType>) -> Result<SInterStore, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(name(op, true)));
+        }
+
+        let dest = parse_bytes(&args[0])?;
+        let keys = args[1..].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        Ok(SInterStore { op, dest, keys })
+    }
+
+    /// Executes the command, replying with the size of the stored set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.set_op_store(self.op, self.dest.clone(), &self.keys) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
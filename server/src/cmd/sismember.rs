@@ -0,0 +1,63 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SISMEMBER command.
+#[derive(Debug)]
+pub struct SIsMember {
+    key: Bytes,
+    member: [Bytes; 1],
+}
+
+/// Represents the SMISMEMBER command.
+#[derive(Debug)]
+pub struct SMIsMember {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl SIsMember {
+    /// Creates a new SIsMember instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SIsMember, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("sismember"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let member = parse_bytes(&args[1])?;
+
+        Ok(SIsMember { key, member: [member] })
+    }
+
+    /// Executes the SISMEMBER command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.smismember(&self.key, &self.member) {
+            Ok(found) => RespType::Integer(found[0] as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl SMIsMember {
+    /// Creates a new SMIsMember instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SMIsMember, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("smismember"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let members = parse_values(args)?;
+
+        Ok(SMIsMember { key, members })
+    }
+
+    /// Executes the SMISMEMBER command, replying with 1 or 0 for each member.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.smismember(&self.key, &self.members) {
+            Ok(found) => RespType::Array(found.into_iter().map(|f| RespType::Integer(f as i64)).collect()),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SMEMBERS command.
+#[derive(Debug)]
+pub struct SMembers {
+    key: Bytes,
+}
+
+impl SMembers {
+    /// Creates a new SMembers instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SMembers, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("smembers"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(SMembers { key })
+    }
+
+    /// Executes the SMEMBERS command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.smembers(&self.key) {
+            Ok(members) => RespType::Set(members.into_iter().map(RespType::BulkString).collect()),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
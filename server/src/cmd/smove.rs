@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SMOVE command.
+#[derive(Debug)]
+pub struct SMove {
+    src: Bytes,
+    dest: Bytes,
+    member: Bytes,
+}
+
+impl SMove {
+    /// Creates a new SMove instance from the given args.
+    /// Syntax: SMOVE source destination member
+    pub fn with_args(args: Vec<RespType>) -> Result<SMove, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("smove"));
+        }
+
+        let src = parse_bytes(&args[0])?;
+        let dest = parse_bytes(&args[1])?;
+        let member = parse_bytes(&args[2])?;
+
+        Ok(SMove { src, dest, member })
+    }
+
+    /// Executes the SMOVE command, replying 1 if the member was moved.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.smove(&self.src, self.dest.clone(), self.member.clone()) {
+            Ok(moved) => RespType::Integer(moved as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SPOP command.
+#[derive(Debug)]
+pub struct SPop {
+    key: Bytes,
+    /// The number of members to pop. Without it a single member is replied, not a set.
+    count: Option<usize>,
+}
+
+impl SPop {
+    /// Creates a new SPop instance from the given args.
+    /// Syntax: SPOP key [count]
+    pub fn with_args(args: Vec<RespType>) -> Result<SPop, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("spop"));
+        }
+        if args.len() > 2 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = match args.get(1) {
+            Some(count) => match parse_i64(count) {
+                Ok(count) if count >= 0 => Some(count as usize),
+                _ => return Err(CommandError::Other(String::from(
+                    "value is out of range, must be positive",
+                ))),
+            },
+            None => None,
+        };
+
+        Ok(SPop { key, count })
+    }
+
+    /// Executes the SPOP command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.spop(&self.key, self.count.unwrap_or(1)) {
+            Ok(members) if self.count.is_some() => {
+                RespType::Set(members.into_iter().map(RespType::BulkString).collect())
+            }
+            Ok(mut members) => match members.pop() {
+                Some(member) => RespType::BulkString(member),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
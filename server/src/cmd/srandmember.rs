@@ -0,0 +1,45 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_random_count};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SRANDMEMBER command.
+#[derive(Debug)]
+pub struct SRandMember {
+    key: Bytes,
+    /// The number of members to return. Without it a single member is replied, not an array.
+    count: Option<i64>,
+}
+
+impl SRandMember {
+    /// Creates a new SRandMember instance from the given args.
+    /// Syntax: SRANDMEMBER key [count]
+    pub fn with_args(args: Vec<RespType>) -> Result<SRandMember, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("srandmember"));
+        }
+        if args.len() > 2 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = args.get(1).map(parse_random_count).transpose()?;
+
+        Ok(SRandMember { key, count })
+    }
+
+    /// Executes the SRANDMEMBER command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.srandmember(&self.key, self.count.unwrap_or(1)) {
+            Ok(members) if self.count.is_some() => {
+                RespType::Array(members.into_iter().map(RespType::BulkString).collect())
+            }
+            Ok(mut members) => match members.pop() {
+                Some(member) => RespType::BulkString(member),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the SREM command.
+#[derive(Debug)]
+pub struct SRem {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl SRem {
+    /// Creates a new SRem instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<SRem, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("srem"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let members = parse_values(args)?;
+
+        Ok(SRem { key, members })
+    }
+
+    /// Executes the SREM command, replying with the number of removed members.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.srem(&self.key, &self.members) {
+            Ok(removed) => RespType::Integer(removed as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the STRLEN command.
+#[derive(Debug)]
+pub struct StrLen {
+    key: Bytes,
+}
+
+impl StrLen {
+    /// Creates a new StrLen instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<StrLen, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("strlen"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(StrLen { key })
+    }
+
+    /// Executes the STRLEN command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.strlen(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
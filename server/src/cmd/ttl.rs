@@ -0,0 +1,112 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::utils::now_ms;
+
+/// Represents the TTL, PTTL, EXPIRETIME and PEXPIRETIME commands.
+#[derive(Debug)]
+pub struct Ttl {
+    key: Bytes,
+    kind: TtlKind,
+}
+
+/// The variants of the TTL command.
+#[derive(Debug, Clone, Copy)]
+pub enum TtlKind {
+    /// Remaining time to live in seconds.
+    Ttl,
+    /// Remaining time to live in milliseconds.
+    PTtl,
+    /// Unix time in seconds at which the key expires.
+    ExpireTime,
+    /// Unix time in milliseconds at which the key expires.
+    PExpireTime,
+}
+
+impl TtlKind {
+    /// The lowercase name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TtlKind::Ttl => "ttl",
+            TtlKind::PTtl => "pttl",
+            TtlKind::ExpireTime => "expiretime",
+            TtlKind::PExpireTime => "pexpiretime",
+        }
+    }
+
+    /// Converts the deadline of a key, in unix time milliseconds, into the reply of the command.
+    pub fn reply(&self, deadline: u64) -> i64 {
+        let deadline = deadline as i64;
+        let ttl = (deadline - now_ms() as i64).max(0);
+        match self {
+            // rounded to the closest second, like Redis does
+            TtlKind::Ttl => (ttl + 500) / 1000,
+            TtlKind::PTtl => ttl,
+            TtlKind::ExpireTime => deadline / 1000,
+            TtlKind::PExpireTime => deadline,
+        }
+    }
+}
+
+impl Ttl {
+    /// Creates a new Ttl instance from the given args.
+    pub fn with_args(kind: TtlKind, args: Vec<RespType>) -> Result<Ttl, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity(kind.name()));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(Ttl { key, kind })
+    }
+
+    /// Executes the command. Replies -2 if the key doesn't exist and -1 if it has no TTL.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let deadline = match db.expires_at(&self.key) {
+            Ok(None) => return RespType::Integer(-2),
+            Ok(Some(None)) => return RespType::Integer(-1),
+            Ok(Some(Some(deadline))) => deadline,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+
+        RespType::Integer(self.kind.reply(deadline))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::expire::ExpireCondition;
+
+    fn ttl(db: &DB, kind: TtlKind) -> i64 {
+        let args = vec![RespType::BulkString(Bytes::from("k"))];
+        match Ttl::with_args(kind, args).unwrap().apply(db) {
+            RespType::Integer(ttl) => ttl,
+            reply => panic!("unexpected reply {:?}", reply),
+        }
+    }
+
+    #[test]
+    fn replies_the_remaining_time_to_live() {
+        let db = DB::new();
+        assert_eq!(ttl(&db, TtlKind::Ttl), -2);
+        assert_eq!(ttl(&db, TtlKind::PTtl), -2);
+
+        db.mset(vec![(Bytes::from("k"), Bytes::from("v"))], false).unwrap();
+        assert_eq!(ttl(&db, TtlKind::Ttl), -1);
+        assert_eq!(ttl(&db, TtlKind::PExpireTime), -1);
+
+        let deadline = now_ms() + 10_400;
+        db.expire_at(b"k", deadline, ExpireCondition::default()).unwrap();
+        // rounded to the closest second
+        assert_eq!(ttl(&db, TtlKind::Ttl), 10);
+        assert!((10_300..=10_400).contains(&ttl(&db, TtlKind::PTtl)));
+        assert_eq!(ttl(&db, TtlKind::ExpireTime), deadline as i64 / 1000);
+        assert_eq!(ttl(&db, TtlKind::PExpireTime), deadline as i64);
+
+        db.persist(b"k").unwrap();
+        assert_eq!(ttl(&db, TtlKind::PTtl), -1);
+    }
+}
@@ -9,6 +9,8 @@ pub struct Transaction {
     commands: Vec<Command>,
     /// Indicates whether a transaction is currently active.
     is_active: bool,
+    /// Indicates whether a command failed to be queued, in which case EXEC aborts.
+    is_dirty: bool,
 }
 
 impl Transaction {
@@ -17,6 +19,7 @@ impl Transaction {
         Transaction {
             commands: Vec::new(),
             is_active: false,
+            is_dirty: false,
         }
     }
 
@@ -39,8 +42,22 @@ impl Transaction {
         self.commands.push(command);
     }
 
+    /// Flags the transaction so that EXEC discards it, used when a command
+    /// couldn't be queued because of an error.
+    pub fn mark_dirty(&mut self) {
+        self.is_dirty = true;
+    }
+
     /// Executes the transaction.
     pub async fn execute(&mut self, db: &DB) -> RespType {
+        if !self.is_active() {
+            return RespType::SimpleError(TransactionError::ExecWithoutMulti.to_string());
+        }
+        if self.is_dirty {
+            self.discard();
+            return RespType::SimpleError(TransactionError::ExecAbort.to_string());
+        }
+
         let mut responses: Vec<RespType> = vec![];
 
         for cmd in self.commands.iter() {
@@ -60,6 +77,7 @@ impl Transaction {
     pub fn discard(&mut self) {
         self.commands.clear();
         self.is_active = false;
+        self.is_dirty = false;
     }
 }
 
@@ -68,6 +86,15 @@ impl Transaction {
 pub enum TransactionError {
     /// Indicates that a MULTI command cannot be nested within another active transaction.
     CannotNestMulti,
+    /// Indicates that EXEC was called without an active transaction.
+    ExecWithoutMulti,
+    /// Indicates that DISCARD was called without an active transaction.
+    DiscardWithoutMulti,
+    /// Indicates that the transaction was discarded because a command failed to be queued.
+    ExecAbort,
+    /// Indicates that the command can't be queued, such as HELLO which changes the
+    /// protocol of the connection.
+    NotAllowed,
 }
 
 impl std::error::Error for TransactionError {}
@@ -75,7 +102,13 @@ impl std::error::Error for TransactionError {}
 impl fmt::Display for TransactionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TransactionError::CannotNestMulti => "MULTI calls cannot be nested".fmt(f),
+            TransactionError::CannotNestMulti => "ERR MULTI calls can not be nested".fmt(f),
+            TransactionError::ExecWithoutMulti => "ERR EXEC without MULTI".fmt(f),
+            TransactionError::DiscardWithoutMulti => "ERR DISCARD without MULTI".fmt(f),
+            TransactionError::ExecAbort => {
+                "EXECABORT Transaction discarded because of previous errors.".fmt(f)
+            }
+            TransactionError::NotAllowed => "ERR Command not allowed inside a transaction".fmt(f),
         }
     }
 }
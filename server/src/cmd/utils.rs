@@ -1,18 +1,234 @@
+use bytes::Bytes;
+use std::time::Duration;
 use crate::cmd::CommandError;
 use crate::resp::types::RespType;
+use crate::storage::list::ListEnd;
+use crate::storage::sorted_set::{LexBound, ScoreBound, ZSetEnd};
+use crate::storage::stream::{StreamId, XAddId};
+use crate::utils;
 
-pub fn parse_values(args: Vec<RespType>) -> Result<Vec<String>, CommandError> {
-    let mut values = Vec::new();
-
-    for arg in args.iter().skip(1) {
-        match arg {
-            RespType::BulkString(s) => values.push(s.to_string()),
-            _ => {
-                return Err(CommandError::Other(String::from(
-                    "Invalid argument. Value must be a bulk string",
-                )));
-            }
+/// Parses all the args after the first one (the key) as values.
+pub fn parse_values(args: Vec<RespType>) -> Result<Vec<Bytes>, CommandError> {
+    args.iter().skip(1).map(parse_bytes).collect()
+}
+
+/// Returns the payload of a bulk string argument, such as a key or a value.
+pub fn parse_bytes(arg: &RespType) -> Result<Bytes, CommandError> {
+    match arg {
+        RespType::BulkString(s) => Ok(s.clone()),
+        _ => Err(CommandError::InvalidFormat),
+    }
+}
+
+/// Returns the argument as a lowercase string, used to match option names.
+pub fn parse_option(arg: &RespType) -> Result<String, CommandError> {
+    match arg {
+        RespType::BulkString(s) => Ok(String::from_utf8_lossy(s).to_lowercase()),
+        _ => Err(CommandError::InvalidFormat),
+    }
+}
+
+/// Parses an integer argument.
+pub fn parse_i64(arg: &RespType) -> Result<i64, CommandError> {
+    match arg {
+        RespType::BulkString(s) => utils::parse_i64(s).ok_or(CommandError::NotInteger),
+        _ => Err(CommandError::InvalidFormat),
+    }
+}
+
+/// The largest number of members or fields a negative count may pick. Unlike Redis, which
+/// streams it, the reply is built at once, so its length is bounded.
+const MAX_RANDOM_REPEATS: i64 = 1024 * 1024;
+
+/// Parses the count of random members or fields to return, which is negative to allow
+/// repeated ones. Counts so large that the reply could never be sent are rejected.
+pub fn parse_random_count(arg: &RespType) -> Result<i64, CommandError> {
+    match parse_i64(arg)? {
+        count if (-MAX_RANDOM_REPEATS..=i64::MAX / 2).contains(&count) => Ok(count),
+        _ => Err(CommandError::Other(String::from("value is out of range"))),
+    }
+}
+
+/// Parses a floating point argument.
+pub fn parse_f64(arg: &RespType) -> Result<f64, CommandError> {
+    match arg {
+        RespType::BulkString(s) => utils::parse_f64(s).ok_or(CommandError::NotFloat),
+        _ => Err(CommandError::InvalidFormat),
+    }
+}
+
+/// Parses the timeout of a blocking command, in seconds with decimals.
+/// Returns `None` for zero, which blocks forever.
+pub fn parse_timeout(arg: &RespType) -> Result<Option<Duration>, CommandError> {
+    let timeout = match arg {
+        RespType::BulkString(s) => utils::parse_f64(s).filter(|t| t.is_finite()),
+        _ => return Err(CommandError::InvalidFormat),
+    };
+
+    match timeout {
+        Some(t) if t < 0.0 => Err(CommandError::Other(String::from("timeout is negative"))),
+        Some(0.0) => Ok(None),
+        Some(t) => match Duration::try_from_secs_f64(t) {
+            Ok(t) => Ok(Some(t)),
+            Err(_) => Err(CommandError::Other(String::from("timeout is out of range"))),
+        },
+        None => Err(CommandError::Other(String::from("timeout is not a float or out of range"))),
+    }
+}
+
+/// Parses the end of a list, LEFT or RIGHT.
+pub fn parse_list_end(arg: &RespType) -> Result<ListEnd, CommandError> {
+    match parse_option(arg)?.as_str() {
+        "left" => Ok(ListEnd::Left),
+        "right" => Ok(ListEnd::Right),
+        _ => Err(CommandError::Syntax),
+    }
+}
+
+/// Parses the end of a sorted set, MIN or MAX.
+pub fn parse_zset_end(arg: &RespType) -> Result<ZSetEnd, CommandError> {
+    match parse_option(arg)?.as_str() {
+        "min" => Ok(ZSetEnd::Min),
+        "max" => Ok(ZSetEnd::Max),
+        _ => Err(CommandError::Syntax),
+    }
+}
+
+/// Parses the `FIELDS numfields field [field ...]` block ending the hash field expiration
+/// commands.
+pub fn parse_hash_fields(args: &[RespType]) -> Result<Vec<Bytes>, CommandError> {
+    match args.first() {
+        Some(arg) if parse_option(arg)? == "fields" => {}
+        _ => return Err(CommandError::Other(String::from(
+            "Mandatory argument FIELDS is missing or not at the right position",
+        ))),
+    }
+
+    let numfields = match args.get(1).map(parse_i64) {
+        Some(Ok(n)) if n > 0 => n as usize,
+        _ => return Err(CommandError::Other(String::from(
+            "Parameter `numFields` should be greater than 0",
+        ))),
+    };
+    if numfields != args.len() - 2 {
+        return Err(CommandError::Other(String::from(
+            "The `numfields` parameter must match the number of arguments",
+        )));
+    }
+
+    args[2..].iter().map(parse_bytes).collect()
+}
+
+/// Parses a bound of a score range: a score, `(` followed by a score when exclusive,
+/// or `-inf` and `+inf`.
+pub fn parse_score_bound(arg: &RespType) -> Result<ScoreBound, CommandError> {
+    let s = parse_bytes(arg)?;
+    let (value, exclusive) = match s.strip_prefix(b"(") {
+        Some(value) => (value, true),
+        None => (s.as_ref(), false),
+    };
+
+    match utils::parse_f64(value) {
+        Some(value) => Ok(ScoreBound { value, exclusive }),
+        None => Err(CommandError::Other(String::from("min or max is not a float"))),
+    }
+}
+
+/// Parses a bound of a lexicographical range: `-`, `+`, `[member` or `(member`.
+pub fn parse_lex_bound(arg: &RespType) -> Result<LexBound, CommandError> {
+    let s = parse_bytes(arg)?;
+    match s.first() {
+        Some(b'-') if s.len() == 1 => Ok(LexBound::Min),
+        Some(b'+') if s.len() == 1 => Ok(LexBound::Max),
+        Some(b'[') => Ok(LexBound::Inclusive(s.slice(1..))),
+        Some(b'(') => Ok(LexBound::Exclusive(s.slice(1..))),
+        _ => Err(CommandError::Other(String::from("min or max not valid string range item"))),
+    }
+}
+
+/// Parses a stream ID, `ms-seq` or just `ms`, in which case the sequence number is `seq`.
+pub fn parse_stream_id(arg: &RespType, seq: u64) -> Result<StreamId, CommandError> {
+    stream_id(&parse_bytes(arg)?, seq)
+}
+
+/// Parses a bound of a stream range: an ID, `(` followed by an ID when exclusive, or `-`
+/// and `+`. An incomplete start ID starts at the first sequence number, and an incomplete
+/// end ID ends at the last one.
+pub fn parse_stream_bound(arg: &RespType, start: bool) -> Result<StreamId, CommandError> {
+    let s = parse_bytes(arg)?;
+    let (exclusive, s) = match s.strip_prefix(b"(") {
+        Some(rest) => (true, rest),
+        None => (false, s.as_ref()),
+    };
+
+    let id = match s {
+        b"-" => StreamId::MIN,
+        b"+" => StreamId::MAX,
+        _ => stream_id(s, if start { 0 } else { u64::MAX })?,
+    };
+    match (exclusive, start) {
+        (false, _) => Ok(id),
+        (true, true) => id
+            .next()
+            .ok_or_else(|| CommandError::Other(String::from("invalid start ID for the interval"))),
+        (true, false) => id
+            .prev()
+            .ok_or_else(|| CommandError::Other(String::from("invalid end ID for the interval"))),
+    }
+}
+
+/// Parses the ID of a new stream entry: `*`, `ms-*`, `ms-seq` or just `ms`, which is the
+/// first ID of the millisecond.
+pub fn parse_xadd_id(arg: &RespType) -> Result<XAddId, CommandError> {
+    let s = parse_bytes(arg)?;
+    match s.strip_suffix(b"-*") {
+        _ if s.as_ref() == b"*" => Ok(XAddId::Auto),
+        Some(ms) => match parse_u64(ms) {
+            Some(ms) => Ok(XAddId::AutoSeq(ms)),
+            None => stream_id(&s, 0).map(XAddId::Explicit),
+        },
+        None => stream_id(&s, 0).map(XAddId::Explicit),
+    }
+}
+
+/// Parses `ms-seq` or just `ms`, in which case the sequence number is `seq`.
+fn stream_id(s: &[u8], seq: u64) -> Result<StreamId, CommandError> {
+    let id = match s.iter().position(|b| *b == b'-') {
+        Some(dash) => parse_u64(&s[..dash]).zip(parse_u64(&s[dash + 1..])),
+        None => parse_u64(s).map(|ms| (ms, seq)),
+    };
+
+    match id {
+        Some((ms, seq)) => Ok(StreamId { ms, seq }),
+        None => Err(CommandError::Other(String::from(
+            "Invalid stream ID specified as stream command argument",
+        ))),
+    }
+}
+
+/// Parses an unsigned decimal integer, without sign.
+fn parse_u64(s: &[u8]) -> Option<u64> {
+    if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(s).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(s: &'static str) -> Result<i64, CommandError> {
+        parse_random_count(&RespType::BulkString(Bytes::from(s)))
+    }
+
+    #[test]
+    fn bounds_random_counts() {
+        assert_eq!(count("-1048576").unwrap(), -1048576);
+        assert_eq!(count("4611686018427387903").unwrap(), i64::MAX / 2);
+        for s in ["-1048577", "-4000000000000000000", "-9223372036854775808", "4611686018427387904"] {
+            assert_eq!(count(s).unwrap_err().to_string(), "ERR value is out of range");
         }
+        assert_eq!(count("x").unwrap_err().to_string(), "ERR value is not an integer or out of range");
     }
-    Ok(values)
-}
\ No newline at end of file
+}
@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_stream_id};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::StreamId;
+
+/// Represents the XACK command.
+#[derive(Debug)]
+pub struct XAck {
+    key: Bytes,
+    group: Bytes,
+    ids: Vec<StreamId>,
+}
+
+impl XAck {
+    /// Creates a new XAck instance from the given args.
+    /// Syntax: XACK key group id [id ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<XAck, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("xack"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let group = parse_bytes(&args[1])?;
+        let ids = args[2..].iter().map(|arg| parse_stream_id(arg, 0)).collect::<Result<_, _>>()?;
+
+        Ok(XAck { key, group, ids })
+    }
+
+    /// Executes the XACK command, replying with the number of acknowledged entries.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xack(&self.key, &self.group, &self.ids) {
+            Ok(acked) => RespType::Integer(acked as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_id, parse_xadd_id};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::{StreamTrim, TrimStrategy, XAddId};
+
+/// The number of entries evicted at most by `~` trimming without LIMIT, like Redis with
+/// its default of 100 entries per node.
+const DEFAULT_APPROX_LIMIT: usize = 100 * 100;
+
+/// Represents the XADD command.
+#[derive(Debug)]
+pub struct XAdd {
+    key: Bytes,
+    id: XAddId,
+    fields: Vec<(Bytes, Bytes)>,
+    trim: Option<StreamTrim>,
+    /// Don't create the stream if missing.
+    nomkstream: bool,
+}
+
+impl XAdd {
+    /// Creates a new XAdd instance from the given args.
+    /// Syntax: XADD key [NOMKSTREAM] [MAXLEN | MINID [= | ~] threshold [LIMIT count]]
+    /// * | id field value [field value ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<XAdd, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity("xadd"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let (trim, nomkstream, i) = parse_trim_options(&args[1..], true)?;
+        let rest = &args[1 + i..];
+        if rest.len() < 3 || rest.len().is_multiple_of(2) {
+            return Err(CommandError::WrongArity("xadd"));
+        }
+
+        let id = parse_xadd_id(&rest[0])?;
+        let fields = rest[1..]
+            .chunks(2)
+            .map(|pair| Ok((parse_bytes(&pair[0])?, parse_bytes(&pair[1])?)))
+            .collect::<Result<_, CommandError>>()?;
+
+        Ok(XAdd { key, id, fields, trim, nomkstream })
+    }
+
+    /// Executes the XADD command, replying with the ID of the new entry.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xadd(self.key.clone(), self.id, self.fields.clone(), self.trim, self.nomkstream) {
+            Ok(Some(id)) => RespType::BulkString(Bytes::from(id.to_string())),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the options of XADD and XTRIM: `[NOMKSTREAM] [MAXLEN | MINID [= | ~] threshold
+/// [LIMIT count]]`, NOMKSTREAM being only taken by XADD, whose options end at the ID.
+/// Returns the trimming, whether NOMKSTREAM was given and the index following the options.
+pub fn parse_trim_options(
+    args: &[RespType],
+    xadd: bool,
+) -> Result<(Option<StreamTrim>, bool, usize), CommandError> {
+    let (mut strategy, mut approx, mut limit, mut nomkstream) = (None, false, None, false);
+    let mut i = 0;
+    while i < args.len() {
+        let more = i + 1 < args.len();
+        match parse_option(&args[i])?.as_str() {
+            option @ ("maxlen" | "minid") if more => {
+                if strategy.is_some() {
+                    return Err(CommandError::Other(String::from(
+                        "syntax error, MAXLEN and MINID options at the same time are not compatible",
+                    )));
+                }
+                // the threshold may be preceded by `=` or `~`
+                if i + 2 < args.len() {
+                    match parse_option(&args[i + 1])?.as_str() {
+                        "~" => (approx, i) = (true, i + 1),
+                        "=" => i += 1,
+                        _ => {}
+                    }
+                }
+                let threshold = &args[i + 1];
+                strategy = Some(match option {
+                    "maxlen" => match parse_i64(threshold)? {
+                        max if max >= 0 => TrimStrategy::MaxLen(max as u64),
+                        _ => return Err(CommandError::Other(String::from("The MAXLEN argument must be >= 0."))),
+                    },
+                    _ => TrimStrategy::MinId(parse_stream_id(threshold, 0)?),
+                });
+                i += 1;
+            }
+            "limit" if more => {
+                limit = match parse_i64(&args[i + 1])? {
+                    limit if limit >= 0 => Some(limit as usize),
+                    _ => return Err(CommandError::Other(String::from("The LIMIT argument must be >= 0."))),
+                };
+                i += 1;
+            }
+            "nomkstream" if xadd => nomkstream = true,
+            // the ID of the new entry
+            _ if xadd => break,
+            _ => return Err(CommandError::Syntax),
+        }
+        i += 1;
+    }
+
+    let strategy = match strategy {
+        Some(strategy) => strategy,
+        None if limit.is_some() => return Err(CommandError::Other(String::from(
+            "syntax error, LIMIT cannot be used without specifying a trimming strategy",
+        ))),
+        None if !xadd => return Err(CommandError::Other(String::from(
+            "syntax error, XTRIM must be called with a trimming strategy",
+        ))),
+        None => return Ok((None, nomkstream, i)),
+    };
+    let limit = match (approx, limit) {
+        (false, Some(_)) => return Err(CommandError::Other(String::from(
+            "syntax error, LIMIT cannot be used without the special ~ option",
+        ))),
+        (false, None) | (true, Some(0)) => None,
+        (true, Some(limit)) => Some(limit),
+        (true, None) => Some(DEFAULT_APPROX_LIMIT),
+    };
+
+    Ok((Some(StreamTrim { strategy, limit }), nomkstream, i))
+}
@@ -0,0 +1,76 @@
+use bytes::Bytes;
+use crate::cmd::xrange::{entries_reply, ids_reply};
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::{ClaimOptions, StreamId};
+
+/// The number of entries claimed by XAUTOCLAIM without COUNT.
+const DEFAULT_COUNT: usize = 100;
+
+/// Represents the XAUTOCLAIM command.
+#[derive(Debug)]
+pub struct XAutoClaim {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+    start: StreamId,
+    count: usize,
+    /// The minimum idle time and JUSTID.
+    options: ClaimOptions,
+}
+
+impl XAutoClaim {
+    /// Creates a new XAutoClaim instance from the given args.
+    /// Syntax: XAUTOCLAIM key group consumer min-idle-time start [COUNT count] [JUSTID]
+    pub fn with_args(args: Vec<RespType>) -> Result<XAutoClaim, CommandError> {
+        if args.len() < 5 {
+            return Err(CommandError::WrongArity("xautoclaim"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let group = parse_bytes(&args[1])?;
+        let consumer = parse_bytes(&args[2])?;
+        let min_idle = parse_i64(&args[3])
+            .map_err(|_| CommandError::Other(String::from("Invalid min-idle-time argument for XAUTOCLAIM")))?;
+        let start = parse_stream_bound(&args[4], true)?;
+
+        let mut options = ClaimOptions { min_idle: min_idle.max(0) as u64, ..Default::default() };
+        let mut count = DEFAULT_COUNT;
+        let mut i = 5;
+        while i < args.len() {
+            match parse_option(&args[i])?.as_str() {
+                "count" if i + 1 < args.len() => {
+                    // the scan is bounded by ten times the count
+                    count = match parse_i64(&args[i + 1]) {
+                        Ok(count) if (1..=i64::MAX / 10).contains(&count) => count as usize,
+                        _ => return Err(CommandError::Other(String::from("COUNT must be > 0"))),
+                    };
+                    i += 1;
+                }
+                "justid" => options.justid = true,
+                _ => return Err(CommandError::Syntax),
+            }
+            i += 1;
+        }
+
+        Ok(XAutoClaim { key, group, consumer, start, count, options })
+    }
+
+    /// Executes the XAUTOCLAIM command, replying with the ID to continue from, the claimed
+    /// entries, or their IDs with JUSTID, and the IDs of the entries deleted from the stream.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xautoclaim(&self.key, &self.group, &self.consumer, self.start, self.count, &self.options) {
+            Ok((next, claimed, deleted)) => {
+                let claimed = if self.options.justid {
+                    ids_reply(claimed.into_iter().map(|entry| entry.id))
+                } else {
+                    entries_reply(claimed)
+                };
+                RespType::Array(vec![RespType::BulkString(Bytes::from(next.to_string())), claimed, ids_reply(deleted)])
+            }
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use bytes::Bytes;
+use crate::cmd::xrange::{entries_reply, ids_reply};
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_id};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::{ClaimOptions, StreamId};
+use crate::utils::now_ms;
+
+/// Represents the XCLAIM command.
+#[derive(Debug)]
+pub struct XClaim {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+    ids: Vec<StreamId>,
+    options: ClaimOptions,
+}
+
+impl XClaim {
+    /// Creates a new XClaim instance from the given args.
+    /// Syntax: XCLAIM key group consumer min-idle-time id [id ...] [IDLE ms]
+    /// [TIME unix-time-milliseconds] [RETRYCOUNT count] [FORCE] [JUSTID] [LASTID lastid]
+    pub fn with_args(args: Vec<RespType>) -> Result<XClaim, CommandError> {
+        if args.len() < 5 {
+            return Err(CommandError::WrongArity("xclaim"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let group = parse_bytes(&args[1])?;
+        let consumer = parse_bytes(&args[2])?;
+        let min_idle = parse_i64(&args[3])
+            .map_err(|_| CommandError::Other(String::from("Invalid min-idle-time argument for XCLAIM")))?;
+        let mut options = ClaimOptions { min_idle: min_idle.max(0) as u64, ..Default::default() };
+
+        // the IDs go on until the first argument which isn't one
+        let mut ids = Vec::new();
+        let mut i = 4;
+        while let Some(Ok(id)) = args.get(i).map(|arg| parse_stream_id(arg, 0)) {
+            ids.push(id);
+            i += 1;
+        }
+
+        while i < args.len() {
+            let option = parse_option(&args[i])?;
+            let more = i + 1 < args.len();
+            let invalid = |name: &str| CommandError::Other(format!("Invalid {} option argument for XCLAIM", name));
+            match option.as_str() {
+                "force" => options.force = true,
+                "justid" => options.justid = true,
+                "idle" if more => {
+                    let idle = parse_i64(&args[i + 1]).map_err(|_| invalid("IDLE"))?;
+                    // a negative or too long idle time delivers now
+                    let now = now_ms();
+                    options.delivery_time = Some(u64::try_from(idle).ok().and_then(|idle| now.checked_sub(idle)).unwrap_or(now));
+                    i += 1;
+                }
+                "time" if more => {
+                    let time = parse_i64(&args[i + 1]).map_err(|_| invalid("TIME"))?;
+                    options.delivery_time = Some(u64::try_from(time).unwrap_or_else(|_| now_ms()));
+                    i += 1;
+                }
+                "retrycount" if more => {
+                    let count = parse_i64(&args[i + 1]).map_err(|_| invalid("RETRYCOUNT"))?;
+                    options.retry_count = Some(count.max(0) as u64);
+                    i += 1;
+                }
+                "lastid" if more => {
+                    options.last_id = Some(parse_stream_id(&args[i + 1], 0)?);
+                    i += 1;
+                }
+                _ => {
+                    return Err(CommandError::Other(format!(
+                        "Unrecognized XCLAIM option '{}'",
+                        String::from_utf8_lossy(&parse_bytes(&args[i])?),
+                    )))
+                }
+            }
+            i += 1;
+        }
+
+        Ok(XClaim { key, group, consumer, ids, options })
+    }
+
+    /// Executes the XCLAIM command, replying with the claimed entries, or their IDs with
+    /// JUSTID.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xclaim(&self.key, &self.group, &self.consumer, &self.ids, &self.options) {
+            Ok(claimed) if self.options.justid => ids_reply(claimed.into_iter().map(|entry| entry.id)),
+            Ok(claimed) => entries_reply(claimed),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
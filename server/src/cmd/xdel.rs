@@ -0,0 +1,36 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_stream_id};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::StreamId;
+
+/// Represents the XDEL command.
+#[derive(Debug)]
+pub struct XDel {
+    key: Bytes,
+    ids: Vec<StreamId>,
+}
+
+impl XDel {
+    /// Creates a new XDel instance from the given args.
+    /// Syntax: XDEL key id [id ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<XDel, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("xdel"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let ids = args[1..].iter().map(|arg| parse_stream_id(arg, 0)).collect::<Result<_, _>>()?;
+
+        Ok(XDel { key, ids })
+    }
+
+    /// Executes the XDEL command, replying with the number of deleted entries.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xdel(&self.key, &self.ids) {
+            Ok(deleted) => RespType::Integer(deleted as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_id};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::StreamId;
+
+/// Represents the XGROUP command and its subcommands, which manage the consumer groups
+/// of a stream.
+#[derive(Debug)]
+pub struct XGroup {
+    key: Bytes,
+    group: Bytes,
+    op: XGroupOp,
+}
+
+/// The subcommands of XGROUP.
+#[derive(Debug)]
+enum XGroupOp {
+    /// Create the group. `None` IDs stand for `$`, the last ID of the stream.
+    Create { id: Option<StreamId>, mkstream: bool, entries_read: Option<u64> },
+    Destroy,
+    /// Set the ID of the last entry delivered to the group.
+    SetId { id: Option<StreamId>, entries_read: Option<u64> },
+    CreateConsumer(Bytes),
+    DelConsumer(Bytes),
+}
+
+impl XGroup {
+    /// Creates a new XGroup instance from the given args.
+    /// Syntax: XGROUP CREATE key group id | $ [MKSTREAM] [ENTRIESREAD entries-read]
+    ///         XGROUP DESTROY key group
+    ///         XGROUP SETID key group id | $ [ENTRIESREAD entries-read]
+    ///         XGROUP CREATECONSUMER key group consumer
+    ///         XGROUP DELCONSUMER key group consumer
+    pub fn with_args(args: Vec<RespType>) -> Result<XGroup, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("xgroup"));
+        }
+
+        let subcommand = parse_option(&args[0])?;
+        let (name, arity) = match subcommand.as_str() {
+            "create" => ("xgroup|create", 4..=7),
+            "destroy" => ("xgroup|destroy", 3..=3),
+            "setid" => ("xgroup|setid", 4..=6),
+            "createconsumer" => ("xgroup|createconsumer", 4..=4),
+            "delconsumer" => ("xgroup|delconsumer", 4..=4),
+            _ => return Err(CommandError::Other(format!("unknown subcommand '{}'", subcommand))),
+        };
+        if !arity.contains(&args.len()) {
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[1])?;
+        let group = parse_bytes(&args[2])?;
+        let op = match subcommand.as_str() {
+            "create" => {
+                let id = parse_group_id(&args[3])?;
+                let (mut mkstream, mut entries_read) = (false, None);
+                let mut i = 4;
+                while i < args.len() {
+                    match parse_option(&args[i])?.as_str() {
+                        "mkstream" => mkstream = true,
+                        "entriesread" if i + 1 < args.len() => {
+                            entries_read = parse_entries_read(&args[i + 1])?;
+                            i += 1;
+                        }
+                        _ => return Err(CommandError::Syntax),
+                    }
+                    i += 1;
+                }
+                XGroupOp::Create { id, mkstream, entries_read }
+            }
+            "destroy" => XGroupOp::Destroy,
+            "setid" => {
+                let id = parse_group_id(&args[3])?;
+                let entries_read = match &args[4..] {
+                    [] => None,
+                    [option, value] if parse_option(option)? == "entriesread" => parse_entries_read(value)?,
+                    _ => return Err(CommandError::Syntax),
+                };
+                XGroupOp::SetId { id, entries_read }
+            }
+            "createconsumer" => XGroupOp::CreateConsumer(parse_bytes(&args[3])?),
+            _ => XGroupOp::DelConsumer(parse_bytes(&args[3])?),
+        };
+
+        Ok(XGroup { key, group, op })
+    }
+
+    /// Executes the XGROUP command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let (key, group) = (&self.key, &self.group);
+        let result = match &self.op {
+            XGroupOp::Create { id, mkstream, entries_read } => db
+                .xgroup_create(key.clone(), group.clone(), *id, *mkstream, *entries_read)
+                .map(|_| RespType::SimpleString(String::from("OK"))),
+            XGroupOp::Destroy => db
+                .xgroup_destroy(key.clone(), group)
+                .map(|destroyed| RespType::Integer(destroyed as i64)),
+            XGroupOp::SetId { id, entries_read } => db
+                .xgroup_setid(key, group, *id, *entries_read)
+                .map(|_| RespType::SimpleString(String::from("OK"))),
+            XGroupOp::CreateConsumer(consumer) => db
+                .xgroup_createconsumer(key, group, consumer.clone())
+                .map(|created| RespType::Integer(created as i64)),
+            XGroupOp::DelConsumer(consumer) => db
+                .xgroup_delconsumer(key, group, consumer)
+                .map(|pending| RespType::Integer(pending as i64)),
+        };
+
+        match result {
+            Ok(reply) => reply,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the last delivered ID of a group: an ID, or `$` for the last ID of the stream.
+fn parse_group_id(arg: &RespType) -> Result<Option<StreamId>, CommandError> {
+    match parse_bytes(arg)?.as_ref() {
+        b"$" => Ok(None),
+        _ => parse_stream_id(arg, 0).map(Some),
+    }
+}
+
+/// Parses the number of entries read by a group, `-1` when unknown.
+fn parse_entries_read(arg: &RespType) -> Result<Option<u64>, CommandError> {
+    match parse_i64(arg)? {
+        -1 => Ok(None),
+        read if read >= 0 => Ok(Some(read as u64)),
+        _ => Err(CommandError::Other(String::from("value for ENTRIESREAD must be positive or -1"))),
+    }
+}
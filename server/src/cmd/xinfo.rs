@@ -0,0 +1,197 @@
+use bytes::Bytes;
+use crate::cmd::xrange::{entries_reply, entry_reply};
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::{ConsumerInfo, GroupInfo, PendingEntry, StreamId, StreamInfo};
+use crate::utils::now_ms;
+
+/// The number of entries listed by XINFO STREAM FULL without COUNT.
+const DEFAULT_FULL_COUNT: usize = 10;
+
+/// Represents the XINFO command and its subcommands.
+#[derive(Debug)]
+pub struct XInfo {
+    key: Bytes,
+    kind: XInfoKind,
+}
+
+/// The subcommands of XINFO.
+#[derive(Debug)]
+enum XInfoKind {
+    /// Information about the stream. With FULL, up to this many entries and pending
+    /// entries are listed too.
+    Stream { full: Option<usize> },
+    Groups,
+    Consumers(Bytes),
+}
+
+impl XInfo {
+    /// Creates a new XInfo instance from the given args.
+    /// Syntax: XINFO STREAM key [FULL [COUNT count]]
+    ///         XINFO GROUPS key
+    ///         XINFO CONSUMERS key group
+    pub fn with_args(args: Vec<RespType>) -> Result<XInfo, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity("xinfo"));
+        }
+
+        let subcommand = parse_option(&args[0])?;
+        let (name, arity) = match subcommand.as_str() {
+            "stream" => ("xinfo|stream", 2..=5),
+            "groups" => ("xinfo|groups", 2..=2),
+            "consumers" => ("xinfo|consumers", 3..=3),
+            _ => return Err(CommandError::Other(format!("unknown subcommand '{}'", subcommand))),
+        };
+        if !arity.contains(&args.len()) {
+            return Err(CommandError::WrongArity(name));
+        }
+
+        let key = parse_bytes(&args[1])?;
+        let kind = match subcommand.as_str() {
+            "stream" => {
+                let full = match &args[2..] {
+                    [] => None,
+                    [full] if parse_option(full)? == "full" => Some(DEFAULT_FULL_COUNT),
+                    [full, count, n] if parse_option(full)? == "full" && parse_option(count)? == "count" => {
+                        // zero lists everything
+                        match parse_i64(n)? {
+                            0 => Some(usize::MAX),
+                            n if n > 0 => Some(n as usize),
+                            _ => Some(DEFAULT_FULL_COUNT),
+                        }
+                    }
+                    _ => return Err(CommandError::Syntax),
+                };
+                XInfoKind::Stream { full }
+            }
+            "groups" => XInfoKind::Groups,
+            _ => XInfoKind::Consumers(parse_bytes(&args[2])?),
+        };
+
+        Ok(XInfo { key, kind })
+    }
+
+    /// Executes the XINFO command, replying with a map for the stream, or an array of maps
+    /// for the groups and the consumers.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let result = match &self.kind {
+            XInfoKind::Stream { full } => db.xinfo_stream(&self.key, *full).map(|info| stream_reply(info, full.is_some())),
+            XInfoKind::Groups => db
+                .xinfo_groups(&self.key)
+                .map(|groups| RespType::Array(groups.into_iter().map(group_reply).collect())),
+            XInfoKind::Consumers(group) => db.xinfo_consumers(&self.key, group).map(|consumers| {
+                let now = now_ms();
+                RespType::Array(consumers.into_iter().map(|consumer| consumer_reply(consumer, now)).collect())
+            }),
+        };
+
+        match result {
+            Ok(reply) => reply,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// The reply of XINFO STREAM, with the entries and the details of the groups for FULL.
+fn stream_reply(info: StreamInfo, full: bool) -> RespType {
+    let mut fields = vec![
+        field("length", RespType::Integer(info.length as i64)),
+        field("last-generated-id", id_reply(info.last_id)),
+        field("max-deleted-entry-id", id_reply(info.max_deleted_id)),
+        field("entries-added", RespType::Integer(info.entries_added as i64)),
+        field("recorded-first-entry-id", id_reply(info.first_id)),
+    ];
+    if full {
+        fields.push(field("entries", entries_reply(info.entries)));
+        fields.push(field("groups", RespType::Array(info.groups.into_iter().map(full_group_reply).collect())));
+    } else {
+        fields.push(field("groups", RespType::Integer(info.groups.len() as i64)));
+        fields.push(field("first-entry", info.first_entry.map_or(RespType::Null, entry_reply)));
+        fields.push(field("last-entry", info.last_entry.map_or(RespType::Null, entry_reply)));
+    }
+    RespType::Map(fields)
+}
+
+/// The reply of a group, for XINFO GROUPS.
+fn group_reply(group: GroupInfo) -> RespType {
+    RespType::Map(vec![
+        field("name", RespType::BulkString(group.name)),
+        field("consumers", RespType::Integer(group.consumers.len() as i64)),
+        field("pending", RespType::Integer(group.pending_count as i64)),
+        field("last-delivered-id", id_reply(group.last_id)),
+        field("entries-read", count_reply(group.entries_read)),
+        field("lag", count_reply(group.lag)),
+    ])
+}
+
+/// The reply of a group with its pending entries and consumers, for XINFO STREAM FULL.
+fn full_group_reply(group: GroupInfo) -> RespType {
+    let pending = group.pending.into_iter().map(|(id, pending)| {
+        RespType::Array(vec![
+            id_reply(id),
+            RespType::BulkString(pending.consumer),
+            RespType::Integer(pending.delivery_time as i64),
+            RespType::Integer(pending.delivery_count as i64),
+        ])
+    });
+    let consumers = group.consumers.into_iter().map(|consumer| {
+        RespType::Map(vec![
+            field("name", RespType::BulkString(consumer.name)),
+            field("seen-time", RespType::Integer(consumer.seen_time as i64)),
+            field("active-time", time_reply(consumer.active_time)),
+            field("pel-count", RespType::Integer(consumer.pending_count as i64)),
+            field("pending", RespType::Array(consumer.pending.into_iter().map(consumer_pending_reply).collect())),
+        ])
+    });
+
+    RespType::Map(vec![
+        field("name", RespType::BulkString(group.name)),
+        field("last-delivered-id", id_reply(group.last_id)),
+        field("entries-read", count_reply(group.entries_read)),
+        field("lag", count_reply(group.lag)),
+        field("pel-count", RespType::Integer(group.pending_count as i64)),
+        field("pending", RespType::Array(pending.collect())),
+        field("consumers", RespType::Array(consumers.collect())),
+    ])
+}
+
+/// The reply of a consumer, for XINFO CONSUMERS: the milliseconds elapsed since it was
+/// last seen, and since it last read or claimed entries, `-1` if never.
+fn consumer_reply(consumer: ConsumerInfo, now: u64) -> RespType {
+    let inactive = match consumer.active_time {
+        Some(time) => now.saturating_sub(time) as i64,
+        None => -1,
+    };
+    RespType::Map(vec![
+        field("name", RespType::BulkString(consumer.name)),
+        field("pending", RespType::Integer(consumer.pending_count as i64)),
+        field("idle", RespType::Integer(now.saturating_sub(consumer.seen_time) as i64)),
+        field("inactive", RespType::Integer(inactive)),
+    ])
+}
+
+fn consumer_pending_reply((id, pending): (StreamId, PendingEntry)) -> RespType {
+    RespType::Array(vec![
+        id_reply(id),
+        RespType::Integer(pending.delivery_time as i64),
+        RespType::Integer(pending.delivery_count as i64),
+    ])
+}
+
+fn field(name: &'static str, value: RespType) -> (RespType, RespType) {
+    (RespType::BulkString(Bytes::from_static(name.as_bytes())), value)
+}
+
+fn id_reply(id: StreamId) -> RespType {
+    RespType::BulkString(Bytes::from(id.to_string()))
+}
+
+fn count_reply(count: Option<u64>) -> RespType {
+    count.map_or(RespType::Null, |count| RespType::Integer(count as i64))
+}
+
+fn time_reply(time: Option<u64>) -> RespType {
+    RespType::Integer(time.map_or(-1, |time| time as i64))
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the XLEN command.
+#[derive(Debug)]
+pub struct XLen {
+    key: Bytes,
+}
+
+impl XLen {
+    /// Creates a new XLen instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<XLen, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("xlen"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(XLen { key })
+    }
+
+    /// Executes the XLEN command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xlen(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::PendingFilter;
+use crate::utils::now_ms;
+
+/// Represents the XPENDING command.
+#[derive(Debug)]
+pub struct XPending {
+    key: Bytes,
+    group: Bytes,
+    /// Which entries to list, `None` for the summary of the pending entries.
+    filter: Option<PendingFilter>,
+}
+
+impl XPending {
+    /// Creates a new XPending instance from the given args.
+    /// Syntax: XPENDING key group [[IDLE min-idle-time] start end count [consumer]]
+    pub fn with_args(args: Vec<RespType>) -> Result<XPending, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("xpending"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let group = parse_bytes(&args[1])?;
+        if args.len() == 2 {
+            return Ok(XPending { key, group, filter: None });
+        }
+
+        let (min_idle, rest) = match parse_option(&args[2])?.as_str() {
+            "idle" if args.len() >= 4 => (parse_i64(&args[3])?.max(0) as u64, &args[4..]),
+            _ => (0, &args[2..]),
+        };
+        if !(3..=4).contains(&rest.len()) {
+            return Err(CommandError::Syntax);
+        }
+        let filter = PendingFilter {
+            start: parse_stream_bound(&rest[0], true)?,
+            end: parse_stream_bound(&rest[1], false)?,
+            count: parse_i64(&rest[2])?.max(0) as usize,
+            consumer: rest.get(3).map(parse_bytes).transpose()?,
+            min_idle,
+        };
+
+        Ok(XPending { key, group, filter: Some(filter) })
+    }
+
+    /// Executes the XPENDING command. The summary is the number of pending entries, the
+    /// lowest and greatest of their IDs, and the number of entries pending for each
+    /// consumer. Otherwise, each entry is listed with its consumer, the milliseconds
+    /// elapsed since its last delivery, and its number of deliveries.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let filter = match &self.filter {
+            Some(filter) => filter,
+            None => return self.summary(db),
+        };
+
+        let pending = match db.xpending(&self.key, &self.group, filter) {
+            Ok(pending) => pending,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+        let now = now_ms();
+        RespType::Array(
+            pending
+                .into_iter()
+                .map(|(id, pending)| {
+                    RespType::Array(vec![
+                        RespType::BulkString(Bytes::from(id.to_string())),
+                        RespType::BulkString(pending.consumer),
+                        RespType::Integer(now.saturating_sub(pending.delivery_time) as i64),
+                        RespType::Integer(pending.delivery_count as i64),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    fn summary(&self, db: &DB) -> RespType {
+        let summary = match db.xpending_summary(&self.key, &self.group) {
+            Ok(summary) => summary,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+        let (first, last) = match summary.range {
+            Some((first, last)) => (
+                RespType::BulkString(Bytes::from(first.to_string())),
+                RespType::BulkString(Bytes::from(last.to_string())),
+            ),
+            None => (RespType::NullBulkString, RespType::NullBulkString),
+        };
+        let consumers = if summary.consumers.is_empty() {
+            RespType::NullArray
+        } else {
+            RespType::Array(
+                summary
+                    .consumers
+                    .into_iter()
+                    .map(|(name, count)| {
+                        RespType::Array(vec![
+                            RespType::BulkString(name),
+                            RespType::BulkString(Bytes::from(count.to_string())),
+                        ])
+                    })
+                    .collect(),
+            )
+        };
+
+        RespType::Array(vec![RespType::Integer(summary.count as i64), first, last, consumers])
+    }
+}
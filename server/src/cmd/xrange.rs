@@ -0,0 +1,87 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::{StreamEntry, StreamId};
+
+/// Represents the XRANGE and XREVRANGE commands.
+#[derive(Debug)]
+pub struct XRange {
+    key: Bytes,
+    start: StreamId,
+    end: StreamId,
+    /// The maximum number of entries, `None` for all.
+    count: Option<usize>,
+    /// Iterate from the last entry, for XREVRANGE.
+    rev: bool,
+}
+
+impl XRange {
+    /// Creates a new XRange instance from the given args.
+    /// Syntax: XRANGE key start end [COUNT count]
+    ///
+    /// XREVRANGE takes the end first.
+    pub fn with_args(rev: bool, args: Vec<RespType>) -> Result<XRange, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity(if rev { "xrevrange" } else { "xrange" }));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let (start, end) = if rev { (&args[2], &args[1]) } else { (&args[1], &args[2]) };
+        let start = parse_stream_bound(start, true)?;
+        let end = parse_stream_bound(end, false)?;
+
+        let mut count = None;
+        let mut i = 3;
+        while i < args.len() {
+            match parse_option(&args[i])?.as_str() {
+                "count" if i + 1 < args.len() => {
+                    // a negative count returns nothing, like zero
+                    count = Some(parse_i64(&args[i + 1])?.max(0) as usize);
+                    i += 2;
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        Ok(XRange { key, start, end, count, rev })
+    }
+
+    /// Executes the command, replying with the entries in the range.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xrange(&self.key, self.start, self.end, self.count, self.rev) {
+            Ok(Some(_)) if self.count == Some(0) => RespType::NullArray,
+            Ok(entries) => entries_reply(entries.unwrap_or_default()),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// The reply of the stream entries: an array of entries, each being an array of the ID
+/// and the flat array of the fields and their values.
+pub fn entries_reply(entries: Vec<StreamEntry>) -> RespType {
+    RespType::Array(entries.into_iter().map(entry_reply).collect())
+}
+
+/// The reply of a stream entry. Deleted entries, which have no fields, are replied with a
+/// null array instead of the fields.
+pub fn entry_reply(entry: StreamEntry) -> RespType {
+    let fields = if entry.fields.is_empty() {
+        RespType::NullArray
+    } else {
+        RespType::Array(
+            entry
+                .fields
+                .into_iter()
+                .flat_map(|(field, value)| [RespType::BulkString(field), RespType::BulkString(value)])
+                .collect(),
+        )
+    };
+    RespType::Array(vec![RespType::BulkString(Bytes::from(entry.id.to_string())), fields])
+}
+
+/// The reply of the IDs of stream entries, as a flat array.
+pub fn ids_reply(ids: impl IntoIterator<Item = StreamId>) -> RespType {
+    RespType::Array(ids.into_iter().map(|id| RespType::BulkString(Bytes::from(id.to_string()))).collect())
+}
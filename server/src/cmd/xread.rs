@@ -0,0 +1,249 @@
+use bytes::Bytes;
+use std::time::Duration;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::xrange::entries_reply;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option, parse_stream_id};
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::stream::{StreamEntry, StreamId};
+
+/// Represents the XREAD command.
+#[derive(Debug)]
+pub struct XRead {
+    keys: Vec<Bytes>,
+    /// The ID of each stream to read after, `None` for `$`, its last ID.
+    /// With XREADGROUP, `None` stands for `>`, the entries never delivered to the group.
+    ids: Vec<Option<StreamId>>,
+    /// The maximum number of entries per stream, `None` for all.
+    count: Option<usize>,
+    /// Whether BLOCK was given.
+    block: bool,
+    timeout: Option<Duration>,
+}
+
+/// Represents the XREADGROUP command.
+#[derive(Debug)]
+pub struct XReadGroup {
+    group: Bytes,
+    consumer: Bytes,
+    /// Don't add the entries to the pending entries list.
+    noack: bool,
+    read: XRead,
+}
+
+/// The options only taken by XREADGROUP.
+#[derive(Debug, Default)]
+struct GroupOptions {
+    /// The group and the consumer given with GROUP.
+    group: Option<(Bytes, Bytes)>,
+    noack: bool,
+}
+
+impl XRead {
+    /// Creates a new XRead instance from the given args.
+    /// Syntax: XREAD [COUNT count] [BLOCK milliseconds] STREAMS key [key ...] id [id ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<XRead, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("xread"));
+        }
+
+        let (read, _) = parse_args(&args, false)?;
+        Ok(read)
+    }
+
+    /// Checks if the command blocks when no stream has new entries.
+    pub fn is_blocking(&self) -> bool {
+        self.block
+    }
+
+    /// Executes the command without blocking, replying with the new entries of each
+    /// stream having some.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xread(&self.keys, &self.ids, self.count) {
+            Ok(read) if read.is_empty() => RespType::NullArray,
+            Ok(read) => streams_reply(read),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl XReadGroup {
+    /// Creates a new XReadGroup instance from the given args.
+    /// Syntax: XREADGROUP GROUP group consumer [COUNT count] [BLOCK milliseconds] [NOACK]
+    /// STREAMS key [key ...] id [id ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<XReadGroup, CommandError> {
+        if args.len() < 6 {
+            return Err(CommandError::WrongArity("xreadgroup"));
+        }
+
+        let (read, GroupOptions { group, noack }) = parse_args(&args, true)?;
+        let (group, consumer) = group.ok_or_else(|| {
+            CommandError::Other(String::from("Missing GROUP option for XREADGROUP"))
+        })?;
+        Ok(XReadGroup { group, consumer, noack, read })
+    }
+
+    /// Checks if the command blocks when no stream has new entries.
+    pub fn is_blocking(&self) -> bool {
+        self.read.block
+    }
+
+    /// Executes the command without blocking, replying with the entries read from each
+    /// stream, the new ones for `>` and the pending ones of the consumer for other IDs.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let read = &self.read;
+        match db.xreadgroup(&self.group, &self.consumer, &read.keys, &read.ids, read.count, self.noack) {
+            Ok(read) if read.is_empty() => RespType::NullArray,
+            Ok(read) => streams_reply(read),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl Blocking for XRead {
+    fn keys(&self) -> &[Bytes] {
+        &self.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::XRead { ids: self.ids.clone(), count: self.count }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Replies with the stream and its new entries.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        match served {
+            Some(Served { key, entries, .. }) => streams_reply(vec![(key, entries)]),
+            None => RespType::NullArray,
+        }
+    }
+
+    /// Replies right away if some of the streams have new entries.
+    fn ready(&self, db: &DB) -> Option<RespType> {
+        match self.apply(db) {
+            RespType::NullArray => None,
+            reply => Some(reply),
+        }
+    }
+}
+
+impl Blocking for XReadGroup {
+    fn keys(&self) -> &[Bytes] {
+        &self.read.keys
+    }
+
+    fn op(&self) -> BlockingOp {
+        BlockingOp::XReadGroup {
+            group: self.group.clone(),
+            consumer: self.consumer.clone(),
+            count: self.read.count,
+            noack: self.noack,
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.read.timeout
+    }
+
+    /// Replies with the stream and the entries delivered.
+    fn reply(&self, served: Option<Served>) -> RespType {
+        self.read.reply(served)
+    }
+
+    /// Replies right away if some of the streams have new entries, or if the history of
+    /// the consumer is read.
+    fn ready(&self, db: &DB) -> Option<RespType> {
+        match self.apply(db) {
+            RespType::NullArray => None,
+            reply => Some(reply),
+        }
+    }
+}
+
+/// Parses the args of XREAD, or of XREADGROUP when `group` is set.
+fn parse_args(args: &[RespType], group: bool) -> Result<(XRead, GroupOptions), CommandError> {
+    let name = if group { "xreadgroup" } else { "xread" };
+    let (mut count, mut block, mut timeout) = (None, false, None);
+    let mut options = GroupOptions::default();
+    let mut i = 0;
+    let streams = loop {
+        let more = i + 1 < args.len();
+        match args.get(i).map(parse_option).transpose()?.as_deref() {
+            Some("count") if more => {
+                // zero or less reads all the entries
+                count = match parse_i64(&args[i + 1])? {
+                    count if count > 0 => Some(count as usize),
+                    _ => None,
+                };
+                i += 2;
+            }
+            Some("block") if more => {
+                let ms = match parse_i64(&args[i + 1]) {
+                    Ok(ms) if ms < 0 => return Err(CommandError::Other(String::from("timeout is negative"))),
+                    Ok(ms) => ms as u64,
+                    Err(_) => return Err(CommandError::Other(String::from(
+                        "timeout is not an integer or out of range",
+                    ))),
+                };
+                (block, timeout) = (true, (ms > 0).then(|| Duration::from_millis(ms)));
+                i += 2;
+            }
+            Some("group") if i + 2 < args.len() => {
+                if !group {
+                    return Err(CommandError::Other(String::from(
+                        "The GROUP option is only supported by XREADGROUP. You called XREAD instead.",
+                    )));
+                }
+                options.group = Some((parse_bytes(&args[i + 1])?, parse_bytes(&args[i + 2])?));
+                i += 3;
+            }
+            Some("noack") if group => {
+                options.noack = true;
+                i += 1;
+            }
+            Some("streams") => break &args[i + 1..],
+            _ => return Err(CommandError::Syntax),
+        }
+    };
+
+    if streams.is_empty() || !streams.len().is_multiple_of(2) {
+        return Err(CommandError::Other(format!(
+            "Unbalanced '{}' list of streams: for each stream key an ID or '{}' must be specified.",
+            name,
+            if group { ">" } else { "$" },
+        )));
+    }
+    let (keys, ids) = streams.split_at(streams.len() / 2);
+    let keys = keys.iter().map(parse_bytes).collect::<Result<_, _>>()?;
+    let ids = ids
+        .iter()
+        .map(|arg| match (parse_bytes(arg)?.as_ref(), group) {
+            (b"$", false) | (b">", true) => Ok(None),
+            (b"$", true) => Err(CommandError::Other(String::from(
+                "The $ ID is meaningless in the context of XREADGROUP: you want to read the history of \
+                 this consumer by specifying a proper ID, or use the > ID to get new messages. \
+                 The $ ID would just return an empty result set.",
+            ))),
+            (b">", false) => Err(CommandError::Other(String::from(
+                "The > ID can be specified only when calling XREADGROUP using the GROUP <group> <consumer> option.",
+            ))),
+            _ => parse_stream_id(arg, 0).map(Some),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((XRead { keys, ids, count, block, timeout }, options))
+}
+
+/// The reply of XREAD and XREADGROUP: each stream with its entries.
+fn streams_reply(read: Vec<(Bytes, Vec<StreamEntry>)>) -> RespType {
+    RespType::NestedMap(
+        read.into_iter()
+            .map(|(key, entries)| (RespType::BulkString(key), entries_reply(entries)))
+            .collect(),
+    )
+}
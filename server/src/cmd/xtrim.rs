@@ -0,0 +1,40 @@
+use bytes::Bytes;
+use crate::cmd::xadd::parse_trim_options;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::stream::StreamTrim;
+
+/// Represents the XTRIM command.
+#[derive(Debug)]
+pub struct XTrim {
+    key: Bytes,
+    trim: StreamTrim,
+}
+
+impl XTrim {
+    /// Creates a new XTrim instance from the given args.
+    /// Syntax: XTRIM key MAXLEN | MINID [= | ~] threshold [LIMIT count]
+    pub fn with_args(args: Vec<RespType>) -> Result<XTrim, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("xtrim"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let trim = match parse_trim_options(&args[1..], false)? {
+            (Some(trim), _, _) => trim,
+            (None, _, _) => unreachable!("XTRIM requires a trimming strategy"),
+        };
+
+        Ok(XTrim { key, trim })
+    }
+
+    /// Executes the XTRIM command, replying with the number of evicted entries.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.xtrim(&self.key, &self.trim) {
+            Ok(evicted) => RespType::Integer(evicted as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_f64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZAddFlags;
+
+/// Represents the ZADD command.
+#[derive(Debug)]
+pub struct ZAdd {
+    key: Bytes,
+    flags: ZAddFlags,
+    /// Reply with the number of members added or updated, instead of only the added ones.
+    ch: bool,
+    /// Increment the score of the single member like ZINCRBY.
+    incr: bool,
+    pairs: Vec<(f64, Bytes)>,
+}
+
+impl ZAdd {
+    /// Creates a new ZAdd instance from the given args.
+    /// Syntax: ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]
+    pub fn with_args(args: Vec<RespType>) -> Result<ZAdd, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("zadd"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        let mut flags = ZAddFlags::default();
+        let (mut ch, mut incr) = (false, false);
+        let mut i = 1;
+        while i < args.len() {
+            match parse_option(&args[i])?.as_str() {
+                "nx" => flags.nx = true,
+                "xx" => flags.xx = true,
+                "gt" => flags.gt = true,
+                "lt" => flags.lt = true,
+                "ch" => ch = true,
+                "incr" => incr = true,
+                _ => break,
+            }
+            i += 1;
+        }
+
+        let elements = &args[i..];
+        if elements.is_empty() || !elements.len().is_multiple_of(2) {
+            return Err(CommandError::Syntax);
+        }
+        if flags.nx && flags.xx {
+            return Err(CommandError::Other(String::from(
+                "XX and NX options at the same time are not compatible",
+            )));
+        }
+        if flags.nx && (flags.gt || flags.lt) || flags.gt && flags.lt {
+            return Err(CommandError::Other(String::from(
+                "GT, LT, and/or NX options at the same time are not compatible",
+            )));
+        }
+        if incr && elements.len() > 2 {
+            return Err(CommandError::Other(String::from(
+                "INCR option supports a single increment-element pair",
+            )));
+        }
+
+        let pairs = elements
+            .chunks(2)
+            .map(|pair| Ok((parse_f64(&pair[0])?, parse_bytes(&pair[1])?)))
+            .collect::<Result<_, CommandError>>()?;
+
+        Ok(ZAdd { key, flags, ch, incr, pairs })
+    }
+
+    /// Executes the ZADD command, replying with the number of members added.
+    /// With INCR it replies with the new score, or nil if the flags prevented the update.
+    pub fn apply(&self, db: &DB) -> RespType {
+        if self.incr {
+            let (delta, member) = self.pairs[0].clone();
+            return match db.zincrby(self.key.clone(), self.flags, delta, member) {
+                Ok(Some(score)) => RespType::Double(score),
+                Ok(None) => RespType::NullBulkString,
+                Err(e) => RespType::SimpleError(format!("{}", e)),
+            };
+        }
+
+        match db.zadd(self.key.clone(), self.flags, self.pairs.clone()) {
+            Ok((added, changed)) if self.ch => RespType::Integer((added + changed) as i64),
+            Ok((added, _)) => RespType::Integer(added as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the ZCARD command.
+#[derive(Debug)]
+pub struct ZCard {
+    key: Bytes,
+}
+
+impl ZCard {
+    /// Creates a new ZCard instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<ZCard, CommandError> {
+        if args.len() != 1 {
+            return Err(CommandError::WrongArity("zcard"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+
+        Ok(ZCard { key })
+    }
+
+    /// Executes the ZCARD command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zcard(&self.key) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
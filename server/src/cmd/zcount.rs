@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_score_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ScoreBound;
+
+/// Represents the ZCOUNT command.
+#[derive(Debug)]
+pub struct ZCount {
+    key: Bytes,
+    min: ScoreBound,
+    max: ScoreBound,
+}
+
+impl ZCount {
+    /// Creates a new ZCount instance from the given args.
+    /// Syntax: ZCOUNT key min max
+    pub fn with_args(args: Vec<RespType>) -> Result<ZCount, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("zcount"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let min = parse_score_bound(&args[1])?;
+        let max = parse_score_bound(&args[2])?;
+
+        Ok(ZCount { key, min, max })
+    }
+
+    /// Executes the ZCOUNT command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zcount(&self.key, self.min, self.max) {
+            Ok(count) => RespType::Integer(count as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_f64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZAddFlags;
+
+/// Represents the ZINCRBY command.
+#[derive(Debug)]
+pub struct ZIncrBy {
+    key: Bytes,
+    delta: f64,
+    member: Bytes,
+}
+
+impl ZIncrBy {
+    /// Creates a new ZIncrBy instance from the given args.
+    /// Syntax: ZINCRBY key increment member
+    pub fn with_args(args: Vec<RespType>) -> Result<ZIncrBy, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity("zincrby"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let delta = parse_f64(&args[1])?;
+        let member = parse_bytes(&args[2])?;
+
+        Ok(ZIncrBy { key, delta, member })
+    }
+
+    /// Executes the ZINCRBY command, replying with the new score.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zincrby(self.key.clone(), ZAddFlags::default(), self.delta, self.member.clone()) {
+            Ok(Some(score)) => RespType::Double(score),
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::set::SetOp;
+use crate::storage::sorted_set::Aggregate;
+use crate::utils;
+
+/// Represents the ZINTER, ZUNION and ZDIFF commands.
+#[derive(Debug)]
+pub struct ZInter {
+    op: SetOp,
+    keys: Vec<Bytes>,
+    /// The weight of each key, 1 unless given.
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+    with_scores: bool,
+}
+
+/// Represents the ZINTERSTORE, ZUNIONSTORE and ZDIFFSTORE commands.
+#[derive(Debug)]
+pub struct ZInterStore {
+    dest: Bytes,
+    inter: ZInter,
+}
+
+/// The lowercase name of the command combining sorted sets with `op`.
+fn name(op: SetOp, store: bool) -> &'static str {
+    match (op, store) {
+        (SetOp::Inter, false) => "zinter",
+        (SetOp::Union, false) => "zunion",
+        (SetOp::Diff, false) => "zdiff",
+        (SetOp::Inter, true) => "zinterstore",
+        (SetOp::Union, true) => "zunionstore",
+        (SetOp::Diff, true) => "zdiffstore",
+    }
+}
+
+impl ZInter {
+    /// Creates a new ZInter instance from the given args.
+    /// Syntax: ZINTER numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    /// [AGGREGATE SUM | MIN | MAX] [WITHSCORES]
+    ///
+    /// ZDIFF takes neither WEIGHTS nor AGGREGATE.
+    pub fn with_args(op: SetOp, args: Vec<RespType>) -> Result<ZInter, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(name(op, false)));
+        }
+
+        parse_args(op, &args, false)
+    }
+
+    /// Executes the command, replying with the members of the resulting sorted set,
+    /// along with their scores with WITHSCORES.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zset_op(self.op, &self.keys, &self.weights, self.aggregate) {
+            Ok(members) if self.with_scores => RespType::Pairs(
+                members
+                    .into_iter()
+                    .map(|(member, score)| (RespType::BulkString(member), RespType::Double(score)))
+                    .collect(),
+            ),
+            Ok(members) => {
+                RespType::Array(members.into_iter().map(|(member, _)| RespType::BulkString(member)).collect())
+            }
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl ZInterStore {
+    /// Creates a new ZInterStore instance from the given args.
+    /// Syntax: ZINTERSTORE destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    /// [AGGREGATE SUM | MIN | MAX]
+    pub fn with_args(op: SetOp, args: Vec<RespType>) -> Result<ZInterStore, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity(name(op, true)));
+        }
+
+        let dest = parse_bytes(&args[0])?;
+        let inter = parse_args(op, &args[1..], true)?;
+
+        Ok(ZInterStore { dest, inter })
+    }
+
+    /// Executes the command, replying with the size of the stored sorted set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let ZInter { op, keys, weights, aggregate, .. } = &self.inter;
+        match db.zset_op_store(*op, self.dest.clone(), keys, weights, *aggregate) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the args starting at numkeys. The STORE variants don't take WITHSCORES.
+fn parse_args(op: SetOp, args: &[RespType], store: bool) -> Result<ZInter, CommandError> {
+    let numkeys = match parse_i64(&args[0])? {
+        numkeys if numkeys > 0 => numkeys as usize,
+        _ => return Err(CommandError::Other(format!(
+            "at least 1 input key is needed for '{}' command",
+            name(op, store),
+        ))),
+    };
+    if numkeys > args.len() - 1 {
+        return Err(CommandError::Syntax);
+    }
+    let keys = args[1..=numkeys].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+    let mut weights = vec![1.0; numkeys];
+    let mut aggregate = Aggregate::default();
+    let mut with_scores = false;
+    let mut i = numkeys + 1;
+    while i < args.len() {
+        let remaining = args.len() - i - 1;
+        match parse_option(&args[i])?.as_str() {
+            "weights" if !matches!(op, SetOp::Diff) && remaining >= numkeys => {
+                for (weight, arg) in weights.iter_mut().zip(&args[i + 1..]) {
+                    *weight = match arg {
+                        RespType::BulkString(s) => utils::parse_f64(s),
+                        _ => return Err(CommandError::InvalidFormat),
+                    }
+                    .ok_or_else(|| CommandError::Other(String::from("weight value is not a float")))?;
+                }
+                i += numkeys;
+            }
+            "aggregate" if !matches!(op, SetOp::Diff) && remaining >= 1 => {
+                aggregate = match parse_option(&args[i + 1])?.as_str() {
+                    "sum" => Aggregate::Sum,
+                    "min" => Aggregate::Min,
+                    "max" => Aggregate::Max,
+                    _ => return Err(CommandError::Syntax),
+                };
+                i += 1;
+            }
+            "withscores" if !store => with_scores = true,
+            _ => return Err(CommandError::Syntax),
+        }
+        i += 1;
+    }
+
+    Ok(ZInter { op, keys, weights, aggregate, with_scores })
+}
@@ -0,0 +1,60 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the ZINTERCARD command.
+#[derive(Debug)]
+pub struct ZInterCard {
+    keys: Vec<Bytes>,
+    /// The count stops at the limit, zero means no limit.
+    limit: usize,
+}
+
+impl ZInterCard {
+    /// Creates a new ZInterCard instance from the given args.
+    /// Syntax: ZINTERCARD numkeys key [key ...] [LIMIT limit]
+    pub fn with_args(args: Vec<RespType>) -> Result<ZInterCard, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("zintercard"));
+        }
+
+        let numkeys = match parse_i64(&args[0]) {
+            Ok(numkeys) if numkeys > 0 => numkeys as usize,
+            _ => return Err(CommandError::Other(String::from("numkeys should be greater than 0"))),
+        };
+        if numkeys > args.len() - 1 {
+            return Err(CommandError::Other(String::from(
+                "Number of keys can't be greater than number of args",
+            )));
+        }
+        let keys = args[1..=numkeys].iter().map(parse_bytes).collect::<Result<_, _>>()?;
+
+        let mut limit = 0;
+        let mut i = numkeys + 1;
+        while i < args.len() {
+            match parse_option(&args[i])?.as_str() {
+                "limit" if i + 1 < args.len() => {
+                    limit = match parse_i64(&args[i + 1]) {
+                        Ok(limit) if limit >= 0 => limit as usize,
+                        Ok(_) => return Err(CommandError::Other(String::from("LIMIT can't be negative"))),
+                        Err(e) => return Err(e),
+                    };
+                    i += 2;
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        Ok(ZInterCard { keys, limit })
+    }
+
+    /// Executes the ZINTERCARD command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zintercard(&self.keys, self.limit) {
+            Ok(count) => RespType::Integer(count as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use bytes::Bytes;
+use crate::cmd::blmpop::parse_mpop_args;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_zset_end;
+use crate::resp::types::RespType;
+use crate::storage::blocking::{BlockingOp, Served};
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZSetEnd;
+
+/// Represents the ZMPOP command.
+#[derive(Debug)]
+pub struct ZMPop {
+    keys: Vec<Bytes>,
+    end: ZSetEnd,
+    count: usize,
+}
+
+impl ZMPop {
+    /// Creates a new ZMPop instance from the given args.
+    /// Syntax: ZMPOP numkeys key [key ...] MIN | MAX [COUNT count]
+    pub fn with_args(args: Vec<RespType>) -> Result<ZMPop, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("zmpop"));
+        }
+
+        let (keys, end, count) = parse_mpop_args(&args, parse_zset_end)?;
+
+        Ok(ZMPop { keys, end, count })
+    }
+
+    /// Executes the command, popping from the first non empty sorted set.
+    pub fn apply(&self, db: &DB) -> RespType {
+        let op = BlockingOp::ZPop { end: self.end, count: self.count };
+
+        match db.pop_first(&self.keys, &op) {
+            Ok(served) => reply(served),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// The reply of ZMPOP and BZMPOP: the key and the popped members with their scores.
+pub fn reply(served: Option<Served>) -> RespType {
+    match served {
+        Some(Served { key, elems, scores, .. }) => RespType::Array(vec![
+            RespType::BulkString(key),
+            RespType::Array(
+                elems
+                    .into_iter()
+                    .zip(scores)
+                    .map(|(member, score)| {
+                        RespType::Array(vec![RespType::BulkString(member), RespType::Double(score)])
+                    })
+                    .collect(),
+            ),
+        ]),
+        None => RespType::NullArray,
+    }
+}
@@ -0,0 +1,62 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZSetEnd;
+
+/// Represents the ZPOPMIN and ZPOPMAX commands.
+#[derive(Debug)]
+pub struct ZPopMin {
+    key: Bytes,
+    end: ZSetEnd,
+    /// The number of members to pop, `None` to pop one without nesting the reply.
+    count: Option<usize>,
+}
+
+impl ZPopMin {
+    /// Creates a new ZPopMin instance popping from the given end of the sorted set.
+    /// Syntax: ZPOPMIN key [count]
+    pub fn with_args(end: ZSetEnd, args: Vec<RespType>) -> Result<ZPopMin, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::WrongArity(match end {
+                ZSetEnd::Min => "zpopmin",
+                ZSetEnd::Max => "zpopmax",
+            }));
+        }
+        if args.len() > 2 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let count = match args.get(1).map(parse_i64) {
+            Some(Ok(count)) if count >= 0 => Some(count as usize),
+            Some(Ok(_)) => return Err(CommandError::Other(String::from(
+                "value is out of range, must be positive",
+            ))),
+            Some(Err(e)) => return Err(e),
+            None => None,
+        };
+
+        Ok(ZPopMin { key, end, count })
+    }
+
+    /// Executes the command, replying with the popped members and their scores.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zpop(&self.key, self.end, self.count.unwrap_or(1)) {
+            Ok(members) if self.count.is_some() => RespType::Pairs(
+                members
+                    .into_iter()
+                    .map(|(member, score)| (RespType::BulkString(member), RespType::Double(score)))
+                    .collect(),
+            ),
+            Ok(members) => RespType::Array(
+                members
+                    .into_iter()
+                    .flat_map(|(member, score)| [RespType::BulkString(member), RespType::Double(score)])
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
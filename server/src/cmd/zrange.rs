@@ -0,0 +1,92 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_lex_bound, parse_option, parse_score_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::{ZRange as Range, ZRangeBy};
+
+/// Represents the ZRANGE command.
+#[derive(Debug)]
+pub struct ZRange {
+    key: Bytes,
+    range: Range,
+    with_scores: bool,
+}
+
+impl ZRange {
+    /// Creates a new ZRange instance from the given args.
+    /// Syntax: ZRANGE key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]
+    pub fn with_args(args: Vec<RespType>) -> Result<ZRange, CommandError> {
+        if args.len() < 3 {
+            return Err(CommandError::WrongArity("zrange"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let (range, with_scores) = parse_range_args(&args[1..], true)?;
+
+        Ok(ZRange { key, range, with_scores })
+    }
+
+    /// Executes the ZRANGE command, replying with the members, along with their scores
+    /// with WITHSCORES.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zrange(&self.key, &self.range) {
+            Ok(members) if self.with_scores => RespType::Pairs(
+                members
+                    .into_iter()
+                    .map(|(member, score)| (RespType::BulkString(member), RespType::Double(score)))
+                    .collect(),
+            ),
+            Ok(members) => {
+                RespType::Array(members.into_iter().map(|(member, _)| RespType::BulkString(member)).collect())
+            }
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+/// Parses the args of ZRANGE following the key: `start stop [BYSCORE | BYLEX] [REV]
+/// [LIMIT offset count]`, and `[WITHSCORES]` if allowed. Returns the range and whether
+/// WITHSCORES was given.
+pub fn parse_range_args(args: &[RespType], allow_scores: bool) -> Result<(Range, bool), CommandError> {
+    let (mut by_score, mut by_lex, mut rev, mut with_scores) = (false, false, false, false);
+    let mut limit = None;
+    let mut i = 2;
+    while i < args.len() {
+        match parse_option(&args[i])?.as_str() {
+            "byscore" => (by_score, by_lex) = (true, false),
+            "bylex" => (by_score, by_lex) = (false, true),
+            "rev" => rev = true,
+            "withscores" if allow_scores => with_scores = true,
+            "limit" if i + 2 < args.len() => {
+                limit = Some((parse_i64(&args[i + 1])?, parse_i64(&args[i + 2])?));
+                i += 2;
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+        i += 1;
+    }
+
+    if limit.is_some() && !by_score && !by_lex {
+        return Err(CommandError::Other(String::from(
+            "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX",
+        )));
+    }
+    if with_scores && by_lex {
+        return Err(CommandError::Other(String::from(
+            "syntax error, WITHSCORES not supported in combination with BYLEX",
+        )));
+    }
+
+    // with REV, score and lex ranges are given from the highest to the lowest
+    let (min, max) = if rev && (by_score || by_lex) { (&args[1], &args[0]) } else { (&args[0], &args[1]) };
+    let by = if by_score {
+        ZRangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?)
+    } else if by_lex {
+        ZRangeBy::Lex(parse_lex_bound(min)?, parse_lex_bound(max)?)
+    } else {
+        ZRangeBy::Rank(parse_i64(min)?, parse_i64(max)?)
+    };
+
+    Ok((Range { by, rev, limit }, with_scores))
+}
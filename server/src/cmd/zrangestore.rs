@@ -0,0 +1,39 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::parse_bytes;
+use crate::cmd::zrange::parse_range_args;
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::ZRange;
+
+/// Represents the ZRANGESTORE command.
+#[derive(Debug)]
+pub struct ZRangeStore {
+    dest: Bytes,
+    src: Bytes,
+    range: ZRange,
+}
+
+impl ZRangeStore {
+    /// Creates a new ZRangeStore instance from the given args.
+    /// Syntax: ZRANGESTORE dst src min max [BYSCORE | BYLEX] [REV] [LIMIT offset count]
+    pub fn with_args(args: Vec<RespType>) -> Result<ZRangeStore, CommandError> {
+        if args.len() < 4 {
+            return Err(CommandError::WrongArity("zrangestore"));
+        }
+
+        let dest = parse_bytes(&args[0])?;
+        let src = parse_bytes(&args[1])?;
+        let (range, _) = parse_range_args(&args[2..], false)?;
+
+        Ok(ZRangeStore { dest, src, range })
+    }
+
+    /// Executes the command, replying with the number of members stored.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zrangestore(self.dest.clone(), &self.src, &self.range) {
+            Ok(len) => RespType::Integer(len as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
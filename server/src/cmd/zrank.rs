@@ -0,0 +1,51 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_option};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the ZRANK and ZREVRANK commands.
+#[derive(Debug)]
+pub struct ZRank {
+    key: Bytes,
+    member: Bytes,
+    /// Rank from the highest score, for ZREVRANK.
+    rev: bool,
+    with_score: bool,
+}
+
+impl ZRank {
+    /// Creates a new ZRank instance from the given args.
+    /// Syntax: ZRANK key member [WITHSCORE]
+    pub fn with_args(rev: bool, args: Vec<RespType>) -> Result<ZRank, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity(if rev { "zrevrank" } else { "zrank" }));
+        }
+        if args.len() > 3 {
+            return Err(CommandError::Syntax);
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let member = parse_bytes(&args[1])?;
+        let with_score = match args.get(2) {
+            Some(arg) if parse_option(arg)? == "withscore" => true,
+            Some(_) => return Err(CommandError::Syntax),
+            None => false,
+        };
+
+        Ok(ZRank { key, member, rev, with_score })
+    }
+
+    /// Executes the command, replying with the rank, along with the score with WITHSCORE.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zrank(&self.key, &self.member, self.rev) {
+            Ok(Some((rank, score))) if self.with_score => {
+                RespType::Array(vec![RespType::Integer(rank as i64), RespType::Double(score)])
+            }
+            Ok(Some((rank, _))) => RespType::Integer(rank as i64),
+            Ok(None) if self.with_score => RespType::NullArray,
+            Ok(None) => RespType::NullBulkString,
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_i64, parse_lex_bound, parse_score_bound};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+use crate::storage::sorted_set::{ZRange, ZRangeBy};
+
+/// Represents the ZREMRANGEBYRANK, ZREMRANGEBYSCORE and ZREMRANGEBYLEX commands.
+#[derive(Debug)]
+pub struct ZRemRange {
+    key: Bytes,
+    range: ZRange,
+}
+
+/// The variants of the ZREMRANGE commands.
+#[derive(Debug, Clone, Copy)]
+pub enum ZRemRangeKind {
+    /// Members between two ranks.
+    Rank,
+    /// Members with a score between two bounds.
+    Score,
+    /// Members between two lexicographical bounds.
+    Lex,
+}
+
+impl ZRemRangeKind {
+    /// The lowercase name of the command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ZRemRangeKind::Rank => "zremrangebyrank",
+            ZRemRangeKind::Score => "zremrangebyscore",
+            ZRemRangeKind::Lex => "zremrangebylex",
+        }
+    }
+}
+
+impl ZRemRange {
+    /// Creates a new ZRemRange instance from the given args.
+    /// Syntax: ZREMRANGEBYRANK key start stop
+    pub fn with_args(kind: ZRemRangeKind, args: Vec<RespType>) -> Result<ZRemRange, CommandError> {
+        if args.len() != 3 {
+            return Err(CommandError::WrongArity(kind.name()));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let by = match kind {
+            ZRemRangeKind::Rank => ZRangeBy::Rank(parse_i64(&args[1])?, parse_i64(&args[2])?),
+            ZRemRangeKind::Score => ZRangeBy::Score(parse_score_bound(&args[1])?, parse_score_bound(&args[2])?),
+            ZRemRangeKind::Lex => ZRangeBy::Lex(parse_lex_bound(&args[1])?, parse_lex_bound(&args[2])?),
+        };
+
+        Ok(ZRemRange { key, range: ZRange { by, rev: false, limit: None } })
+    }
+
+    /// Executes the command, replying with the number of removed members.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zremrange(&self.key, &self.range) {
+            Ok(removed) => RespType::Integer(removed as i64),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
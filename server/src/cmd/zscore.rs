@@ -0,0 +1,74 @@
+use bytes::Bytes;
+use crate::cmd::CommandError;
+use crate::cmd::utils::{parse_bytes, parse_values};
+use crate::resp::types::RespType;
+use crate::storage::db::DB;
+
+/// Represents the ZSCORE command.
+#[derive(Debug)]
+pub struct ZScore {
+    key: Bytes,
+    member: [Bytes; 1],
+}
+
+/// Represents the ZMSCORE command.
+#[derive(Debug)]
+pub struct ZMScore {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl ZScore {
+    /// Creates a new ZScore instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<ZScore, CommandError> {
+        if args.len() != 2 {
+            return Err(CommandError::WrongArity("zscore"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let member = parse_bytes(&args[1])?;
+
+        Ok(ZScore { key, member: [member] })
+    }
+
+    /// Executes the ZSCORE command.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zmscore(&self.key, &self.member) {
+            Ok(mut scores) => match scores.pop().flatten() {
+                Some(score) => RespType::Double(score),
+                None => RespType::NullBulkString,
+            },
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
+
+impl ZMScore {
+    /// Creates a new ZMScore instance from the given args.
+    pub fn with_args(args: Vec<RespType>) -> Result<ZMScore, CommandError> {
+        if args.len() < 2 {
+            return Err(CommandError::WrongArity("zmscore"));
+        }
+
+        let key = parse_bytes(&args[0])?;
+        let members = parse_values(args)?;
+
+        Ok(ZMScore { key, members })
+    }
+
+    /// Executes the ZMSCORE command. Missing members are replied as nil.
+    pub fn apply(&self, db: &DB) -> RespType {
+        match db.zmscore(&self.key, &self.members) {
+            Ok(scores) => RespType::Array(
+                scores
+                    .into_iter()
+                    .map(|score| match score {
+                        Some(score) => RespType::Double(score),
+                        None => RespType::NullBulkString,
+                    })
+                    .collect(),
+            ),
+            Err(e) => RespType::SimpleError(format!("{}", e)),
+        }
+    }
+}
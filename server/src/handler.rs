@@ -1,11 +1,16 @@
-use crate::cmd::tx::Transaction;
+use crate::cmd::blocking::Blocking;
+use crate::cmd::tx::{Transaction, TransactionError};
 use crate::cmd::Command;
 use crate::resp::frame::RespCommandFrame;
 use crate::resp::types::RespType;
+use crate::resp::ProtocolVersion;
+use crate::storage::blocking::BlockingPop;
 use crate::storage::db::DB;
 use anyhow::Result;
+use bytes::BytesMut;
 use futures::{SinkExt, StreamExt};
-use log::error;
+use log::{debug, error};
+use tokio::io::AsyncReadExt;
 use tokio::net::TcpStream;
 use tokio_util::codec::Framed;
 
@@ -13,12 +18,27 @@ use tokio_util::codec::Framed;
 pub struct FrameHandler {
     /// The framed connection using `RespCommandFrame` as the codec.
     conn: Framed<TcpStream, RespCommandFrame>,
+    /// The unique id of the connection, reported by HELLO.
+    id: u64,
+    /// The protocol version negotiated with the client.
+    protocol: ProtocolVersion,
 }
 
 impl FrameHandler {
-    /// Create a new `FrameHandler` with the given connection.
-    pub fn new(conn: Framed<TcpStream, RespCommandFrame>) -> Self {
-        FrameHandler { conn }
+    /// Create a new `FrameHandler` with the given connection and connection id.
+    pub fn new(conn: Framed<TcpStream, RespCommandFrame>, id: u64) -> Self {
+        FrameHandler {
+            conn,
+            id,
+            protocol: ProtocolVersion::default(),
+        }
+    }
+
+    /// Switch the protocol version of the connection, so the encoder
+    /// writes the following responses using that version.
+    fn set_protocol(&mut self, protocol: ProtocolVersion) {
+        self.protocol = protocol;
+        self.conn.codec_mut().set_protocol(protocol);
     }
 
     /// Handle the incoming connection by reading the frames and processing the commands.
@@ -41,33 +61,44 @@ impl FrameHandler {
                                     Err(e) => RespType::SimpleError(format!("{}", e)),
                                 }
                             },
-                            Command::Exec => {
-                                if multicommand.is_active() {
-                                    multicommand.execute(db).await
-                                } else {
-                                    RespType::SimpleError(String::from("EXEC without MULTI"))
+                            Command::Exec => multicommand.execute(db).await,
+                            Command::Hello(_) if multicommand.is_active() => {
+                                // HELLO switches the protocol of the connection, it can't be queued
+                                multicommand.mark_dirty();
+                                RespType::SimpleError(TransactionError::NotAllowed.to_string())
+                            },
+                            Command::Hello(hello) => {
+                                if let Some(protocol) = hello.protocol() {
+                                    self.set_protocol(protocol);
                                 }
+                                if let Some(name) = hello.client_name() {
+                                    debug!("Connection {} is named {}", self.id, String::from_utf8_lossy(name));
+                                }
+                                hello.apply(self.id, self.protocol)
                             },
                             Command::Discard => {
                                 if multicommand.is_active() {
                                     multicommand.discard();
                                     RespType::SimpleString(String::from("OK"))
                                 } else {
-                                    RespType::SimpleError(String::from("DISCARD without MULTI"))
+                                    RespType::SimpleError(TransactionError::DiscardWithoutMulti.to_string())
                                 }
                             },
                             _ => {
                                 if multicommand.is_active() {
                                     multicommand.add_command(cmd);
                                     RespType::SimpleString(String::from("QUEUED"))
+                                } else if let Some(blocking) = cmd.as_blocking() {
+                                    self.block(blocking, db).await
                                 } else {
                                     cmd.execute(db)
                                 }
                             }
                         },
                         Err(e) => {
-                            if multicommand.is_active() { 
-                                multicommand.discard();
+                            // the transaction can't be executed anymore, EXEC will abort it
+                            if multicommand.is_active() {
+                                multicommand.mark_dirty();
                             }
                             RespType::SimpleError(format!("{}", e))
                         },
@@ -81,6 +112,11 @@ impl FrameHandler {
                 }
                 Err(e) => {
                     error!("Error reading the request: {}", e);
+
+                    // Let the client know about the protocol error before closing the connection.
+                    if e.kind() == std::io::ErrorKind::InvalidData {
+                        let _ = self.conn.send(RespType::SimpleError(format!("ERR {}", e))).await;
+                    }
                     break;
                 }
             };
@@ -91,4 +127,48 @@ impl FrameHandler {
 
         Ok(())
     }
+
+    /// Execute a blocking command, waiting until one of its keys holds a list,
+    /// the timeout elapses or the client closes the connection.
+    async fn block(&mut self, cmd: &dyn Blocking, db: &DB) -> RespType {
+        if let Some(reply) = cmd.ready(db) {
+            return reply;
+        }
+        let mut waiter = match db.pop_or_block(cmd.keys(), cmd.op()) {
+            Ok(BlockingPop::Ready(served)) => return cmd.reply(Some(served)),
+            Ok(BlockingPop::Blocked(waiter)) => waiter,
+            Err(e) => return RespType::SimpleError(format!("{}", e)),
+        };
+
+        let timeout = async {
+            match cmd.timeout() {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        // the commands sent while blocked are kept aside, and executed once unblocked, so
+        // that the end of the connection is noticed even after pipelined commands
+        let mut pipelined = BytesMut::new();
+        let closed = async {
+            while let Ok(1..) = self.conn.get_mut().read_buf(&mut pipelined).await {}
+        };
+
+        let served = tokio::select! {
+            served = waiter.served() => Some(served),
+            _ = timeout => None,
+            _ = closed => None,
+        };
+        self.conn.read_buffer_mut().extend_from_slice(&pipelined);
+        // an element may have been served between the timeout and the unblocking
+        let served = match served {
+            Some(served) => Some(served),
+            None => waiter.cancel(),
+        };
+
+        match served {
+            Some(Ok(served)) => cmd.reply(Some(served)),
+            Some(Err(e)) => RespType::SimpleError(format!("{}", e)),
+            None => cmd.reply(None),
+        }
+    }
 }
@@ -7,14 +7,22 @@ mod handler;
 mod resp;
 mod cmd;
 mod storage;
+mod utils;
 
 use anyhow::Result;
 use log::{error, info};
 use std::process::exit;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use clap::Parser;
+use resp::frame::{
+    ProtocolLimits, DEFAULT_MAX_BULK_LEN, DEFAULT_MAX_INLINE_LEN, DEFAULT_MAX_MULTIBULK_LEN,
+};
 
 const DEFAULT_PORT: u16 = 16379;
+const DEFAULT_HZ: u32 = 10;
+/// Percentage of each `hz` period that active expiration may use by default.
+const ACTIVE_EXPIRE_CYCLE_TIME_PERC: u32 = 25;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -27,6 +35,27 @@ struct Cli {
     /// Port to be bound to tiny redis server
     #[arg(long)]
     port: Option<u16>,
+
+    /// Maximum length in bytes of a single bulk string sent by a client
+    #[arg(long, default_value_t = DEFAULT_MAX_BULK_LEN)]
+    proto_max_bulk_len: usize,
+
+    /// Maximum number of arguments of a single command sent by a client
+    #[arg(long, default_value_t = DEFAULT_MAX_MULTIBULK_LEN)]
+    proto_max_multibulk_len: usize,
+
+    /// Maximum length in bytes of an inline command sent by a client
+    #[arg(long, default_value_t = DEFAULT_MAX_INLINE_LEN)]
+    proto_max_inline_len: usize,
+
+    /// How many times per second background tasks, such as active expiration, run
+    #[arg(long, default_value_t = DEFAULT_HZ, value_parser = clap::value_parser!(u32).range(1..=500))]
+    hz: u32,
+
+    /// Maximum milliseconds spent by each active expiration cycle.
+    /// Defaults to 25% of the period defined by --hz
+    #[arg(long)]
+    active_expire_budget_ms: Option<u64>,
 }
 
 #[tokio::main]
@@ -57,8 +86,22 @@ async fn main() -> Result<()> {
     // initialize the storage
     let storage = storage::db::Storage::new();
 
+    let config = server::ServerConfig {
+        // limits enforced while decoding the commands sent by clients
+        limits: ProtocolLimits {
+            max_bulk_len: cli.proto_max_bulk_len,
+            max_multibulk_len: cli.proto_max_multibulk_len,
+            max_inline_len: cli.proto_max_inline_len,
+        },
+        hz: cli.hz,
+        active_expire_budget: match cli.active_expire_budget_ms {
+            Some(ms) => Duration::from_millis(ms),
+            None => Duration::from_secs(1) * ACTIVE_EXPIRE_CYCLE_TIME_PERC / 100 / cli.hz,
+        },
+    };
+
     // Create a new server instance with the listener.
-    let mut server = server::Server::new(listener, storage);
+    let mut server = server::Server::new(listener, storage, config);
 
     // Run the server to start accepting and handling incoming connections.
     // This will run infinitely until the server is stopped.
@@ -5,30 +5,80 @@ use std::fmt::{Debug, Formatter};
 use std::io::Error;
 use tokio_util::codec::{Decoder, Encoder};
 
-use super::RespError;
+use super::{ProtocolVersion, RespError};
 
-const SIZE_OF_RESP_LEN: usize = 1 + std::mem::size_of::<usize>() + 2;
+/// Default maximum length of a single bulk string, same as Redis' `proto-max-bulk-len`.
+pub const DEFAULT_MAX_BULK_LEN: usize = 512 * 1024 * 1024;
+/// Default maximum number of elements in a command array.
+pub const DEFAULT_MAX_MULTIBULK_LEN: usize = 1024 * 1024;
+/// Default maximum size of an inline command or of a `*`/`$` header line.
+pub const DEFAULT_MAX_INLINE_LEN: usize = 64 * 1024;
+/// Maximum space reserved ahead for a bulk string whose data hasn't arrived yet, so that
+/// a `$<len>` header alone can't make the server allocate up to the maximum bulk length.
+const MAX_BULK_RESERVE: usize = 64 * 1024;
+
+/// Limits enforced while decoding the commands sent by a client, so that a
+/// misbehaving client can't make the server allocate unbounded memory.
+#[derive(Debug, Clone, Copy)]
+pub struct ProtocolLimits {
+    /// Maximum length of a single bulk string.
+    pub max_bulk_len: usize,
+    /// Maximum number of elements in a command array.
+    pub max_multibulk_len: usize,
+    /// Maximum size of an inline command or of a `*`/`$` header line.
+    pub max_inline_len: usize,
+}
+
+impl Default for ProtocolLimits {
+    fn default() -> Self {
+        ProtocolLimits {
+            max_bulk_len: DEFAULT_MAX_BULK_LEN,
+            max_multibulk_len: DEFAULT_MAX_MULTIBULK_LEN,
+            max_inline_len: DEFAULT_MAX_INLINE_LEN,
+        }
+    }
+}
 
 /// This codec handles commands, which are always represented as an array of bulk strings
 /// in the RESP protocol.
 pub struct RespCommandFrame {
     /// Builder for appending the bulk strings in the command array.
     cmd_builder: Option<CommandBuilder>,
+    /// The protocol version used to encode the outgoing values.
+    protocol: ProtocolVersion,
+    /// The limits enforced on the incoming commands.
+    limits: ProtocolLimits,
 }
 
 impl RespCommandFrame {
-    /// Create a new `RespCommandFrame`.
+    /// Create a new `RespCommandFrame` with the default protocol limits.
+    #[cfg(test)]
     pub fn new() -> Self {
-        RespCommandFrame { cmd_builder: None }
+        Self::with_limits(ProtocolLimits::default())
+    }
+
+    /// Create a new `RespCommandFrame` enforcing the given protocol limits.
+    pub fn with_limits(limits: ProtocolLimits) -> Self {
+        RespCommandFrame {
+            cmd_builder: None,
+            protocol: ProtocolVersion::default(),
+            limits,
+        }
+    }
+
+    /// Switch the protocol version used to encode the outgoing values.
+    pub fn set_protocol(&mut self, protocol: ProtocolVersion) {
+        self.protocol = protocol;
     }
 }
 
 impl Encoder<RespType> for RespCommandFrame {
     type Error = std::io::Error;
 
-    /// Encodes a `RespType` into bytes and writes them to the output buffer.
+    /// Encodes a `RespType` into bytes using the negotiated protocol version
+    /// and writes them to the output buffer.
     fn encode(&mut self, item: RespType, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        dst.extend(item.to_bytes());
+        dst.extend(item.for_protocol(self.protocol).to_bytes());
         Ok(())
     }
 }
@@ -39,78 +89,120 @@ impl Decoder for RespCommandFrame {
 
     /// Decodes bytes from the input stream into a `Vec<RespType>` representing a command.
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if src.len() < 4 {
-            src.advance(src.len());
-            return Ok(None);
-        }
+        // The decoder is resumable: every step below only consumes a complete
+        // header or bulk string, otherwise it leaves the bytes in the buffer and
+        // returns `Ok(None)` to wait for more data. Progress across calls is
+        // kept in the command builder.
+        loop {
+            // Anything that doesn't start like a RESP array is an inline command,
+            // a single line of space separated arguments terminated by CRLF or LF.
+            if self.cmd_builder.is_none() && !src.is_empty() && src[0] != b'*' {
+                let line_end = match src.iter().position(|b| *b == b'\n') {
+                    Some(pos) if pos <= self.limits.max_inline_len => pos,
+                    None if src.len() <= self.limits.max_inline_len => return Ok(None),
+                    _ => return Err(protocol_error("Protocol error: too big inline request")),
+                };
 
-        if self.cmd_builder.is_none() {
-            let n = src.len().min(SIZE_OF_RESP_LEN);
-            let (cmd_len, bytes_consumed) =
-                match RespType::parse_array_len(BytesMut::from(&src[..n])) {
-                    Ok((len, consumed)) => (len, consumed),
+                let line = src.split_to(line_end + 1);
+                let args = match RespType::parse_inline(&line) {
+                    Ok(args) => args,
                     Err(e) => {
                         return Err(Error::new(
                             std::io::ErrorKind::InvalidData,
                             FrameError::from(e),
                         ));
-                    },
+                    }
                 };
 
-            self.cmd_builder = Some(CommandBuilder::new(cmd_len));
-            src.advance(bytes_consumed);
-        }
+                // empty lines are ignored, continue with the next command in the buffer
+                if args.is_empty() {
+                    continue;
+                }
+                return Ok(Some(args));
+            }
 
-        // Read all bytes in the buffer
-        while src.len() != 0 {
-            let n = src.len().min(SIZE_OF_RESP_LEN);
-            let (bulkstr_len, _) = match RespType::parse_bulk_string_len(
-                BytesMut::from(&src[..n]),
-            ) {
-                Ok((len, consumed)) => (len, consumed),
-                Err(e) => {
-                    return Err(Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        FrameError::from(e),
-                    ));
-                },
-            };
+            let cmd_builder = match self.cmd_builder.as_mut() {
+                Some(cmd_builder) => cmd_builder,
+                None => {
+                    let (cmd_len, bytes_consumed) = match RespType::parse_array_len(src) {
+                        Ok(Some(header)) => header,
+                        Ok(None) if src.len() <= self.limits.max_inline_len => return Ok(None),
+                        Ok(None) => {
+                            return Err(protocol_error("Protocol error: too big mbulk count string"));
+                        }
+                        Err(e) => return Err(Error::from(FrameError::from(e))),
+                    };
+                    if cmd_len > self.limits.max_multibulk_len {
+                        return Err(protocol_error("Protocol error: invalid multibulk length"));
+                    }
+                    src.advance(bytes_consumed);
+
+                    // empty commands are skipped, like Redis does
+                    if cmd_len == 0 {
+                        continue;
+                    }
 
+                    self.cmd_builder.insert(CommandBuilder::new(cmd_len))
+                }
+            };
 
-            let n = src.len().min(SIZE_OF_RESP_LEN + bulkstr_len + 2);
-            let (bulkstr, bytes_consumed) = match RespType::parse_bulk_string(
-                BytesMut::from(&src[..n]),
-            ) {
-                Ok((resp_type, bytes_read)) => (resp_type, bytes_read),
-                Err(e) => {
-                    return Err(Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        FrameError::from(e),
-                    ));
+            let bulkstr_len = match cmd_builder.next_part_len {
+                Some(len) => len,
+                None => {
+                    let (len, bytes_consumed) = match RespType::parse_bulk_string_len(src) {
+                        Ok(Some(header)) => header,
+                        Ok(None) if src.len() <= self.limits.max_inline_len => return Ok(None),
+                        Ok(None) => {
+                            return Err(protocol_error("Protocol error: too big bulk count string"));
+                        }
+                        Err(e) => return Err(Error::from(FrameError::from(e))),
+                    };
+                    if len > self.limits.max_bulk_len {
+                        return Err(protocol_error("Protocol error: invalid bulk length"));
+                    }
+                    src.advance(bytes_consumed);
+                    cmd_builder.next_part_len = Some(len);
+                    len
                 }
             };
 
-            self.cmd_builder.as_mut().unwrap().add_part(bulkstr);
-            src.advance(bytes_consumed);
+            // wait until the whole bulk string including its CRLF has arrived, the buffer
+            // grows a chunk at a time as the data comes in
+            if src.len() < bulkstr_len + 2 {
+                src.reserve((bulkstr_len + 2 - src.len()).min(MAX_BULK_RESERVE));
+                return Ok(None);
+            }
+            if &src[bulkstr_len..bulkstr_len + 2] != b"\r\n" {
+                return Err(Error::from(FrameError::from(RespError::InvalidBulkString(
+                    String::from("Protocol error: bulk string is not terminated by CRLF"),
+                ))));
+            }
+
+            let bulkstr = src.split_to(bulkstr_len).freeze();
+            src.advance(2);
+            cmd_builder.add_part(RespType::BulkString(bulkstr));
 
-            let cmd_builder = self.cmd_builder.as_ref().unwrap();
             if cmd_builder.all_parts_received() {
-                let cmd = cmd_builder.build();
-                self.cmd_builder = None;
+                let cmd = self.cmd_builder.take().unwrap().build();
                 return Ok(Some(cmd));
             }
         }
-
-        Ok(None)
     }
 }
 
+// Build the error returned when the client violates the protocol or its limits.
+fn protocol_error(msg: &str) -> Error {
+    Error::from(FrameError::from(RespError::Other(String::from(msg))))
+}
+
 /// This struct is used to accumulate the parts of a command,
 /// which are typically represented as an array of bulk strings in the RESP protocol.
 struct CommandBuilder {
     parts: Vec<RespType>,
     num_parts: usize,
     parts_parsed: usize,
+    /// Length of the bulk string whose header was consumed but whose data is still pending.
+    next_part_len: Option<usize>,
 }
 
 impl CommandBuilder {
@@ -120,6 +212,7 @@ impl CommandBuilder {
             parts: vec![],
             num_parts,
             parts_parsed: 0,
+            next_part_len: None,
         }
     }
 
@@ -127,6 +220,7 @@ impl CommandBuilder {
     pub fn add_part(&mut self, part: RespType) {
         self.parts.push(part);
         self.parts_parsed += 1;
+        self.next_part_len = None;
     }
 
     /// Checks if all parts have been received.
@@ -135,8 +229,8 @@ impl CommandBuilder {
     }
 
     /// Builds and returns the complete command as a vector of RESP values.
-    pub fn build(&self) -> Vec<RespType> {
-        self.parts.clone()
+    pub fn build(self) -> Vec<RespType> {
+        self.parts
     }
 }
 
@@ -160,8 +254,194 @@ impl fmt::Display for FrameError {
 
 impl std::error::Error for FrameError {}
 
-impl Into<Error> for FrameError {
-    fn into(self) -> Error {
-        Error::new(std::io::ErrorKind::InvalidData, self.to_string())
+impl From<FrameError> for Error {
+    fn from(err: FrameError) -> Error {
+        Error::new(std::io::ErrorKind::InvalidData, err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    // Encode the given args as a RESP array of bulk strings.
+    fn encode_cmd(args: &[&[u8]]) -> Vec<u8> {
+        let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            buf.extend(format!("${}\r\n", arg.len()).into_bytes());
+            buf.extend_from_slice(arg);
+            buf.extend_from_slice(b"\r\n");
+        }
+        buf
+    }
+
+    fn args_of(cmd: Vec<RespType>) -> Vec<Bytes> {
+        cmd.into_iter()
+            .map(|part| match part {
+                RespType::BulkString(b) => b,
+                other => panic!("unexpected part {:?}", other),
+            })
+            .collect()
+    }
+
+    // Feed the input to the decoder in chunks of `chunk` bytes and collect all commands.
+    fn decode_chunked(input: &[u8], chunk: usize) -> Vec<Vec<Bytes>> {
+        let mut codec = RespCommandFrame::new();
+        let mut buf = BytesMut::new();
+        let mut cmds = vec![];
+        for part in input.chunks(chunk) {
+            buf.extend_from_slice(part);
+            while let Some(cmd) = codec.decode(&mut buf).unwrap() {
+                cmds.push(args_of(cmd));
+            }
+        }
+        assert!(buf.is_empty(), "unconsumed bytes: {:?}", buf);
+        cmds
+    }
+
+    #[test]
+    fn decodes_a_complete_command() {
+        let cmds = decode_chunked(&encode_cmd(&[b"SET", b"key", b"value"]), usize::MAX);
+        assert_eq!(cmds, vec![vec![Bytes::from("SET"), Bytes::from("key"), Bytes::from("value")]]);
+    }
+
+    #[test]
+    fn decodes_byte_at_a_time() {
+        let input = encode_cmd(&[b"SET", b"k", b"\r\n\x00binary\r\n"]);
+        let cmds = decode_chunked(&input, 1);
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0][2], Bytes::from_static(b"\r\n\x00binary\r\n"));
+    }
+
+    #[test]
+    fn decodes_at_every_split_point() {
+        let input = encode_cmd(&[b"RPUSH", b"list", b"a", b"", b"0123456789abcdef"]);
+        let expected = decode_chunked(&input, usize::MAX);
+
+        for split in 0..=input.len() {
+            let mut codec = RespCommandFrame::new();
+            let mut buf = BytesMut::from(&input[..split]);
+            let mut cmds = vec![];
+            if let Some(cmd) = codec.decode(&mut buf).unwrap() {
+                cmds.push(args_of(cmd));
+            }
+            buf.extend_from_slice(&input[split..]);
+            if let Some(cmd) = codec.decode(&mut buf).unwrap() {
+                cmds.push(args_of(cmd));
+            }
+            assert_eq!(cmds, expected, "split at {}", split);
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn decodes_pipelined_commands() {
+        let mut input = vec![];
+        for i in 0..100 {
+            input.extend(encode_cmd(&[b"SET", format!("key{}", i).as_bytes(), b"v"]));
+        }
+        input.extend(b"PING\r\n");
+        input.extend(encode_cmd(&[b"GET", b"key1"]));
+
+        for chunk in [1, 3, 7, 64, usize::MAX] {
+            let cmds = decode_chunked(&input, chunk);
+            assert_eq!(cmds.len(), 102, "chunk size {}", chunk);
+            assert_eq!(cmds[99][1], Bytes::from("key99"));
+            assert_eq!(cmds[100], vec![Bytes::from("PING")]);
+            assert_eq!(cmds[101], vec![Bytes::from("GET"), Bytes::from("key1")]);
+        }
+    }
+
+    #[test]
+    fn keeps_short_partial_header() {
+        let mut codec = RespCommandFrame::new();
+        let mut buf = BytesMut::from(&b"*1"[..]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert_eq!(&buf[..], b"*1");
+
+        buf.extend_from_slice(b"\r\n$4\r\nPING\r\n");
+        let cmd = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(args_of(cmd), vec![Bytes::from("PING")]);
+    }
+
+    #[test]
+    fn skips_empty_commands() {
+        let mut input = b"*0\r\n\r\n".to_vec();
+        input.extend(encode_cmd(&[b"PING"]));
+        assert_eq!(decode_chunked(&input, 1), vec![vec![Bytes::from("PING")]]);
+    }
+
+    #[test]
+    fn skips_many_empty_commands_at_once() {
+        let mut input = b"\r\n*0\r\n".repeat(500_000);
+        input.extend(encode_cmd(&[b"PING"]));
+        assert_eq!(decode_chunked(&input, usize::MAX), vec![vec![Bytes::from("PING")]]);
+    }
+
+    #[test]
+    fn reserves_large_bulk_strings_as_they_arrive() {
+        let mut codec = RespCommandFrame::new();
+        let mut buf = BytesMut::from(&b"*2\r\n$3\r\nSET\r\n$100000000\r\n"[..]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.capacity() <= 2 * MAX_BULK_RESERVE, "capacity {}", buf.capacity());
+
+        let value = vec![b'x'; 300_000];
+        let input = encode_cmd(&[b"SET", &value]);
+        assert_eq!(decode_chunked(&input, 4096), vec![vec![Bytes::from("SET"), Bytes::from(value)]]);
+    }
+
+    #[test]
+    fn decodes_inline_commands() {
+        let input = b"SET key \"hello world\"\r\nGET 'key'\n";
+        let cmds = decode_chunked(input, 1);
+        assert_eq!(cmds, vec![
+            vec![Bytes::from("SET"), Bytes::from("key"), Bytes::from("hello world")],
+            vec![Bytes::from("GET"), Bytes::from("key")],
+        ]);
+    }
+
+    #[test]
+    fn rejects_malformed_frames() {
+        for input in [
+            &b"*1\r\n$4\r\nPINGxx"[..],
+            &b"*1\r\n+PING\r\n"[..],
+            &b"*x\r\n"[..],
+            &b"*1\r\n$-5\r\n"[..],
+            &b"GET \"key\n"[..],
+        ] {
+            let mut codec = RespCommandFrame::new();
+            let mut buf = BytesMut::from(input);
+            assert!(codec.decode(&mut buf).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn enforces_protocol_limits() {
+        let limits = ProtocolLimits {
+            max_bulk_len: 8,
+            max_multibulk_len: 2,
+            max_inline_len: 16,
+        };
+
+        // within the limits
+        let mut codec = RespCommandFrame::with_limits(limits);
+        let mut buf = BytesMut::from(&encode_cmd(&[b"GET", b"12345678"])[..]);
+        assert!(codec.decode(&mut buf).unwrap().is_some());
+
+        for input in [
+            encode_cmd(&[b"GET", b"123456789"]),
+            encode_cmd(&[b"MGET", b"a", b"b"]),
+            // the header is rejected before the payload arrives
+            b"*1\r\n$1000000000\r\n".to_vec(),
+            b"*100000000000\r\n".to_vec(),
+            // headers and inline commands without a line end
+            b"*11111111111111111111".to_vec(),
+            b"PING PING PING PING PING".to_vec(),
+        ] {
+            let mut codec = RespCommandFrame::with_limits(limits);
+            let mut buf = BytesMut::from(&input[..]);
+            assert!(codec.decode(&mut buf).is_err(), "{:?}", input);
+        }
     }
 }
@@ -4,6 +4,34 @@ use std::fmt::Formatter;
 pub mod frame;
 pub mod types;
 
+/// The RESP version spoken on a connection. Every connection starts with RESP2 and can
+/// switch to RESP3 with the HELLO command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProtocolVersion {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl ProtocolVersion {
+    /// Returns the protocol version for the numeric version used in HELLO, if supported.
+    pub fn from_number(n: i64) -> Option<Self> {
+        match n {
+            2 => Some(ProtocolVersion::Resp2),
+            3 => Some(ProtocolVersion::Resp3),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric version reported back to the client.
+    pub fn number(&self) -> i64 {
+        match self {
+            ProtocolVersion::Resp2 => 2,
+            ProtocolVersion::Resp3 => 3,
+        }
+    }
+}
+
 /// This module contains the different errors that can occur while parsing RESP.
 #[derive(Debug)]
 pub enum RespError {
@@ -1,24 +1,58 @@
-use crate::resp::RespError;
+use crate::resp::{ProtocolVersion, RespError};
 use bytes::{Bytes, BytesMut};
 
 const CR: u8 = b'\r';
 const LF: u8 = b'\n';
 
+/// Upper bound of the `$<len>\r\n` header and the trailing CRLF of a bulk string.
+const SIZE_OF_BULK_HEADER: usize = 1 + 20 + 2 + 2;
+
 /// This enum is wrapper for the different data types in RESP
 #[derive(Debug, Clone)]
 pub enum RespType {
     /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#simple-strings>
     SimpleString(String),
     /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#bulk-strings>
-    BulkString(String),
+    /// Bulk strings are binary safe, so the payload is kept as raw bytes.
+    BulkString(Bytes),
     /// Null representation in RESP2. It's simply a BulkString with length of negative one (-1).
     NullBulkString,
+    /// Null array in RESP2, an Array with length of negative one (-1).
+    NullArray,
     /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#simple-errors>
     SimpleError(String),
     /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#arrays>
     Array(Vec<RespType>),
     /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#integers>
     Integer(i64),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#nulls>
+    Null,
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#booleans>
+    #[allow(dead_code)]
+    Boolean(bool),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#doubles>
+    Double(f64),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#big-numbers>
+    #[allow(dead_code)]
+    BigNumber(String),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#verbatim-strings>
+    /// Holds the three letter encoding (e.g. `txt`) and the payload.
+    #[allow(dead_code)]
+    VerbatimString(String, Bytes),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#maps>
+    Map(Vec<(RespType, RespType)>),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#sets>
+    Set(Vec<RespType>),
+    /// Refer <https://redis.io/docs/latest/develop/reference/protocol-spec/#pushes>
+    #[allow(dead_code)]
+    Push(Vec<RespType>),
+    /// Not a RESP type, but a list of pairs such as the fields and values of HRANDFIELD
+    /// WITHVALUES. Like Redis, it's replied as a flat array in RESP2, and as an array of
+    /// two element arrays in RESP3.
+    Pairs(Vec<(RespType, RespType)>),
+    /// Not a RESP type, but a map replied as an array of two element arrays in RESP2
+    /// rather than a flat array, like the reply of XREAD.
+    NestedMap(Vec<(RespType, RespType)>),
 }
 
 #[allow(dead_code)]
@@ -38,20 +72,126 @@ impl RespType {
     pub fn to_bytes(&self) -> Bytes {
         match self {
             RespType::SimpleString(s) => Bytes::from(format!("+{}\r\n", s)),
-            RespType::BulkString(s) => Bytes::from(format!("${}\r\n{}\r\n", s.chars().count(), s)),
+            RespType::BulkString(s) => {
+                let mut buf = BytesMut::with_capacity(s.len() + SIZE_OF_BULK_HEADER);
+                buf.extend_from_slice(format!("${}\r\n", s.len()).as_bytes());
+                buf.extend_from_slice(s);
+                buf.extend_from_slice(b"\r\n");
+                buf.freeze()
+            }
             RespType::NullBulkString => Bytes::from("$-1\r\n"),
+            RespType::NullArray => Bytes::from("*-1\r\n"),
             RespType::SimpleError(s) => Bytes::from(format!("-{}\r\n", s)),
             RespType::Integer(i) => Bytes::from_iter(format!(":{}\r\n", i).into_bytes()),
-            RespType::Array(arr) => {
-                let mut arr_bytes = format!("*{}\r\n", arr.len()).into_bytes();
-                arr.iter()
-                    .map(|item| item.to_bytes())
-                    .for_each(|b| arr_bytes.extend(b));
-                Bytes::from_iter(arr_bytes)
+            RespType::Array(arr) => Self::aggregate_to_bytes('*', arr),
+            RespType::Null => Bytes::from("_\r\n"),
+            RespType::Boolean(b) => Bytes::from(if *b { "#t\r\n" } else { "#f\r\n" }),
+            RespType::Double(d) => Bytes::from(format!(",{}\r\n", format_double(*d))),
+            RespType::BigNumber(n) => Bytes::from(format!("({}\r\n", n)),
+            RespType::VerbatimString(enc, s) => {
+                let mut buf = BytesMut::with_capacity(s.len() + enc.len() + 1 + SIZE_OF_BULK_HEADER);
+                buf.extend_from_slice(format!("={}\r\n{}:", s.len() + enc.len() + 1, enc).as_bytes());
+                buf.extend_from_slice(s);
+                buf.extend_from_slice(b"\r\n");
+                buf.freeze()
+            }
+            RespType::Map(pairs) | RespType::NestedMap(pairs) => {
+                let mut map_bytes = BytesMut::from(format!("%{}\r\n", pairs.len()).as_bytes());
+                for (k, v) in pairs.iter() {
+                    map_bytes.extend(k.to_bytes());
+                    map_bytes.extend(v.to_bytes());
+                }
+                map_bytes.freeze()
+            }
+            RespType::Set(items) => Self::aggregate_to_bytes('~', items),
+            RespType::Push(items) => Self::aggregate_to_bytes('>', items),
+            RespType::Pairs(pairs) => Self::aggregate_to_bytes('*', &Self::pairs_to_arrays(pairs)),
+        }
+    }
+
+    /// Converts the value into the representation expected by a client speaking the
+    /// given protocol version.
+    ///
+    /// RESP2 clients don't understand the RESP3 only types, so those are downgraded the
+    /// same way Redis does it: maps are flattened into arrays, doubles, big numbers and
+    /// verbatim strings become bulk strings, booleans become integers and pushes become
+    /// arrays. RESP3 clients get `_` for nulls.
+    pub fn for_protocol(self, protocol: ProtocolVersion) -> RespType {
+        match protocol {
+            ProtocolVersion::Resp2 => self.into_resp2(),
+            ProtocolVersion::Resp3 => self.into_resp3(),
+        }
+    }
+
+    fn into_resp2(self) -> RespType {
+        match self {
+            RespType::Array(arr) => RespType::Array(arr.into_iter().map(Self::into_resp2).collect()),
+            RespType::Null => RespType::NullBulkString,
+            RespType::Boolean(b) => RespType::Integer(b as i64),
+            RespType::Double(d) => RespType::BulkString(Bytes::from(format_double(d))),
+            RespType::BigNumber(n) => RespType::BulkString(Bytes::from(n)),
+            RespType::VerbatimString(_, s) => RespType::BulkString(s),
+            RespType::Map(pairs) => RespType::Array(
+                pairs
+                    .into_iter()
+                    .flat_map(|(k, v)| [k.into_resp2(), v.into_resp2()])
+                    .collect(),
+            ),
+            RespType::Set(items) | RespType::Push(items) => {
+                RespType::Array(items.into_iter().map(Self::into_resp2).collect())
             }
+            RespType::Pairs(pairs) => RespType::Array(
+                pairs
+                    .into_iter()
+                    .flat_map(|(a, b)| [a.into_resp2(), b.into_resp2()])
+                    .collect(),
+            ),
+            RespType::NestedMap(pairs) => RespType::Array(
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| RespType::Array(vec![k.into_resp2(), v.into_resp2()]))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn into_resp3(self) -> RespType {
+        match self {
+            RespType::NullBulkString | RespType::NullArray => RespType::Null,
+            RespType::Array(arr) => RespType::Array(arr.into_iter().map(Self::into_resp3).collect()),
+            RespType::Map(pairs) | RespType::NestedMap(pairs) => RespType::Map(
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| (k.into_resp3(), v.into_resp3()))
+                    .collect(),
+            ),
+            RespType::Set(items) => RespType::Set(items.into_iter().map(Self::into_resp3).collect()),
+            RespType::Push(items) => RespType::Push(items.into_iter().map(Self::into_resp3).collect()),
+            RespType::Pairs(pairs) => RespType::Array(
+                pairs
+                    .into_iter()
+                    .map(|(a, b)| RespType::Array(vec![a.into_resp3(), b.into_resp3()]))
+                    .collect(),
+            ),
+            other => other,
         }
     }
 
+    // Converts pairs into two element arrays.
+    fn pairs_to_arrays(pairs: &[(RespType, RespType)]) -> Vec<RespType> {
+        pairs.iter().map(|(a, b)| RespType::Array(vec![a.clone(), b.clone()])).collect()
+    }
+
+    // Serialize an aggregate type (array, set, push) with the given type prefix.
+    fn aggregate_to_bytes(prefix: char, items: &[RespType]) -> Bytes {
+        let mut buf = BytesMut::from(format!("{}{}\r\n", prefix, items.len()).as_bytes());
+        items.iter()
+            .map(|item| item.to_bytes())
+            .for_each(|b| buf.extend(b));
+        buf.freeze()
+    }
+
     /// Parse the buffer into an Array RESP value, and the number of bytes consumed
     pub fn parse_array(buf: BytesMut) -> Result<(RespType, usize), RespError> {
         let (arr_len, bytes_consumed) =
@@ -82,17 +222,25 @@ impl RespType {
         Ok((RespType::Array(arr), bytes_consumed))
     }
 
-    /// Parses the length of a RESP array from the given byte buffer.
-    pub fn parse_array_len(buf: BytesMut) -> Result<(usize, usize), RespError> {
-        if let Some((buf_data, len)) = Self::read_util_crlf(&buf[..]) {
-            if len < 4 || buf_data[0] as char != '*' {
-                return Err(RespError::InvalidArray(String::from("Not a valid RESP array")));
-            }
+    /// Parses the length of a RESP array header (`*<len>\r\n`) from the given byte buffer.
+    ///
+    /// Returns the length and the number of bytes consumed, or `None` when the buffer
+    /// doesn't contain the complete header yet. Null and empty arrays have a length of zero.
+    pub fn parse_array_len(buf: &[u8]) -> Result<Option<(usize, usize)>, RespError> {
+        let (buf_data, len) = match Self::read_util_crlf(buf) {
+            Some(line) => line,
+            None => return Ok(None),
+        };
 
-            let arr_len = Self::parse_usize_from_buf(&buf_data[1..])?;
-            Ok((arr_len, len))
-        } else {
-            Err(RespError::InvalidArray(String::from("Invalid Array")))
+        if buf_data.first() != Some(&b'*') {
+            return Err(RespError::InvalidArray(String::from("Not a valid RESP array")));
+        }
+
+        match Self::parse_i64_from_buf(&buf_data[1..]) {
+            Ok(arr_len) => Ok(Some((arr_len.max(0) as usize, len))),
+            Err(_) => Err(RespError::InvalidArray(String::from(
+                "Protocol error: invalid multibulk length",
+            ))),
         }
     }
 
@@ -125,35 +273,132 @@ impl RespType {
         // validate if buffer contains the complete string data based on
         // the length parsed in the previous step.
         let bulkstr_end_idx = bytes_consumed + blkstr_len;
-        if bulkstr_end_idx >= buf.len() {
+        if bulkstr_end_idx + 2 > buf.len() || &buf[bulkstr_end_idx..bulkstr_end_idx + 2] != b"\r\n" {
             return Err(RespError::InvalidBulkString(String::from(
                 "Invalid value for bulk string length",
             )));
         }
 
-        // convert the bytes to a UTF-8 string
-        let bulkstr = String::from_utf8(buf[bytes_consumed..bulkstr_end_idx].to_vec());
-        match bulkstr {
-            Ok(s) => Ok((RespType::BulkString(s), bulkstr_end_idx + 2)),
-            Err(_) => Err(RespError::InvalidBulkString(String::from(
-                "Bulk string value is not a valid UTF-8 string",
-            ))),
-        }
+        // bulk strings are binary safe, so the payload is copied as is
+        let bulkstr = Bytes::copy_from_slice(&buf[bytes_consumed..bulkstr_end_idx]);
+        Ok((RespType::BulkString(bulkstr), bulkstr_end_idx + 2))
     }
 
-    /// Parses the length of a RESP bulk string from the given byte buffer.
-    pub fn parse_bulk_string_len(buf: BytesMut) -> Result<(usize, usize), RespError> {
-        if let Some((buf_data, len)) = Self::read_util_crlf(&buf[..]) {
-            if len < 4 || buf_data[0] as char != '$' {
-                return Err(RespError::InvalidBulkString(String::from(
-                    "Not a valid RESP bulk string",
-                )));
+    /// Parse an inline command line into its arguments, each as a BulkString RESP value.
+    ///
+    /// Inline commands are the plain text format used from telnet or by health check probes,
+    /// e.g. `SET key "hello world"`. Arguments are separated by whitespace and can be quoted:
+    /// double quoted arguments understand the usual escapes (`\n`, `\"`, `\x41`, ...),
+    /// single quoted arguments only `\'`. The trailing CRLF or LF is optional.
+    pub fn parse_inline(line: &[u8]) -> Result<Vec<RespType>, RespError> {
+        let unbalanced = || RespError::Other(String::from(
+            "Protocol error: unbalanced quotes in request",
+        ));
+
+        let mut args = Vec::new();
+        let mut i = 0;
+        loop {
+            // skip the blanks before the next argument
+            while i < line.len() && line[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i == line.len() {
+                return Ok(args);
             }
 
-            let blkstr_len = Self::parse_usize_from_buf(&buf_data[1..])?;
-            Ok((blkstr_len, len))
-        } else {
-            Err(RespError::InvalidBulkString(String::from("Invalid Bulk String")))
+            let mut arg = Vec::new();
+            let mut in_dquotes = false;
+            let mut in_squotes = false;
+            loop {
+                if in_dquotes {
+                    match line.get(i) {
+                        None => return Err(unbalanced()),
+                        Some(b'\\') if i + 3 < line.len()
+                            && line[i + 1] == b'x'
+                            && line[i + 2].is_ascii_hexdigit()
+                            && line[i + 3].is_ascii_hexdigit() =>
+                        {
+                            let hex = std::str::from_utf8(&line[i + 2..i + 4]).unwrap();
+                            arg.push(u8::from_str_radix(hex, 16).unwrap());
+                            i += 3;
+                        }
+                        Some(b'\\') if i + 1 < line.len() => {
+                            i += 1;
+                            arg.push(match line[i] {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+                                c => c,
+                            });
+                        }
+                        Some(b'"') => {
+                            // closing quote must be followed by a space or nothing at all
+                            if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                                return Err(unbalanced());
+                            }
+                            in_dquotes = false;
+                        }
+                        Some(c) => arg.push(*c),
+                    }
+                } else if in_squotes {
+                    match line.get(i) {
+                        None => return Err(unbalanced()),
+                        Some(b'\\') if line.get(i + 1) == Some(&b'\'') => {
+                            i += 1;
+                            arg.push(b'\'');
+                        }
+                        Some(b'\'') => {
+                            if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                                return Err(unbalanced());
+                            }
+                            in_squotes = false;
+                        }
+                        Some(c) => arg.push(*c),
+                    }
+                } else {
+                    match line.get(i) {
+                        None => break,
+                        Some(c) if c.is_ascii_whitespace() => break,
+                        Some(b'"') => in_dquotes = true,
+                        Some(b'\'') => in_squotes = true,
+                        Some(c) => arg.push(*c),
+                    }
+                }
+                i += 1;
+            }
+
+            args.push(RespType::BulkString(Bytes::from(arg)));
+        }
+    }
+
+    /// Parses the length of a RESP bulk string header (`$<len>\r\n`) from the given byte buffer.
+    ///
+    /// Returns the length and the number of bytes consumed, or `None` when the buffer
+    /// doesn't contain the complete header yet.
+    pub fn parse_bulk_string_len(buf: &[u8]) -> Result<Option<(usize, usize)>, RespError> {
+        let (buf_data, len) = match Self::read_util_crlf(buf) {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+
+        match buf_data.first() {
+            Some(b'$') => {}
+            Some(c) => return Err(RespError::InvalidBulkString(format!(
+                "Protocol error: expected '$', got '{}'",
+                *c as char
+            ))),
+            None => return Err(RespError::InvalidBulkString(String::from(
+                "Protocol error: expected '$', got ''",
+            ))),
+        }
+
+        match Self::parse_usize_from_buf(&buf_data[1..]) {
+            Ok(blkstr_len) => Ok(Some((blkstr_len, len))),
+            Err(_) => Err(RespError::InvalidBulkString(String::from(
+                "Protocol error: invalid bulk length",
+            ))),
         }
     }
 
@@ -168,6 +413,14 @@ impl RespType {
         None
     }
 
+    // Parse i64 from bytes, the number is provided as a string in the buffer.
+    fn parse_i64_from_buf(buf: &[u8]) -> Result<i64, RespError> {
+        std::str::from_utf8(buf)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .ok_or_else(|| RespError::Other(String::from("Invalid integer")))
+    }
+
     // Parse usize from bytes. The number is provided as a string in the buffer.
     // So convert raw bytes into UTF-8 string and then parse the string into usize.
     fn parse_usize_from_buf(p0: &[u8]) -> Result<usize, RespError> {
@@ -190,3 +443,111 @@ impl RespType {
         Ok(parsed_int)
     }
 }
+
+
+/// Formats a double the way Redis replies with it: `inf`, `-inf` and `nan` for the special
+/// values, the shortest representation that round-trips otherwise, switching to exponent
+/// notation for very large or very small magnitudes.
+pub fn format_double(d: f64) -> String {
+    if d.is_nan() {
+        return String::from("nan");
+    }
+    if d.is_infinite() {
+        return String::from(if d > 0.0 { "inf" } else { "-inf" });
+    }
+
+    let abs = d.abs();
+    if abs != 0.0 && !(1e-6..1e21).contains(&abs) {
+        // Rust writes `1e300`, Redis writes `1e+300`
+        let s = format!("{:e}", d);
+        return match s.split_once('e') {
+            Some((m, e)) if !e.starts_with('-') => format!("{}e+{}", m, e),
+            _ => s,
+        };
+    }
+
+    format!("{}", d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Encode the value for a client speaking the given protocol version.
+    fn encode(value: RespType, protocol: ProtocolVersion) -> Bytes {
+        value.for_protocol(protocol).to_bytes()
+    }
+
+    fn bulk(s: &'static str) -> RespType {
+        RespType::BulkString(Bytes::from(s))
+    }
+
+    #[test]
+    fn encodes_booleans() {
+        assert_eq!(encode(RespType::Boolean(true), ProtocolVersion::Resp3), "#t\r\n");
+        assert_eq!(encode(RespType::Boolean(false), ProtocolVersion::Resp3), "#f\r\n");
+        assert_eq!(encode(RespType::Boolean(true), ProtocolVersion::Resp2), ":1\r\n");
+        assert_eq!(encode(RespType::Boolean(false), ProtocolVersion::Resp2), ":0\r\n");
+    }
+
+    #[test]
+    fn encodes_big_numbers() {
+        let n = || RespType::BigNumber(String::from("3492890328409238509324850943850943825024385"));
+        assert_eq!(encode(n(), ProtocolVersion::Resp3), "(3492890328409238509324850943850943825024385\r\n");
+        assert_eq!(
+            encode(n(), ProtocolVersion::Resp2),
+            "$43\r\n3492890328409238509324850943850943825024385\r\n"
+        );
+    }
+
+    #[test]
+    fn encodes_verbatim_strings() {
+        let verbatim = || RespType::VerbatimString(String::from("txt"), Bytes::from("Some string"));
+        assert_eq!(encode(verbatim(), ProtocolVersion::Resp3), "=15\r\ntxt:Some string\r\n");
+        assert_eq!(encode(verbatim(), ProtocolVersion::Resp2), "$11\r\nSome string\r\n");
+    }
+
+    #[test]
+    fn encodes_pushes() {
+        let push = || RespType::Push(vec![bulk("message"), RespType::Null]);
+        assert_eq!(encode(push(), ProtocolVersion::Resp3), ">2\r\n$7\r\nmessage\r\n_\r\n");
+        assert_eq!(encode(push(), ProtocolVersion::Resp2), "*2\r\n$7\r\nmessage\r\n$-1\r\n");
+    }
+
+    #[test]
+    fn converts_nested_values_for_the_protocol() {
+        let reply = || RespType::Array(vec![
+            RespType::Map(vec![(bulk("k"), RespType::Double(1.5))]),
+            RespType::NullBulkString,
+            RespType::Set(vec![bulk("m")]),
+            RespType::Pairs(vec![(bulk("f"), bulk("v"))]),
+            RespType::NestedMap(vec![(bulk("s"), RespType::NullArray)]),
+        ]);
+
+        assert_eq!(
+            encode(reply(), ProtocolVersion::Resp2),
+            concat!(
+                "*5\r\n",
+                "*2\r\n$1\r\nk\r\n$3\r\n1.5\r\n",
+                "$-1\r\n",
+                "*1\r\n$1\r\nm\r\n",
+                "*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+                "*1\r\n*2\r\n$1\r\ns\r\n*-1\r\n",
+            )
+        );
+        assert_eq!(
+            encode(reply(), ProtocolVersion::Resp3),
+            concat!(
+                "*5\r\n",
+                "%1\r\n$1\r\nk\r\n,1.5\r\n",
+                "_\r\n",
+                "~1\r\n$1\r\nm\r\n",
+                "*1\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+                "%1\r\n$1\r\ns\r\n_\r\n",
+            )
+        );
+        // pairs encoded as is are two element arrays
+        let pairs = RespType::Pairs(vec![(bulk("f"), bulk("v"))]);
+        assert_eq!(pairs.to_bytes(), "*1\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n");
+    }
+}
@@ -1,11 +1,23 @@
 use std::sync::Arc;
+use std::time::Duration;
 use anyhow::{Error, Result};
-use log::error;
+use log::{debug, error};
 use tokio::net::{TcpListener, TcpStream};
 use tokio_util::codec::Framed;
 use crate::handler::FrameHandler;
-use crate::resp::frame::RespCommandFrame;
-use crate::storage::db::Storage;
+use crate::resp::frame::{ProtocolLimits, RespCommandFrame};
+use crate::storage::db::{Storage, DB};
+
+/// Settings of the server, taken from the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerConfig {
+    /// The protocol limits enforced on every connection.
+    pub limits: ProtocolLimits,
+    /// How many times per second background tasks, such as active expiration, run.
+    pub hz: u32,
+    /// Maximum time spent by each active expiration cycle.
+    pub active_expire_budget: Duration,
+}
 
 /// The server struct holds the tokio TcpListener which listens for
 /// incoming TCP connections.
@@ -15,12 +27,21 @@ pub struct Server {
     listener: TcpListener,
     /// Contains the shared storage.
     storage: Storage,
+    /// The id assigned to the next accepted connection.
+    next_client_id: u64,
+    /// The settings of the server.
+    config: ServerConfig,
 }
 
 impl Server {
-    /// Create a new server instance with the given TcpListener.
-    pub fn new(listener: TcpListener, storage: Storage) -> Self {
-        Self { listener, storage }
+    /// Create a new server instance with the given TcpListener and settings.
+    pub fn new(listener: TcpListener, storage: Storage, config: ServerConfig) -> Self {
+        Self {
+            listener,
+            storage,
+            next_client_id: 1,
+            config,
+        }
     }
 
     /// Runs the server in an infinite loop, continuously accepting and handling
@@ -28,6 +49,9 @@ impl Server {
     pub async fn run(&mut self) -> Result<()> {
         let db = self.storage.db();
 
+        // Remove the expired keys in the background, `hz` times per second.
+        tokio::spawn(Self::active_expire(Arc::clone(&db), self.config));
+
         loop {
             // accept a new TCP connection
             // If successful the corresponding TcpStream is restored
@@ -41,16 +65,19 @@ impl Server {
             };
 
             let db = Arc::clone(&db);
+            let client_id = self.next_client_id;
+            self.next_client_id += 1;
+            let limits = self.config.limits;
 
             // Spawn a new asynchronous task to handle the incoming connection.
             // This allows the server to handle multiple connections concurrently.
             tokio::spawn(async move {
                 // Use RespCommandFrame codec to read incoming TCP messages as Redis command frames,
                 // and to write RespType values into outgoing TCP messages.
-                let resp_command_frame= Framed::with_capacity(sock, RespCommandFrame::new(), 8 * 1024);
+                let resp_command_frame= Framed::with_capacity(sock, RespCommandFrame::with_limits(limits), 8 * 1024);
 
                 // Create a new FrameHandler instance.
-                let mut handler = FrameHandler::new(resp_command_frame);
+                let mut handler = FrameHandler::new(resp_command_frame, client_id);
 
                 // Echo the RESP message back to the client.
                 if let Err(e) = handler.handle(db.as_ref()).await {
@@ -64,6 +91,24 @@ impl Server {
         }
     }
 
+    /// Periodically runs the active expiration cycle, so that expired keys and hash
+    /// fields which are never accessed again don't leak memory.
+    async fn active_expire(db: Arc<DB>, config: ServerConfig) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1) / config.hz);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match db.active_expire_cycle(config.active_expire_budget) {
+                Ok((0, 0)) => {}
+                Ok((keys, fields)) => {
+                    debug!("Active expiration removed {} keys and {} hash fields", keys, fields)
+                }
+                Err(e) => error!("Active expiration failed: {}", e),
+            }
+        }
+    }
+
     /// Accept a new incoming TCP connection and return the corresponding TcpStream.
     async fn accept_conn(&mut self) -> Result<TcpStream> {
         // Wait for an incoming connection.
@@ -0,0 +1,545 @@
+use bytes::Bytes;
+use crate::storage::db::{Entry, Value, DB};
+use crate::storage::string::update_bytes;
+use crate::storage::DBError;
+
+/// A range of a bitmap given to BITCOUNT or BITPOS.
+/// Negative offsets count from the end of the string, like GETRANGE.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitRange {
+    pub start: i64,
+    /// The last offset of the range, included. `None` for the end of the string.
+    pub end: Option<i64>,
+    /// The offsets are in bits rather than bytes (BIT).
+    pub bit: bool,
+}
+
+/// The bitwise operations of BITOP.
+#[derive(Debug, Clone, Copy)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// An integer type of BITFIELD, such as `i8` or `u16`.
+#[derive(Debug, Clone, Copy)]
+pub struct BitFieldType {
+    pub signed: bool,
+    /// The width of the integer, up to 64 bits signed and 63 bits unsigned.
+    pub bits: u32,
+}
+
+/// How BITFIELD handles a SET or INCRBY result not fitting into its type.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Overflow {
+    /// Wrap around, keeping the low bits of the result.
+    #[default]
+    Wrap,
+    /// Saturate to the minimum or maximum value of the type.
+    Sat,
+    /// Don't apply the operation and reply nil.
+    Fail,
+}
+
+/// An operation of BITFIELD on the integer at the bit `offset` of the string.
+#[derive(Debug, Clone, Copy)]
+pub enum BitFieldOp {
+    Get { ty: BitFieldType, offset: u64 },
+    Set { ty: BitFieldType, offset: u64, value: i64, overflow: Overflow },
+    IncrBy { ty: BitFieldType, offset: u64, incr: i64, overflow: Overflow },
+}
+
+impl BitFieldType {
+    fn min(&self) -> i128 {
+        if self.signed { -(1i128 << (self.bits - 1)) } else { 0 }
+    }
+
+    fn max(&self) -> i128 {
+        if self.signed { (1i128 << (self.bits - 1)) - 1 } else { (1i128 << self.bits) - 1 }
+    }
+
+    /// Reads the integer stored at the bit `offset`. Bits past the end of the string are zero.
+    fn read(&self, s: &[u8], offset: u64) -> i64 {
+        let mut value = 0u64;
+        for i in offset..offset + self.bits as u64 {
+            let byte = s.get((i / 8) as usize).copied().unwrap_or(0);
+            value = value << 1 | (byte >> (7 - i % 8) & 1) as u64;
+        }
+        self.truncate(value)
+    }
+
+    /// Writes the low bits of the value at the bit `offset`. The string must be large enough.
+    fn write(&self, s: &mut [u8], offset: u64, value: i64) {
+        for i in 0..self.bits as u64 {
+            let bit = (value as u64 >> (self.bits as u64 - 1 - i)) & 1;
+            let (byte, mask) = (((offset + i) / 8) as usize, 0x80 >> ((offset + i) % 8));
+            match bit {
+                1 => s[byte] |= mask,
+                _ => s[byte] &= !mask,
+            }
+        }
+    }
+
+    /// Keeps the low bits of the value, sign extended for signed types.
+    fn truncate(&self, value: u64) -> i64 {
+        if self.bits == 64 {
+            return value as i64;
+        }
+        let value = value & ((1 << self.bits) - 1);
+        match self.signed && value >> (self.bits - 1) == 1 {
+            true => (value | u64::MAX << self.bits) as i64,
+            false => value as i64,
+        }
+    }
+
+    /// Fits the value into the type according to the overflow behavior.
+    /// Returns `None` if it doesn't fit and the behavior is FAIL.
+    fn fit(&self, value: i128, overflow: Overflow) -> Option<i64> {
+        if value >= self.min() && value <= self.max() {
+            return Some(value as i64);
+        }
+        match overflow {
+            Overflow::Wrap => Some(self.truncate(value as u64)),
+            Overflow::Sat if value > self.max() => Some(self.max() as i64),
+            Overflow::Sat => Some(self.min() as i64),
+            Overflow::Fail => None,
+        }
+    }
+}
+
+impl BitFieldOp {
+    /// The bit right after the integer written by the operation, if it writes one.
+    fn write_end(&self) -> Option<u64> {
+        match self {
+            BitFieldOp::Get { .. } => None,
+            BitFieldOp::Set { ty, offset, .. } | BitFieldOp::IncrBy { ty, offset, .. } => {
+                Some(offset + ty.bits as u64)
+            }
+        }
+    }
+}
+
+impl BitRange {
+    /// Returns the first and last bit of the range in a string of `len` bytes,
+    /// or `None` if the range is empty.
+    fn resolve(&self, len: usize) -> Option<(u64, u64)> {
+        let total = if self.bit { len as i64 * 8 } else { len as i64 };
+        let start = if self.start < 0 { (total + self.start).max(0) } else { self.start };
+        let end = match self.end.unwrap_or(total - 1) {
+            end if end < 0 => (total + end).max(0),
+            end => end,
+        };
+        let end = end.min(total - 1);
+        if start > end {
+            return None;
+        }
+
+        match self.bit {
+            true => Some((start as u64, end as u64)),
+            false => Some((start as u64 * 8, end as u64 * 8 + 7)),
+        }
+    }
+}
+
+impl DB {
+    /// Set or clear the bit at `offset`, growing the string with zero bytes if needed.
+    /// Returns the previous value of the bit.
+    pub fn setbit(&self, key: Bytes, offset: u64, on: bool) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        let entry = data.get_or_insert_with(key, || Value::String(Bytes::new()));
+        let s = match &mut entry.value {
+            Value::String(s) => s,
+            _ => return Err(DBError::WrongType),
+        };
+
+        let byte = (offset / 8) as usize;
+        let mask = 0x80 >> (offset % 8);
+        Ok(update_bytes(s, |buf| {
+            if buf.len() <= byte {
+                buf.resize(byte + 1, 0);
+            }
+            let previous = buf[byte] & mask != 0;
+            match on {
+                true => buf[byte] |= mask,
+                false => buf[byte] &= !mask,
+            }
+            previous
+        }))
+    }
+
+    /// Get the bit at `offset`. Bits past the end of the string are zero.
+    pub fn getbit(&self, key: &[u8], offset: u64) -> Result<bool, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(false),
+            Some(Entry { value: Value::String(s), .. }) => {
+                let byte = s.get((offset / 8) as usize).copied().unwrap_or(0);
+                Ok(byte & (0x80 >> (offset % 8)) != 0)
+            }
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+
+    /// Count the bits set to 1 in the range of the string.
+    pub fn bitcount(&self, key: &[u8], range: BitRange) -> Result<u64, DBError> {
+        self.with_entry(key, |entry| {
+            let s = match entry {
+                None => return Ok(0),
+                Some(Entry { value: Value::String(s), .. }) => s,
+                Some(_) => return Err(DBError::WrongType),
+            };
+
+            let (first, last) = match range.resolve(s.len()) {
+                Some(bits) => bits,
+                None => return Ok(0),
+            };
+            Ok(bytes_in_range(s, first, last)
+                .map(|(_, byte, mask)| (byte & mask).count_ones() as u64)
+                .sum())
+        })
+    }
+
+    /// Find the position of the first bit set to `bit` in the range of the string.
+    ///
+    /// The string is considered padded with zero bytes on the right, so looking for a
+    /// clear bit past the string returns the position right after it, unless the end
+    /// of the range is given. Returns `None` if no such bit is found.
+    pub fn bitpos(&self, key: &[u8], bit: bool, range: BitRange) -> Result<Option<u64>, DBError> {
+        self.with_entry(key, |entry| {
+            let s = match entry {
+                None => return Ok(if bit { None } else { Some(0) }),
+                Some(Entry { value: Value::String(s), .. }) => s,
+                Some(_) => return Err(DBError::WrongType),
+            };
+
+            let (first, last) = match range.resolve(s.len()) {
+                Some(bits) => bits,
+                None => return Ok(None),
+            };
+            let found = bytes_in_range(s, first, last).find_map(|(i, byte, mask)| {
+                // look for a set bit, inverting the byte when looking for a clear one
+                let byte = (if bit { byte } else { !byte }) & mask;
+                (byte != 0).then(|| i as u64 * 8 + byte.leading_zeros() as u64)
+            });
+
+            match found {
+                None if !bit && range.end.is_none() => Ok(Some(last + 1)),
+                found => Ok(found),
+            }
+        })
+    }
+
+    /// Run the BITFIELD operations in order on the string stored at the key.
+    ///
+    /// Returns the result of each operation: the value for GET, the previous value for SET
+    /// and the new value for INCRBY, or `None` when the overflow behavior is FAIL and the
+    /// result doesn't fit. The string is only created or grown when an operation writes.
+    pub fn bitfield(&self, key: Bytes, ops: &[BitFieldOp]) -> Result<Vec<Option<i64>>, DBError> {
+        let end = match ops.iter().filter_map(BitFieldOp::write_end).max() {
+            Some(end) => end,
+            None => {
+                return self.with_entry(&key, |entry| {
+                    let s = match entry {
+                        None => &Bytes::new(),
+                        Some(Entry { value: Value::String(s), .. }) => s,
+                        Some(_) => return Err(DBError::WrongType),
+                    };
+                    Ok(ops.iter().map(|op| match op {
+                        BitFieldOp::Get { ty, offset } => Some(ty.read(s, *offset)),
+                        _ => None,
+                    }).collect())
+                });
+            }
+        };
+
+        let mut data = self.write()?;
+
+        let entry = data.get_or_insert_with(key, || Value::String(Bytes::new()));
+        let s = match &mut entry.value {
+            Value::String(s) => s,
+            _ => return Err(DBError::WrongType),
+        };
+
+        Ok(update_bytes(s, |buf| {
+            let len = end.div_ceil(8) as usize;
+            if buf.len() < len {
+                buf.resize(len, 0);
+            }
+
+            ops.iter()
+                .map(|op| match *op {
+                    BitFieldOp::Get { ty, offset } => Some(ty.read(buf, offset)),
+                    BitFieldOp::Set { ty, offset, value, overflow } => {
+                        let previous = ty.read(buf, offset);
+                        // unsigned values are checked as such, e.g. -1 overflows an u8
+                        let value = match ty.signed {
+                            true => value as i128,
+                            false => value as u64 as i128,
+                        };
+                        let value = ty.fit(value, overflow)?;
+                        ty.write(buf, offset, value);
+                        Some(previous)
+                    }
+                    BitFieldOp::IncrBy { ty, offset, incr, overflow } => {
+                        let value = ty.read(buf, offset) as i128 + incr as i128;
+                        let value = ty.fit(value, overflow)?;
+                        ty.write(buf, offset, value);
+                        Some(value)
+                    }
+                })
+                .collect()
+        }))
+    }
+
+    /// Perform a bitwise operation between the strings stored at the keys, and store
+    /// the result at `dest`. Missing keys count as empty strings, and shorter strings
+    /// are padded with zero bytes. An empty result deletes `dest`.
+    /// Returns the length of the result.
+    pub fn bitop(&self, op: BitOp, dest: Bytes, keys: &[Bytes]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let mut sources = Vec::with_capacity(keys.len());
+        for key in keys {
+            match data.get(key) {
+                None => sources.push(Bytes::new()),
+                Some(Entry { value: Value::String(s), .. }) => sources.push(s.clone()),
+                Some(_) => return Err(DBError::WrongType),
+            }
+        }
+
+        let len = sources.iter().map(Bytes::len).max().unwrap_or(0);
+        let byte = |s: &Bytes, i: usize| s.get(i).copied().unwrap_or(0);
+        let result: Vec<u8> = (0..len)
+            .map(|i| {
+                let mut bytes = sources.iter().map(|s| byte(s, i));
+                let first = bytes.next().unwrap_or(0);
+                match op {
+                    BitOp::And => bytes.fold(first, |acc, b| acc & b),
+                    BitOp::Or => bytes.fold(first, |acc, b| acc | b),
+                    BitOp::Xor => bytes.fold(first, |acc, b| acc ^ b),
+                    BitOp::Not => !first,
+                }
+            })
+            .collect();
+
+        if result.is_empty() {
+            data.remove(&dest);
+        } else {
+            data.insert(dest, Entry::new(Value::String(Bytes::from(result))));
+        }
+        Ok(len)
+    }
+}
+
+/// Iterates over the bytes holding the bits `first..=last` of the string, along with
+/// their index and the mask of the bits of each byte which are part of the range.
+fn bytes_in_range(s: &[u8], first: u64, last: u64) -> impl Iterator<Item = (usize, u8, u8)> + '_ {
+    let (first_byte, last_byte) = ((first / 8) as usize, (last / 8) as usize);
+    (first_byte..=last_byte).map(move |i| {
+        let mut mask = 0xffu8;
+        if i == first_byte {
+            mask &= 0xff >> (first % 8);
+        }
+        if i == last_byte {
+            mask &= 0xff << (7 - last % 8);
+        }
+        (i, s[i], mask)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db_with(key: &'static str, value: &'static [u8]) -> DB {
+        let db = DB::new();
+        db.mset(vec![(Bytes::from(key), Bytes::from(value))], false).unwrap();
+        db
+    }
+
+    fn bytes(start: i64, end: Option<i64>) -> BitRange {
+        BitRange { start, end, bit: false }
+    }
+
+    fn bits(start: i64, end: Option<i64>) -> BitRange {
+        BitRange { start, end, bit: true }
+    }
+
+    #[test]
+    fn sets_and_gets_bits() {
+        let db = DB::new();
+        assert!(!db.setbit(Bytes::from("k"), 7, true).unwrap());
+        assert!(db.setbit(Bytes::from("k"), 7, true).unwrap());
+        assert!(!db.setbit(Bytes::from("k"), 17, true).unwrap());
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), Some(Bytes::from(&b"\x01\x00\x40"[..])));
+
+        assert!(db.getbit(b"k", 7).unwrap());
+        assert!(!db.getbit(b"k", 8).unwrap());
+        assert!(db.getbit(b"k", 17).unwrap());
+        assert!(!db.getbit(b"k", 1000).unwrap());
+        assert!(!db.getbit(b"missing", 0).unwrap());
+
+        assert!(db.setbit(Bytes::from("k"), 17, false).unwrap());
+        assert!(!db.getbit(b"k", 17).unwrap());
+        // clearing a bit doesn't shrink the string
+        assert_eq!(db.get(&Bytes::from("k")).unwrap().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn counts_bits_in_ranges() {
+        let db = db_with("k", b"foobar");
+        assert_eq!(db.bitcount(b"k", bytes(0, None)).unwrap(), 26);
+        assert_eq!(db.bitcount(b"k", bytes(0, Some(0))).unwrap(), 4);
+        assert_eq!(db.bitcount(b"k", bytes(1, Some(1))).unwrap(), 6);
+        assert_eq!(db.bitcount(b"k", bytes(-2, Some(-1))).unwrap(), 7);
+        assert_eq!(db.bitcount(b"k", bytes(-100, Some(100))).unwrap(), 26);
+        assert_eq!(db.bitcount(b"k", bits(5, Some(30))).unwrap(), 17);
+        assert_eq!(db.bitcount(b"k", bytes(3, Some(1))).unwrap(), 0);
+        assert_eq!(db.bitcount(b"missing", bytes(0, None)).unwrap(), 0);
+    }
+
+    #[test]
+    fn finds_the_first_set_or_clear_bit() {
+        let db = db_with("k", b"\xff\xf0\x00");
+        assert_eq!(db.bitpos(b"k", false, bytes(0, None)).unwrap(), Some(12));
+        assert_eq!(db.bitpos(b"k", true, bytes(1, None)).unwrap(), Some(8));
+        assert_eq!(db.bitpos(b"k", true, bytes(2, None)).unwrap(), None);
+        assert_eq!(db.bitpos(b"k", true, bits(3, Some(9))).unwrap(), Some(3));
+        assert_eq!(db.bitpos(b"k", false, bits(3, Some(9))).unwrap(), None);
+        assert_eq!(db.bitpos(b"k", true, bits(-12, None)).unwrap(), None);
+        assert_eq!(db.bitpos(b"k", false, bits(-12, None)).unwrap(), Some(12));
+
+        assert_eq!(db.bitpos(b"missing", true, bytes(0, None)).unwrap(), None);
+        assert_eq!(db.bitpos(b"missing", false, bytes(0, None)).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn finds_clear_bits_past_a_string_of_ones() {
+        let db = db_with("k", b"\xff\xff");
+        assert_eq!(db.bitpos(b"k", true, bytes(0, None)).unwrap(), Some(0));
+        // the string is padded with zeros, unless the end of the range is given
+        assert_eq!(db.bitpos(b"k", false, bytes(0, None)).unwrap(), Some(16));
+        assert_eq!(db.bitpos(b"k", false, bytes(1, None)).unwrap(), Some(16));
+        assert_eq!(db.bitpos(b"k", false, bytes(0, Some(-1))).unwrap(), None);
+        assert_eq!(db.bitpos(b"k", false, bits(0, Some(15))).unwrap(), None);
+    }
+
+    #[test]
+    fn finds_no_bit_in_empty_ranges() {
+        let db = db_with("k", b"\x00\xff");
+        for bit in [false, true] {
+            assert_eq!(db.bitpos(b"k", bit, bytes(1, Some(0))).unwrap(), None);
+            assert_eq!(db.bitpos(b"k", bit, bytes(2, None)).unwrap(), None);
+            assert_eq!(db.bitpos(b"k", bit, bits(9, Some(8))).unwrap(), None);
+            assert_eq!(db.bitpos(b"k", bit, bytes(-1, Some(-2))).unwrap(), None);
+        }
+
+        let db = db_with("k", b"");
+        assert_eq!(db.bitpos(b"k", true, bytes(0, None)).unwrap(), None);
+        assert_eq!(db.bitcount(b"k", bytes(0, None)).unwrap(), 0);
+    }
+
+    #[test]
+    fn combines_bitmaps() {
+        let db = db_with("a", b"foobar");
+        db.mset(vec![(Bytes::from("b"), Bytes::from("abc"))], false).unwrap();
+        let keys = [Bytes::from("a"), Bytes::from("b")];
+
+        let result = |op, keys: &[Bytes]| {
+            let len = db.bitop(op, Bytes::from("dest"), keys).unwrap();
+            let dest = db.get(&Bytes::from("dest")).unwrap();
+            assert_eq!(dest.as_ref().map_or(0, Bytes::len), len);
+            dest
+        };
+        // the shorter string is padded with zeros
+        assert_eq!(result(BitOp::And, &keys), Some(Bytes::from(&b"`bc\0\0\0"[..])));
+        assert_eq!(result(BitOp::Or, &keys), Some(Bytes::from(&b"goobar"[..])));
+        assert_eq!(result(BitOp::Xor, &keys), Some(Bytes::from(&b"\x07\x0d\x0cbar"[..])));
+        assert_eq!(result(BitOp::Not, &keys[1..]), Some(Bytes::from(&b"\x9e\x9d\x9c"[..])));
+
+        // an empty result deletes the destination
+        assert_eq!(result(BitOp::Or, &[Bytes::from("missing")]), None);
+        db.sadd(Bytes::from("s"), vec![Bytes::from("m")]).unwrap();
+        assert!(db.bitop(BitOp::Or, Bytes::from("dest"), &[Bytes::from("s")]).is_err());
+    }
+
+    const I64: BitFieldType = BitFieldType { signed: true, bits: 64 };
+    const U63: BitFieldType = BitFieldType { signed: false, bits: 63 };
+
+    // Sets the integer at offset 3, so that it spans one more byte, then increments it.
+    fn set_then_incr(ty: BitFieldType, value: i64, incr: i64, overflow: Overflow) -> Vec<Option<i64>> {
+        let db = DB::new();
+        let ops = [
+            BitFieldOp::Set { ty, offset: 3, value, overflow: Overflow::Wrap },
+            BitFieldOp::IncrBy { ty, offset: 3, incr, overflow },
+            BitFieldOp::Get { ty, offset: 3 },
+        ];
+        let results = db.bitfield(Bytes::from("k"), &ops).unwrap();
+        assert_eq!(db.get(&Bytes::from("k")).unwrap().unwrap().len(), (3 + ty.bits as usize).div_ceil(8));
+        results
+    }
+
+    #[test]
+    fn wraps_around_the_limits_of_the_type() {
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(set_then_incr(I64, max, 1, Overflow::Wrap), [Some(0), Some(min), Some(min)]);
+        assert_eq!(set_then_incr(I64, min, -1, Overflow::Wrap), [Some(0), Some(max), Some(max)]);
+        assert_eq!(set_then_incr(I64, max, min, Overflow::Wrap), [Some(0), Some(-1), Some(-1)]);
+        assert_eq!(set_then_incr(U63, max, 1, Overflow::Wrap), [Some(0), Some(0), Some(0)]);
+        assert_eq!(set_then_incr(U63, 0, -1, Overflow::Wrap), [Some(0), Some(max), Some(max)]);
+        assert_eq!(set_then_incr(U63, max, max, Overflow::Wrap), [Some(0), Some(max - 1), Some(max - 1)]);
+    }
+
+    #[test]
+    fn saturates_at_the_limits_of_the_type() {
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(set_then_incr(I64, max, 1, Overflow::Sat), [Some(0), Some(max), Some(max)]);
+        assert_eq!(set_then_incr(I64, min, -1, Overflow::Sat), [Some(0), Some(min), Some(min)]);
+        assert_eq!(set_then_incr(I64, max - 1, 1, Overflow::Sat), [Some(0), Some(max), Some(max)]);
+        assert_eq!(set_then_incr(U63, max, max, Overflow::Sat), [Some(0), Some(max), Some(max)]);
+        assert_eq!(set_then_incr(U63, 5, min, Overflow::Sat), [Some(0), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn fails_past_the_limits_of_the_type() {
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(set_then_incr(I64, max, 1, Overflow::Fail), [Some(0), None, Some(max)]);
+        assert_eq!(set_then_incr(I64, min, -1, Overflow::Fail), [Some(0), None, Some(min)]);
+        assert_eq!(set_then_incr(I64, min, max, Overflow::Fail), [Some(0), Some(-1), Some(-1)]);
+        assert_eq!(set_then_incr(U63, max, 1, Overflow::Fail), [Some(0), None, Some(max)]);
+        assert_eq!(set_then_incr(U63, 0, -1, Overflow::Fail), [Some(0), None, Some(0)]);
+        assert_eq!(set_then_incr(U63, 1, -1, Overflow::Fail), [Some(0), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn checks_set_values_against_the_type() {
+        let db = DB::new();
+        let u8 = BitFieldType { signed: false, bits: 8 };
+        let i8 = BitFieldType { signed: true, bits: 8 };
+        let set = |key: &'static str, ty, value, overflow| {
+            let ops = [BitFieldOp::Set { ty, offset: 0, value, overflow }, BitFieldOp::Get { ty, offset: 0 }];
+            db.bitfield(Bytes::from(key), &ops).unwrap()
+        };
+        // unsigned values are checked as such, so -1 overflows an u8
+        assert_eq!(set("a", u8, -1, Overflow::Wrap), [Some(0), Some(255)]);
+        assert_eq!(set("a", u8, -1, Overflow::Sat), [Some(255), Some(255)]);
+        assert_eq!(set("a", u8, 256, Overflow::Fail), [None, Some(255)]);
+        assert_eq!(set("a", u8, 300, Overflow::Wrap), [Some(255), Some(44)]);
+        assert_eq!(set("a", i8, 128, Overflow::Wrap), [Some(44), Some(-128)]);
+        assert_eq!(set("a", i8, -200, Overflow::Sat), [Some(-128), Some(-128)]);
+
+        assert_eq!(set("b", U63, -1, Overflow::Sat), [Some(0), Some(i64::MAX)]);
+        assert_eq!(set("b", U63, -1, Overflow::Fail), [None, Some(i64::MAX)]);
+        assert_eq!(set("c", I64, i64::MIN, Overflow::Fail), [Some(0), Some(i64::MIN)]);
+    }
+
+    #[test]
+    fn reads_without_creating_the_key() {
+        let db = DB::new();
+        let ops = [BitFieldOp::Get { ty: I64, offset: 100 }];
+        assert_eq!(db.bitfield(Bytes::from("k"), &ops).unwrap(), [Some(0)]);
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), None);
+    }
+}
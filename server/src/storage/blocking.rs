@@ -0,0 +1,423 @@
+use bytes::Bytes;
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::oneshot;
+use crate::storage::db::{Keyspace, Value, DB};
+use crate::storage::list::ListEnd;
+use crate::storage::sorted_set::ZSetEnd;
+use crate::storage::stream::{StreamEntry, StreamId};
+use crate::storage::DBError;
+
+/// The operation run for a blocked client once one of its keys holds a list, a sorted
+/// set for the sorted set pops, or a stream with new entries for XREAD and XREADGROUP.
+#[derive(Debug, Clone)]
+pub enum BlockingOp {
+    /// Pop up to `count` elements from one end of the list (BLPOP, BRPOP and BLMPOP).
+    Pop { end: ListEnd, count: usize },
+    /// Pop an element from one end of the list and push it to one end of `dest` (BLMOVE).
+    Move { from: ListEnd, dest: Bytes, to: ListEnd },
+    /// Pop up to `count` members from one end of the sorted set (BZPOPMIN, BZPOPMAX
+    /// and BZMPOP).
+    ZPop { end: ZSetEnd, count: usize },
+    /// Read up to `count` entries with an ID greater than the one given for the stream
+    /// (XREAD). `None` stands for the last ID of the stream, and is replaced by it when
+    /// the client blocks.
+    XRead { ids: Vec<Option<StreamId>>, count: Option<usize> },
+    /// Deliver to the consumer of the group up to `count` entries never delivered to the
+    /// group (XREADGROUP with the `>` ID).
+    XReadGroup { group: Bytes, consumer: Bytes, count: Option<usize>, noack: bool },
+}
+
+/// The elements served to a client and the key they were popped from.
+#[derive(Debug)]
+pub struct Served {
+    pub key: Bytes,
+    pub elems: Vec<Bytes>,
+    /// The scores of the elements popped from a sorted set, empty for a list.
+    pub scores: Vec<f64>,
+    /// The entries read from a stream, empty for the pops.
+    pub entries: Vec<StreamEntry>,
+}
+
+/// The outcome of a blocking operation.
+pub enum BlockingPop<'a> {
+    /// One of the keys held a value to pop from, the operation ran right away.
+    Ready(Served),
+    /// None of the keys held a value to pop from, the client is now blocked on them.
+    Blocked(Waiter<'a>),
+}
+
+/// The registry of the clients blocked on keys.
+///
+/// It lives in the keyspace, so clients are blocked and served under the same lock as
+/// the values they wait for: an element pushed to a key is handed to the client blocked
+/// the longest on it before any other command can pop it.
+#[derive(Debug, Default)]
+pub struct BlockedClients {
+    /// The clients blocked on each key, in the order they blocked.
+    keys: HashMap<Bytes, VecDeque<u64>>,
+    clients: HashMap<u64, BlockedClient>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct BlockedClient {
+    keys: Vec<Bytes>,
+    op: BlockingOp,
+    /// Receives the elements served to the client, or the error raised by the operation.
+    tx: oneshot::Sender<Result<Served, DBError>>,
+}
+
+/// A client blocked on keys, waiting to be served. Dropping it unblocks the client,
+/// e.g. when the connection is closed while waiting.
+pub struct Waiter<'a> {
+    db: &'a DB,
+    id: u64,
+    rx: oneshot::Receiver<Result<Served, DBError>>,
+    /// Whether the client is still in the registry.
+    blocked: bool,
+}
+
+impl BlockingOp {
+    /// Replaces the `None` IDs of XREAD by the last ID of their stream, so that the client
+    /// is served the entries added once it's blocked.
+    fn resolve(self, keys: &[Bytes], data: &Keyspace) -> Result<BlockingOp, DBError> {
+        match self {
+            BlockingOp::XRead { ids, count } => {
+                let ids = keys
+                    .iter()
+                    .zip(ids)
+                    .map(|(key, id)| match id {
+                        Some(id) => Ok(Some(id)),
+                        None => data.last_stream_id(key).map(Some),
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(BlockingOp::XRead { ids, count })
+            }
+            op => Ok(op),
+        }
+    }
+}
+
+impl Served {
+    fn list(key: Bytes, elems: Vec<Bytes>) -> Served {
+        Served { key, elems, scores: Vec::new(), entries: Vec::new() }
+    }
+
+    fn zset(key: Bytes, members: Vec<(Bytes, f64)>) -> Served {
+        let (elems, scores) = members.into_iter().unzip();
+        Served { key, elems, scores, entries: Vec::new() }
+    }
+
+    fn stream(key: Bytes, entries: Vec<StreamEntry>) -> Served {
+        Served { key, elems: Vec::new(), scores: Vec::new(), entries }
+    }
+}
+
+impl BlockedClient {
+    /// Returns the ID given for the stream stored at the key, by XREAD.
+    fn stream_id(&self, key: &[u8]) -> Option<StreamId> {
+        let ids = match &self.op {
+            BlockingOp::XRead { ids, .. } => ids,
+            _ => return None,
+        };
+        let i = self.keys.iter().position(|other| other == key)?;
+        ids[i]
+    }
+
+    /// Checks if the operation of the client can run on the value stored at the key.
+    fn ready(&self, key: &[u8], value: &Value) -> bool {
+        match (&self.op, value) {
+            (BlockingOp::Pop { .. } | BlockingOp::Move { .. }, Value::List(_)) => true,
+            (BlockingOp::ZPop { .. }, Value::SortedSet(_)) => true,
+            (BlockingOp::XRead { .. }, Value::Stream(stream)) => {
+                self.stream_id(key).is_some_and(|id| stream.has_entries_after(id))
+            }
+            (BlockingOp::XReadGroup { group, .. }, Value::Stream(stream)) => stream.has_entries_for(group),
+            _ => false,
+        }
+    }
+}
+
+impl BlockedClients {
+    /// Blocks a client on the keys. Returns its id and the receiver of what it's served.
+    fn block(&mut self, keys: &[Bytes], op: BlockingOp) -> (u64, oneshot::Receiver<Result<Served, DBError>>) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let (tx, rx) = oneshot::channel();
+        for key in keys {
+            let queue = self.keys.entry(key.clone()).or_default();
+            if !queue.contains(&id) {
+                queue.push_back(id);
+            }
+        }
+        self.clients.insert(id, BlockedClient { keys: keys.to_vec(), op, tx });
+        (id, rx)
+    }
+
+    /// Removes the client from the registry, returning it if it was still blocked.
+    fn unblock(&mut self, id: u64) -> Option<BlockedClient> {
+        let client = self.clients.remove(&id)?;
+        for key in client.keys.iter() {
+            if let Some(queue) = self.keys.get_mut(key) {
+                queue.retain(|other| *other != id);
+                if queue.is_empty() {
+                    self.keys.remove(key);
+                }
+            }
+        }
+        Some(client)
+    }
+
+    /// Returns the id of the client blocked the longest on the key among the ones whose
+    /// operation can run on the value stored at the key.
+    pub(crate) fn first(&self, key: &[u8], value: &Value) -> Option<u64> {
+        let queue = self.keys.get(key)?;
+        queue.iter().copied().find(|id| self.clients.get(id).is_some_and(|client| client.ready(key, value)))
+    }
+}
+
+impl Keyspace {
+    /// Runs the operation on the first of the keys holding a value to pop from, skipping
+    /// missing keys. Fails with WRONGTYPE if a key holds another type. Returns `None` if
+    /// no key holds a value to pop from.
+    pub(crate) fn pop_first(&mut self, keys: &[Bytes], op: &BlockingOp) -> Result<Option<Served>, DBError> {
+        for (i, key) in keys.iter().enumerate() {
+            let served = match op {
+                BlockingOp::Pop { end, count } => {
+                    self.pop_list(key, *end, *count)?.map(|elems| Served::list(key.clone(), elems))
+                }
+                BlockingOp::Move { from, dest, to } => {
+                    let elem = self.move_list(key, dest.clone(), *from, *to)?;
+                    if elem.is_some() {
+                        self.serve_blocked(dest.clone());
+                    }
+                    elem.map(|elem| Served::list(key.clone(), vec![elem]))
+                }
+                BlockingOp::ZPop { end, count } => {
+                    self.pop_zset(key, *end, *count)?.map(|members| Served::zset(key.clone(), members))
+                }
+                BlockingOp::XRead { ids, count } => {
+                    self.read_stream(key, ids[i], *count)?.map(|entries| Served::stream(key.clone(), entries))
+                }
+                BlockingOp::XReadGroup { group, consumer, count, noack } => self
+                    .read_group(key, group, consumer, *count, *noack)?
+                    .map(|entries| Served::stream(key.clone(), entries)),
+            };
+            if served.is_some() {
+                return Ok(served);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Serves the clients blocked on the key in the order they blocked, as long as it
+    /// holds a value they can run on. Clients popping from another type, or reading from a
+    /// stream without new entries, keep waiting. Entries delivered to a consumer group are
+    /// not delivered again to the other consumers of the group.
+    /// Elements moved to another list serve the clients blocked on it too.
+    pub(crate) fn serve_blocked(&mut self, key: Bytes) {
+        let mut ready = VecDeque::from([key]);
+
+        while let Some(key) = ready.pop_front() {
+            while let Some(id) = self.first_blocked(&key) {
+                let client = match self.blocked().unblock(id) {
+                    Some(client) => client,
+                    None => break,
+                };
+
+                let result = match &client.op {
+                    BlockingOp::Pop { end, count } => self
+                        .pop_list(&key, *end, *count)
+                        .map(|elems| elems.map(|elems| Served::list(key.clone(), elems))),
+                    BlockingOp::Move { from, dest, to } => {
+                        let elem = self.move_list(&key, dest.clone(), *from, *to);
+                        if let Ok(Some(_)) = elem {
+                            ready.push_back(dest.clone());
+                        }
+                        elem.map(|elem| elem.map(|elem| Served::list(key.clone(), vec![elem])))
+                    }
+                    BlockingOp::ZPop { end, count } => self
+                        .pop_zset(&key, *end, *count)
+                        .map(|members| members.map(|members| Served::zset(key.clone(), members))),
+                    BlockingOp::XRead { count, .. } => self
+                        .read_stream(&key, client.stream_id(&key), *count)
+                        .map(|entries| entries.map(|entries| Served::stream(key.clone(), entries))),
+                    BlockingOp::XReadGroup { group, consumer, count, noack } => self
+                        .read_group(&key, group, consumer, *count, *noack)
+                        .map(|entries| entries.map(|entries| Served::stream(key.clone(), entries))),
+                };
+                let result = match result {
+                    Ok(Some(served)) => Ok(served),
+                    // the client is ready on the key, so there's always something to serve
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                };
+                // the receiver is alive as long as the client is in the registry
+                let _ = client.tx.send(result);
+            }
+        }
+    }
+}
+
+impl DB {
+    /// Run the operation on the first of the keys holding a value to pop from, or block
+    /// the client on the keys until one of them does.
+    pub fn pop_or_block(&self, keys: &[Bytes], op: BlockingOp) -> Result<BlockingPop<'_>, DBError> {
+        let mut data = self.write()?;
+
+        if let Some(served) = data.pop_first(keys, &op)? {
+            return Ok(BlockingPop::Ready(served));
+        }
+
+        let op = op.resolve(keys, &data)?;
+        let (id, rx) = data.blocked().block(keys, op);
+        Ok(BlockingPop::Blocked(Waiter { db: self, id, rx, blocked: true }))
+    }
+
+    /// Run the operation on the first of the keys holding a value to pop from, without blocking.
+    pub fn pop_first(&self, keys: &[Bytes], op: &BlockingOp) -> Result<Option<Served>, DBError> {
+        self.write()?.pop_first(keys, op)
+    }
+}
+
+impl Waiter<'_> {
+    /// Waits until the client is served.
+    pub async fn served(&mut self) -> Result<Served, DBError> {
+        let result = (&mut self.rx).await;
+        self.blocked = false;
+        result.unwrap_or_else(|_| Err(DBError::Other(String::from("client unblocked"))))
+    }
+
+    /// Unblocks the client. Returns what it was served right before, if anything.
+    pub fn cancel(mut self) -> Option<Result<Served, DBError>> {
+        self.unblock();
+        self.rx.try_recv().ok()
+    }
+
+    fn unblock(&mut self) {
+        if !self.blocked {
+            return;
+        }
+        self.blocked = false;
+        // the client is removed under the lock, so it can't be served concurrently
+        if let Ok(mut data) = self.db.write() {
+            data.blocked().unblock(self.id);
+        }
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        self.unblock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &str) -> Vec<Bytes> {
+        names.split(' ').map(|name| Bytes::from(name.to_string())).collect()
+    }
+
+    fn block<'a>(db: &'a DB, names: &str, op: BlockingOp) -> Waiter<'a> {
+        match db.pop_or_block(&keys(names), op).unwrap() {
+            BlockingPop::Blocked(waiter) => waiter,
+            BlockingPop::Ready(served) => panic!("served {:?} without blocking", served),
+        }
+    }
+
+    fn lpop() -> BlockingOp {
+        BlockingOp::Pop { end: ListEnd::Left, count: 1 }
+    }
+
+    // Returns the number of blocked clients and of keys having blocked clients.
+    fn registered(db: &DB) -> (usize, usize) {
+        let mut data = db.write().unwrap();
+        let blocked = data.blocked();
+        (blocked.clients.len(), blocked.keys.len())
+    }
+
+    #[test]
+    fn serves_the_client_blocked_the_longest() {
+        let db = DB::new();
+        let first = block(&db, "k", lpop());
+        let mut second = block(&db, "other k", lpop());
+        assert_eq!(registered(&db), (2, 2));
+
+        db.rpush(Bytes::from("k"), keys("a")).unwrap();
+        // the first client is served and unregistered, the second one keeps waiting
+        assert_eq!(registered(&db), (1, 2));
+        assert!(second.rx.try_recv().is_err());
+        let served = first.cancel().unwrap().unwrap();
+        assert_eq!((served.key, served.elems), (Bytes::from("k"), keys("a")));
+        assert_eq!(db.llen(b"k").unwrap(), 0);
+
+        db.rpush(Bytes::from("other"), keys("b c")).unwrap();
+        let served = second.cancel().unwrap().unwrap();
+        assert_eq!((served.key, served.elems), (Bytes::from("other"), keys("b")));
+        assert_eq!(db.lrange(b"other", 0, -1).unwrap(), keys("c"));
+        assert_eq!(registered(&db), (0, 0));
+    }
+
+    #[test]
+    fn serves_as_many_clients_as_there_are_elements() {
+        let db = DB::new();
+        let waiters: Vec<_> = (0..3).map(|_| block(&db, "k", lpop())).collect();
+
+        db.rpush(Bytes::from("k"), keys("a b")).unwrap();
+        let served: Vec<_> = waiters
+            .into_iter()
+            .map(|waiter| waiter.cancel().map(|served| served.unwrap().elems))
+            .collect();
+        assert_eq!(served, [Some(keys("a")), Some(keys("b")), None]);
+        assert_eq!(registered(&db), (0, 0));
+    }
+
+    #[test]
+    fn skips_the_clients_waiting_for_another_type() {
+        let db = DB::new();
+        let zpop = block(&db, "k", BlockingOp::ZPop { end: ZSetEnd::Min, count: 1 });
+        let pop = block(&db, "k", lpop());
+
+        db.rpush(Bytes::from("k"), keys("a")).unwrap();
+        assert_eq!(pop.cancel().unwrap().unwrap().elems, keys("a"));
+        assert_eq!(registered(&db), (1, 1));
+
+        // dropping the waiter unregisters the client
+        drop(zpop);
+        assert_eq!(registered(&db), (0, 0));
+    }
+
+    #[test]
+    fn serves_the_clients_blocked_on_the_destination_of_a_move() {
+        let db = DB::new();
+        let mv = BlockingOp::Move { from: ListEnd::Right, dest: Bytes::from("dest"), to: ListEnd::Left };
+        let moved = block(&db, "src", mv);
+        let popped = block(&db, "dest", lpop());
+
+        db.rpush(Bytes::from("src"), keys("a")).unwrap();
+        assert_eq!(moved.cancel().unwrap().unwrap().elems, keys("a"));
+        let served = popped.cancel().unwrap().unwrap();
+        assert_eq!((served.key, served.elems), (Bytes::from("dest"), keys("a")));
+        assert_eq!(db.llen(b"dest").unwrap(), 0);
+    }
+
+    #[test]
+    fn pops_right_away_from_the_first_non_empty_key() {
+        let db = DB::new();
+        db.rpush(Bytes::from("b"), keys("x y")).unwrap();
+        match db.pop_or_block(&keys("a b"), lpop()).unwrap() {
+            BlockingPop::Ready(served) => {
+                assert_eq!((served.key, served.elems), (Bytes::from("b"), keys("x")));
+            }
+            BlockingPop::Blocked(_) => panic!("blocked on a non empty list"),
+        }
+        assert_eq!(registered(&db), (0, 0));
+
+        db.sadd(Bytes::from("s"), keys("m")).unwrap();
+        assert!(db.pop_or_block(&keys("a s"), lpop()).is_err());
+        assert_eq!(registered(&db), (0, 0));
+    }
+}
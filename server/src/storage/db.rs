@@ -1,6 +1,13 @@
+use bytes::Bytes;
 use std::collections::{HashMap, VecDeque};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::storage::blocking::BlockedClients;
+use crate::storage::hash::Hash;
+use crate::storage::set::Set;
+use crate::storage::sorted_set::SortedSet;
+use crate::storage::stream::Stream;
 use crate::storage::DBError;
+use crate::utils::{now_ms, random_index};
 
 /// The Storage struct is designed to act as a wrapper around the core database,
 /// allowing it to be shared across multiple connections. The database is encapsulated within an Arc,
@@ -11,24 +18,60 @@ pub struct Storage {
 }
 
 /// The `DB` struct is the component that houses the actual data,
-/// which is stored in a RwLock wrapped around a hashmap.
+/// which is stored in a RwLock wrapped around the keyspace.
 /// This ensures that the data can be accessed concurrently
 #[derive(Debug)]
 pub struct DB {
-    data: RwLock<HashMap<String, Entry>>
+    data: RwLock<Keyspace>
+}
+
+/// The `Keyspace` struct holds the keys and their entries.
+///
+/// Expired keys are treated as missing by every lookup. Lookups through a mutable
+/// reference also remove them, so write operations never see an expired entry.
+///
+/// The TTL of a key is only updated through the keyspace, which keeps an index of
+/// the keys having one, so the active expiration cycle can sample them. Likewise it
+/// keeps an index of the hashes having fields with a TTL.
+/// A hash whose fields have all expired is expired too.
+#[derive(Debug, Default)]
+pub struct Keyspace {
+    entries: HashMap<Bytes, Entry>,
+    /// The keys having a TTL.
+    volatile: VolatileKeys,
+    /// The keys of the hashes having fields with a TTL. It may hold keys whose
+    /// fields have lost their TTL since, which are dropped when sampled.
+    volatile_hashes: VolatileKeys,
+    /// The clients blocked on keys by BLPOP, BZPOPMIN, XREAD and their variants.
+    blocked: BlockedClients,
+}
+
+/// An indexed set of keys, allowing to pick a random key in constant time.
+#[derive(Debug, Default)]
+struct VolatileKeys {
+    keys: Vec<Bytes>,
+    positions: HashMap<Bytes, usize>,
 }
 
 /// The `Entry` struct represents the value associated with a particular key.
 #[derive(Debug, Clone)]
 pub struct Entry {
-    value: Value,
+    pub value: Value,
+    /// Unix time in milliseconds at which the key expires, if it has a TTL.
+    expires_at: Option<u64>,
 }
 
 /// The `Value` enum allows for storing various types of data associated with a key.
+/// Strings, list elements, hash fields, set members and stream fields are binary safe and
+/// stored as raw bytes.
 #[derive(Debug, Clone)]
 pub enum Value {
-    String(String),
-    List(VecDeque<String>),
+    String(Bytes),
+    List(VecDeque<Bytes>),
+    Hash(Hash),
+    Set(Set),
+    SortedSet(SortedSet),
+    Stream(Stream),
 }
 
 impl Storage {
@@ -45,114 +88,241 @@ impl Storage {
     }
 }
 
-impl DB {
-    /// Creates a new instance of the DB struct.
-    pub fn new() -> Self {
-        DB {
-            data: RwLock::new(HashMap::new())
-        }
+impl Entry {
+    /// Creates a new entry without TTL.
+    pub fn new(value: Value) -> Self {
+        Entry { value, expires_at: None }
     }
 
-    /// Get the string value associated with a key.
-    pub fn get(&self, key: &str) -> Result<Option<String>, DBError> {
-        let data = match self.data.read() {
-            Ok(data) => data,
-            Err(e) => return Err(DBError::Other(format!("{}", e))),
-        };
+    /// Creates a new entry expiring at the given unix time in milliseconds.
+    pub fn with_expires_at(value: Value, expires_at: Option<u64>) -> Self {
+        Entry { value, expires_at }
+    }
 
-        let entry = match data.get(key) {
-            Some(entry) => entry,
-            None => return Ok(None),
-        };
+    /// Returns the unix time in milliseconds at which the entry expires, if it has a TTL.
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
 
-        if let Value::String(s) = &entry.value {
-            return Ok(Some(s.to_string()))
-        }
+    /// Checks if the TTL of the entry has elapsed at the given unix time in milliseconds,
+    /// or if the entry is a hash whose fields have all expired.
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expires_at, Some(t) if t <= now)
+            || matches!(&self.value, Value::Hash(hash) if hash.is_expired(now))
+    }
+}
 
-        Err(DBError::WrongType)
+impl Keyspace {
+    /// Returns the entry of the key, unless it's missing or expired.
+    pub fn get(&self, key: &[u8]) -> Option<&Entry> {
+        self.entries.get(key).filter(|entry| !entry.is_expired(now_ms()))
     }
 
-    /// Set the value associated with a key.
-    pub fn set(&self, key: String, value: Value) -> Result<(), DBError> {
-        let mut data = match self.data.write() {
-            Ok(data) => data,
-            Err(e) => return Err(DBError::Other(format!("{}", e))),
-        };
+    /// Returns the mutable entry of the key. An expired entry is removed first.
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        self.remove_if_expired(key);
+        self.entries.get_mut(key)
+    }
 
-        data.insert(key, Entry { value });
-        Ok(())
+    /// Returns the mutable entry of the key, inserting the value returned by `f` if missing.
+    pub fn get_or_insert_with(&mut self, key: Bytes, f: impl FnOnce() -> Value) -> &mut Entry {
+        self.remove_if_expired(&key);
+        self.entries.entry(key).or_insert_with(|| Entry::new(f()))
     }
 
+    /// Inserts the entry, returning the previous one unless it was expired.
+    pub fn insert(&mut self, key: Bytes, entry: Entry) -> Option<Entry> {
+        self.remove_if_expired(&key);
+        match entry.expires_at {
+            Some(_) => self.volatile.insert(key.clone()),
+            None => self.volatile.remove(&key),
+        }
+        match &entry.value {
+            Value::Hash(hash) if hash.has_volatile_fields() => self.volatile_hashes.insert(key.clone()),
+            _ => self.volatile_hashes.remove(&key),
+        }
+        self.entries.insert(key, entry)
+    }
 
-    /// Left push elements associated with a key.
-    pub fn lpush(&self, key: String, value: Vec<String>) -> Result<usize, DBError> {
-        let mut data = match self.data.write() {
-            Ok(data) => data,
-            Err(e) => return Err(DBError::Other(format!("{}", e))),
-        };
+    /// Removes the key, returning its entry unless it was expired.
+    pub fn remove(&mut self, key: &[u8]) -> Option<Entry> {
+        self.remove_if_expired(key);
+        self.volatile.remove(key);
+        self.volatile_hashes.remove(key);
+        self.entries.remove(key)
+    }
 
-        let entry = data.entry(key).or_insert(Entry { value: Value::List(VecDeque::new()) });
+    /// Sets or clears the TTL of the key. Returns the previous deadline.
+    pub fn set_expires_at(&mut self, key: &[u8], expires_at: Option<u64>) -> Option<u64> {
+        let entry = self.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.expires_at, expires_at);
+        match expires_at {
+            Some(_) => {
+                let key = self.entries.get_key_value(key).map(|(k, _)| k.clone());
+                self.volatile.insert(key.unwrap());
+            }
+            None => self.volatile.remove(key),
+        }
+        previous
+    }
 
-        if let Value::List(list) = &mut entry.value {
-            for item in value.iter() {
-                list.push_front(item.to_string());
+    /// Removes the key if its TTL has elapsed. Returns true if the key was removed.
+    pub fn remove_if_expired(&mut self, key: &[u8]) -> bool {
+        match self.entries.get(key) {
+            Some(entry) if entry.is_expired(now_ms()) => {
+                self.volatile.remove(key);
+                self.volatile_hashes.remove(key);
+                self.entries.remove(key);
+                true
             }
-            return Ok(list.len())
+            _ => false,
         }
+    }
 
-        Err(DBError::WrongType)
+    /// Adds the key of a hash to the index of the hashes having fields with a TTL.
+    pub(crate) fn add_volatile_hash(&mut self, key: &[u8]) {
+        if let Some((key, _)) = self.entries.get_key_value(key) {
+            self.volatile_hashes.insert(key.clone());
+        }
     }
 
-    /// Right push elements associated with a key.
-    pub fn rpush(&self, key: String, value: Vec<String>) -> Result<usize, DBError> {
-        let mut data = match self.data.write() {
-            Ok(data) => data,
-            Err(e) => return Err(DBError::Other(format!("{}", e))),
-        };
+    /// Returns the registry of the clients blocked on keys.
+    pub(crate) fn blocked(&mut self) -> &mut BlockedClients {
+        &mut self.blocked
+    }
 
-        let entry = data.entry(key).or_insert(Entry { value: Value::List(VecDeque::new()) });
+    /// Returns the id of the client blocked the longest on the key among the ones whose
+    /// operation can run on the value stored at the key.
+    pub(crate) fn first_blocked(&self, key: &[u8]) -> Option<u64> {
+        let entry = self.get(key)?;
+        self.blocked.first(key, &entry.value)
+    }
 
-        if let Value::List(list) = &mut entry.value {
-            for item in value.iter() {
-                list.push_back(item.to_string());
+    /// Checks up to `count` random keys having a TTL and removes the expired ones.
+    /// Returns the number of keys checked and the number of keys removed.
+    pub fn expire_random_keys(&mut self, count: usize) -> (usize, usize) {
+        let sampled = count.min(self.volatile.len());
+        let mut expired = 0;
+        for _ in 0..sampled {
+            let key = match self.volatile.random() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            if self.remove_if_expired(&key) {
+                expired += 1;
             }
-            return Ok(list.len())
         }
-
-        Err(DBError::WrongType)
+        (sampled, expired)
     }
 
-    /// Get the specified number of elements of the list stored at key.
-    pub fn lrange(&self, key: String, start: i64, stop: i64) -> Result<Vec<String>, DBError> {
-        let data = match self.data.read() {
-            Ok(data) => data,
-            Err(e) => return Err(DBError::Other(format!("{}", e))),
-        };
+    /// Removes the expired fields of up to `count` random hashes having fields with a TTL,
+    /// and the hashes left empty. Returns the number of hashes checked, the number of them
+    /// that had expired fields and the number of fields removed.
+    pub fn expire_random_hash_fields(&mut self, count: usize) -> (usize, usize, usize) {
+        let sampled = count.min(self.volatile_hashes.len());
+        let now = now_ms();
+        let mut stale = 0;
+        let mut expired = 0;
+        for _ in 0..sampled {
+            let key = match self.volatile_hashes.random() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            let (empty, volatile) = match self.entries.get_mut(&key) {
+                Some(Entry { value: Value::Hash(hash), .. }) => {
+                    let removed = hash.remove_expired(now);
+                    stale += (removed > 0) as usize;
+                    expired += removed;
+                    (hash.is_empty(), hash.has_volatile_fields())
+                }
+                _ => (false, false),
+            };
+            if empty {
+                self.remove(&key);
+            } else if !volatile {
+                self.volatile_hashes.remove(&key);
+            }
+        }
+        (sampled, stale, expired)
+    }
+}
 
-        let entry = match data.get(&key) {
-            Some(entry) => entry,
-            None => return Ok(Vec::new()),
-        };
+impl VolatileKeys {
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
 
-        if let Value::List(list) = &entry.value {
-            let len = list.len() as i64;
-            let start = if start < 0 { len + start } else { start };
-            let stop = if stop < 0 { len + stop } else { stop };
+    fn insert(&mut self, key: Bytes) {
+        if !self.positions.contains_key(&key) {
+            self.positions.insert(key.clone(), self.keys.len());
+            self.keys.push(key);
+        }
+    }
 
-            if start < 0 || stop < 0 || start >= len || stop >= len || start > stop {
-                return Ok(Vec::new())
+    fn remove(&mut self, key: &[u8]) {
+        if let Some(pos) = self.positions.remove(key) {
+            // move the last key into the hole, and update its position
+            self.keys.swap_remove(pos);
+            if let Some(moved) = self.keys.get(pos) {
+                self.positions.insert(moved.clone(), pos);
             }
+        }
+    }
 
-            let mut result = Vec::new();
-            for i in start..=stop {
-                if let Some(item) = list.get(i as usize) {
-                    result.push(item.to_string());
-                }
-            }
+    fn random(&self) -> Option<&Bytes> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        self.keys.get(random_index(self.keys.len()))
+    }
+}
+
+impl DB {
+    /// Creates a new instance of the DB struct.
+    pub fn new() -> Self {
+        DB {
+            data: RwLock::new(Keyspace::default())
+        }
+    }
 
-            return Ok(result)
+    /// Acquires the read lock of the keyspace.
+    pub(crate) fn read(&self) -> Result<RwLockReadGuard<'_, Keyspace>, DBError> {
+        self.data.read().map_err(|e| DBError::Other(format!("{}", e)))
+    }
+
+    /// Acquires the write lock of the keyspace.
+    pub(crate) fn write(&self) -> Result<RwLockWriteGuard<'_, Keyspace>, DBError> {
+        self.data.write().map_err(|e| DBError::Other(format!("{}", e)))
+    }
+
+    /// Runs `f` with the entry of the key under the read lock.
+    ///
+    /// Expired keys are passed as missing, and are lazily removed afterwards under
+    /// the write lock, so reads only take the write lock when there's work to do.
+    pub(crate) fn with_entry<T>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(Option<&Entry>) -> Result<T, DBError>,
+    ) -> Result<T, DBError> {
+        let data = self.read()?;
+        let (result, expired) = match data.entries.get(key) {
+            Some(entry) if entry.is_expired(now_ms()) => (f(None), true),
+            entry => (f(entry), false),
+        };
+        drop(data);
+
+        if expired {
+            self.write()?.remove_if_expired(key);
         }
+        result
+    }
 
-        Err(DBError::WrongType)
+    /// Get the string value associated with a key.
+    pub fn get(&self, key: &Bytes) -> Result<Option<Bytes>, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(None),
+            Some(Entry { value: Value::String(s), .. }) => Ok(Some(s.clone())),
+            Some(_) => Err(DBError::WrongType),
+        })
     }
 }
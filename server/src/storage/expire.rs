@@ -0,0 +1,246 @@
+use crate::storage::db::{Keyspace, DB};
+use crate::storage::DBError;
+use crate::utils::now_ms;
+use std::time::{Duration, Instant};
+
+/// Number of keys with a TTL, or hashes having fields with one, checked by each batch
+/// of the active expiration cycle.
+const ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP: usize = 20;
+/// The active expiration cycle stops when the percentage of expired keys
+/// found in a batch is at most this value.
+const ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE: usize = 10;
+
+/// The conditions under which EXPIRE and its variants update the TTL of a key.
+/// With no flag set the TTL is always updated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpireCondition {
+    /// Set the TTL only when the key has none.
+    pub nx: bool,
+    /// Set the TTL only when the key already has one.
+    pub xx: bool,
+    /// Set the TTL only when the new one is greater than the current one.
+    /// A key without TTL is considered to have an infinite one.
+    pub gt: bool,
+    /// Set the TTL only when the new one is less than the current one.
+    /// A key without TTL is considered to have an infinite one.
+    pub lt: bool,
+}
+
+impl ExpireCondition {
+    /// Checks if a key with the `current` deadline can be updated to `deadline`.
+    pub fn allows(&self, current: Option<u64>, deadline: u64) -> bool {
+        !(self.nx && current.is_some()
+            || self.xx && current.is_none()
+            || self.gt && current.is_none_or(|t| deadline <= t)
+            || self.lt && current.is_some_and(|t| deadline >= t))
+    }
+}
+
+impl DB {
+    /// Set the unix time in milliseconds at which the key expires.
+    /// A deadline in the past deletes the key right away.
+    /// Returns true if the TTL was updated, false if the key doesn't exist
+    /// or the condition isn't met.
+    pub fn expire_at(&self, key: &[u8], deadline: u64, cond: ExpireCondition) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        let entry = match data.get_mut(key) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        if !cond.allows(entry.expires_at(), deadline) {
+            return Ok(false);
+        }
+
+        if deadline <= now_ms() {
+            data.remove(key);
+        } else {
+            data.set_expires_at(key, Some(deadline));
+        }
+        Ok(true)
+    }
+
+    /// Get the unix time in milliseconds at which the key expires.
+    /// Returns `None` if the key doesn't exist, and `Some(None)` if it has no TTL.
+    pub fn expires_at(&self, key: &[u8]) -> Result<Option<Option<u64>>, DBError> {
+        self.with_entry(key, |entry| Ok(entry.map(|entry| entry.expires_at())))
+    }
+
+    /// Remove the TTL of the key. Returns true if the key had a TTL.
+    pub fn persist(&self, key: &[u8]) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        Ok(data.set_expires_at(key, None).is_some())
+    }
+
+    /// Runs one cycle of active expiration, removing keys and hash fields whose TTL has
+    /// elapsed even if they are never accessed again. Returns the number of removed keys
+    /// and the number of removed hash fields.
+    ///
+    /// Like Redis, the cycle samples random keys having a TTL in small batches and keeps
+    /// going as long as a significant part of each batch was expired, which means that
+    /// many more expired keys are likely to be left. The hashes having fields with a TTL
+    /// are then sampled the same way. The write lock is released between batches and the
+    /// cycle stops once the time budget is exhausted.
+    pub fn active_expire_cycle(&self, time_budget: Duration) -> Result<(usize, usize), DBError> {
+        let deadline = Instant::now() + time_budget;
+
+        let keys = self.active_expire_batches(deadline, |data, count| {
+            let (sampled, expired) = data.expire_random_keys(count);
+            (sampled, expired, expired)
+        })?;
+        let fields = self.active_expire_batches(deadline, Keyspace::expire_random_hash_fields)?;
+        Ok((keys, fields))
+    }
+
+    // Runs batches of `expire` until a batch finds few stale keys or the deadline is
+    // reached. `expire` returns the number of keys sampled by the batch, the number of them
+    // that were stale, and the number of items it removed. Returns the number of items
+    // removed by all the batches.
+    fn active_expire_batches(
+        &self,
+        deadline: Instant,
+        expire: impl Fn(&mut Keyspace, usize) -> (usize, usize, usize),
+    ) -> Result<usize, DBError> {
+        let mut expired = 0;
+
+        loop {
+            let (sampled, stale, removed) = expire(&mut *self.write()?, ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP);
+            expired += removed;
+
+            if stale * 100 <= sampled * ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE
+                || Instant::now() >= deadline
+            {
+                return Ok(expired);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{Entry, Value};
+    use bytes::Bytes;
+
+    fn cond(flags: &str) -> ExpireCondition {
+        ExpireCondition {
+            nx: flags.contains("nx"),
+            xx: flags.contains("xx"),
+            gt: flags.contains("gt"),
+            lt: flags.contains("lt"),
+        }
+    }
+
+    // Stores a string at the key, expiring at the deadline even if it has elapsed.
+    fn set(db: &DB, key: &'static str, expires_at: Option<u64>) {
+        let entry = Entry::with_expires_at(Value::String(Bytes::from("v")), expires_at);
+        db.write().unwrap().insert(Bytes::from(key), entry);
+    }
+
+    #[test]
+    fn checks_the_expire_conditions() {
+        assert!(cond("").allows(None, 10));
+        assert!(cond("").allows(Some(20), 10));
+
+        assert!(cond("nx").allows(None, 10));
+        assert!(!cond("nx").allows(Some(20), 10));
+        assert!(!cond("xx").allows(None, 10));
+        assert!(cond("xx").allows(Some(20), 10));
+
+        // a key without TTL has an infinite one
+        assert!(!cond("gt").allows(None, 10));
+        assert!(cond("gt").allows(Some(5), 10));
+        assert!(!cond("gt").allows(Some(10), 10));
+        assert!(!cond("gt").allows(Some(20), 10));
+        assert!(cond("lt").allows(None, 10));
+        assert!(cond("lt").allows(Some(20), 10));
+        assert!(!cond("lt").allows(Some(10), 10));
+        assert!(!cond("lt").allows(Some(5), 10));
+
+        assert!(!cond("xx gt").allows(None, 10));
+        assert!(cond("xx gt").allows(Some(5), 10));
+        assert!(!cond("xx lt").allows(None, 10));
+        assert!(cond("xx lt").allows(Some(20), 10));
+    }
+
+    #[test]
+    fn expires_keys_conditionally() {
+        let db = DB::new();
+        let later = now_ms() + 100_000;
+        assert!(!db.expire_at(b"k", later, cond("")).unwrap());
+
+        set(&db, "k", None);
+        assert!(!db.expire_at(b"k", later, cond("xx")).unwrap());
+        assert!(!db.expire_at(b"k", later, cond("gt")).unwrap());
+        assert!(db.expire_at(b"k", later, cond("lt")).unwrap());
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(Some(later)));
+
+        assert!(!db.expire_at(b"k", later + 1, cond("nx")).unwrap());
+        assert!(!db.expire_at(b"k", later + 1, cond("lt")).unwrap());
+        assert!(db.expire_at(b"k", later + 1, cond("gt")).unwrap());
+        assert!(db.expire_at(b"k", later, cond("xx")).unwrap());
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(Some(later)));
+    }
+
+    #[test]
+    fn deletes_keys_expiring_in_the_past() {
+        let db = DB::new();
+        set(&db, "k", None);
+        assert!(db.expire_at(b"k", now_ms() - 1, cond("")).unwrap());
+        assert_eq!(db.expires_at(b"k").unwrap(), None);
+
+        // a negative time given to EXPIRE is a deadline of zero
+        set(&db, "k", None);
+        assert!(db.expire_at(b"k", 0, cond("")).unwrap());
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), None);
+
+        // unless the condition isn't met
+        set(&db, "k", Some(now_ms() + 100_000));
+        assert!(!db.expire_at(b"k", 0, cond("gt")).unwrap());
+        assert!(db.get(&Bytes::from("k")).unwrap().is_some());
+    }
+
+    #[test]
+    fn reports_and_removes_ttls() {
+        let db = DB::new();
+        assert_eq!(db.expires_at(b"k").unwrap(), None);
+        assert!(!db.persist(b"k").unwrap());
+
+        set(&db, "k", None);
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(None));
+        assert!(!db.persist(b"k").unwrap());
+
+        let deadline = now_ms() + 100_000;
+        set(&db, "k", Some(deadline));
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(Some(deadline)));
+
+        assert!(db.persist(b"k").unwrap());
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(None));
+        assert_eq!(db.write().unwrap().expire_random_keys(20), (0, 0));
+    }
+
+    #[test]
+    fn expires_keys_lazily() {
+        let db = DB::new();
+        set(&db, "k", Some(now_ms() - 1));
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), None);
+        assert_eq!(db.expires_at(b"k").unwrap(), None);
+        assert!(!db.persist(b"k").unwrap());
+        // the read removed the key from the index of the keys having a TTL
+        assert_eq!(db.write().unwrap().expire_random_keys(20), (0, 0));
+    }
+
+    #[test]
+    fn expires_keys_actively() {
+        let db = DB::new();
+        for key in ["a", "b", "c"] {
+            set(&db, key, Some(now_ms() - 1));
+        }
+        // keys without TTL are never sampled
+        set(&db, "d", None);
+        assert_eq!(db.active_expire_cycle(Duration::from_secs(1)).unwrap(), (3, 0));
+        assert_eq!(db.write().unwrap().expire_random_keys(20), (0, 0));
+        assert!(db.get(&Bytes::from("d")).unwrap().is_some());
+    }
+}
@@ -0,0 +1,574 @@
+use bytes::Bytes;
+use std::collections::{BTreeSet, HashMap};
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::expire::ExpireCondition;
+use crate::storage::DBError;
+use crate::utils::{self, now_ms};
+
+/// The `Hash` struct holds the fields of a hash and their values.
+///
+/// Like keys, fields may have a TTL. Expired fields are treated as missing by every
+/// read, and are removed by the next write to the hash or by the active expiration cycle.
+#[derive(Debug, Clone, Default)]
+pub struct Hash {
+    fields: HashMap<Bytes, Bytes>,
+    /// Unix time in milliseconds at which the fields having a TTL expire.
+    expires: HashMap<Bytes, u64>,
+    /// The fields having a TTL ordered by deadline, so the expired ones come first.
+    deadlines: BTreeSet<(u64, Bytes)>,
+}
+
+/// The outcome of setting the TTL of a hash field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldExpiry {
+    /// The field doesn't exist.
+    NoField,
+    /// The TTL wasn't updated because the condition isn't met.
+    NotMet,
+    /// The TTL was updated.
+    Updated,
+    /// The deadline is in the past, so the field was deleted.
+    Deleted,
+}
+
+impl Hash {
+    /// Returns the value of the field, unless it's missing or expired.
+    pub fn get(&self, field: &[u8], now: u64) -> Option<&Bytes> {
+        match self.expires.get(field) {
+            Some(t) if *t <= now => None,
+            _ => self.fields.get(field),
+        }
+    }
+
+    /// Returns the number of fields which aren't expired.
+    pub fn len(&self, now: u64) -> usize {
+        self.fields.len() - self.deadlines.iter().take_while(|(t, _)| *t <= now).count()
+    }
+
+    /// Checks if the hash holds no fields, including the expired ones.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Iterates over the fields which aren't expired and their values.
+    pub fn iter(&self, now: u64) -> impl Iterator<Item = (&Bytes, &Bytes)> {
+        self.fields
+            .iter()
+            .filter(move |(field, _)| self.expires.get(*field).is_none_or(|t| *t > now))
+    }
+
+    /// Checks if every field of the hash has expired.
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.deadlines.len() == self.fields.len()
+            && self.deadlines.last().is_some_and(|(t, _)| *t <= now)
+    }
+
+    /// Checks if some fields of the hash have a TTL.
+    pub fn has_volatile_fields(&self) -> bool {
+        !self.deadlines.is_empty()
+    }
+
+    /// Sets the value of the field, clearing its TTL. Returns the previous value.
+    pub fn insert(&mut self, field: Bytes, value: Bytes) -> Option<Bytes> {
+        self.set_expires_at(&field, None);
+        self.fields.insert(field, value)
+    }
+
+    /// Sets the value of the field, keeping its TTL. Returns the previous value.
+    pub fn update(&mut self, field: Bytes, value: Bytes) -> Option<Bytes> {
+        self.fields.insert(field, value)
+    }
+
+    /// Removes the field, returning its value.
+    pub fn remove(&mut self, field: &[u8]) -> Option<Bytes> {
+        self.set_expires_at(field, None);
+        self.fields.remove(field)
+    }
+
+    /// Returns the unix time in milliseconds at which the field expires, if it has a TTL.
+    pub fn expires_at(&self, field: &[u8]) -> Option<u64> {
+        self.expires.get(field).copied()
+    }
+
+    /// Sets or clears the TTL of an existing field. Returns the previous deadline.
+    pub fn set_expires_at(&mut self, field: &[u8], expires_at: Option<u64>) -> Option<u64> {
+        let previous = self.expires.remove(field);
+        if let Some(t) = previous {
+            let (_, field) = self.deadlines.take(&(t, Bytes::copy_from_slice(field))).unwrap();
+            if let Some(t) = expires_at {
+                self.expires.insert(field.clone(), t);
+                self.deadlines.insert((t, field));
+            }
+        } else if let Some(t) = expires_at {
+            let (field, _) = self.fields.get_key_value(field).expect("field must exist");
+            self.expires.insert(field.clone(), t);
+            self.deadlines.insert((t, field.clone()));
+        }
+        previous
+    }
+
+    /// Removes the fields whose TTL has elapsed. Returns the number of removed fields.
+    pub fn remove_expired(&mut self, now: u64) -> usize {
+        let mut removed = 0;
+        while self.deadlines.first().is_some_and(|(t, _)| *t <= now) {
+            let (_, field) = self.deadlines.pop_first().unwrap();
+            self.expires.remove(&field);
+            self.fields.remove(&field);
+            removed += 1;
+        }
+        removed
+    }
+}
+
+impl DB {
+    /// Set the fields of the hash stored at the key, creating it if missing.
+    /// Updated fields lose their TTL. Returns the number of fields added, not counting the updated ones.
+    pub fn hset(&self, key: Bytes, pairs: Vec<(Bytes, Bytes)>) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let hash = hash_or_insert(&mut data, key)?;
+        Ok(pairs
+            .into_iter()
+            .filter(|(field, value)| hash.insert(field.clone(), value.clone()).is_none())
+            .count())
+    }
+
+    /// Set the field of the hash only if it doesn't exist yet. Returns true if it was set.
+    pub fn hsetnx(&self, key: Bytes, field: Bytes, value: Bytes) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        if let Some(hash) = hash_mut(&mut data, &key)? {
+            if hash.get(&field, now_ms()).is_some() {
+                return Ok(false);
+            }
+        }
+        hash_or_insert(&mut data, key)?.insert(field, value);
+        Ok(true)
+    }
+
+    /// Get the values of the fields of the hash. Missing fields are returned as `None`.
+    pub fn hmget(&self, key: &[u8], fields: &[Bytes]) -> Result<Vec<Option<Bytes>>, DBError> {
+        self.with_hash(key, |hash| {
+            let now = now_ms();
+            fields
+                .iter()
+                .map(|field| hash.and_then(|hash| hash.get(field, now).cloned()))
+                .collect()
+        })
+    }
+
+    /// Delete the fields of the hash, and the key once the hash is empty.
+    /// Returns the number of deleted fields.
+    pub fn hdel(&self, key: &[u8], fields: &[Bytes]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let hash = match hash_mut(&mut data, key)? {
+            Some(hash) => hash,
+            None => return Ok(0),
+        };
+        let deleted = fields.iter().filter(|field| hash.remove(field).is_some()).count();
+        if hash.is_empty() {
+            data.remove(key);
+        }
+        Ok(deleted)
+    }
+
+    /// Get the number of fields of the hash, zero if missing.
+    pub fn hlen(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_hash(key, |hash| hash.map_or(0, |hash| hash.len(now_ms())))
+    }
+
+    /// Get all the fields and values of the hash.
+    pub fn hgetall(&self, key: &[u8]) -> Result<Vec<(Bytes, Bytes)>, DBError> {
+        self.with_hash(key, |hash| {
+            hash.into_iter()
+                .flat_map(|hash| hash.iter(now_ms()))
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect()
+        })
+    }
+
+    /// Increment the integer stored in the field of the hash by `delta`, keeping its TTL.
+    /// A missing field counts as zero. Returns the new value.
+    pub fn hincrby(&self, key: Bytes, field: Bytes, delta: i64) -> Result<i64, DBError> {
+        let mut data = self.write()?;
+
+        let current = match hash_mut(&mut data, &key)?.and_then(|hash| hash.get(&field, now_ms())) {
+            Some(value) => utils::parse_i64(value)
+                .ok_or_else(|| DBError::Other(String::from("hash value is not an integer")))?,
+            None => 0,
+        };
+        let new = current.checked_add(delta).ok_or(DBError::Overflow)?;
+
+        hash_or_insert(&mut data, key)?.update(field, Bytes::from(new.to_string()));
+        Ok(new)
+    }
+
+    /// Increment the floating point number stored in the field of the hash by `delta`,
+    /// keeping its TTL. A missing field counts as zero. Returns the new value, formatted
+    /// the same way it is stored.
+    pub fn hincrbyfloat(&self, key: Bytes, field: Bytes, delta: f64) -> Result<Bytes, DBError> {
+        let mut data = self.write()?;
+
+        let current = match hash_mut(&mut data, &key)?.and_then(|hash| hash.get(&field, now_ms())) {
+            Some(value) => utils::parse_f64(value)
+                .ok_or_else(|| DBError::Other(String::from("hash value is not a float")))?,
+            None => 0.0,
+        };
+        if !(current + delta).is_finite() {
+            return Err(DBError::NanOrInfinity);
+        }
+
+        let new = Bytes::from(utils::add_f64_human(current, delta));
+        hash_or_insert(&mut data, key)?.update(field, new.clone());
+        Ok(new)
+    }
+
+    /// Get random fields of the hash and their values.
+    ///
+    /// With a positive `count` the fields are distinct, so fewer than `count` are returned
+    /// when the hash is smaller. With a negative one exactly `-count` fields are returned,
+    /// possibly several times.
+    pub fn hrandfield(&self, key: &[u8], count: i64) -> Result<Vec<(Bytes, Bytes)>, DBError> {
+        if count < 0 {
+            // the fields aren't indexed, so they're copied out to be picked from once the
+            // lock is released
+            let pairs: Vec<(Bytes, Bytes)> = self.with_hash(key, |hash| {
+                hash.into_iter()
+                    .flat_map(|hash| hash.iter(now_ms()))
+                    .map(|(field, value)| (field.clone(), value.clone()))
+                    .collect()
+            })?;
+            if pairs.is_empty() {
+                return Ok(pairs);
+            }
+            return Ok((0..count.unsigned_abs())
+                .map(|_| pairs[utils::random_index(pairs.len())].clone())
+                .collect());
+        }
+
+        self.with_hash(key, |hash| {
+            let now = now_ms();
+            let len = hash.map_or(0, |hash| hash.len(now));
+            let hash = match hash {
+                Some(hash) if len > 0 => hash,
+                _ => return Vec::new(),
+            };
+            let pairs = hash.iter(now).map(|(field, value)| (field.clone(), value.clone()));
+            if count as usize >= len {
+                return pairs.collect();
+            }
+
+            // reservoir sampling
+            let count = count as usize;
+            let mut picked = Vec::with_capacity(count);
+            for (i, pair) in pairs.enumerate() {
+                if i < count {
+                    picked.push(pair);
+                } else {
+                    let j = utils::random_index(i + 1);
+                    if j < count {
+                        picked[j] = pair;
+                    }
+                }
+            }
+            utils::shuffle(&mut picked);
+            picked
+        })
+    }
+
+    /// Set the unix time in milliseconds at which each of the fields expires.
+    /// A deadline in the past deletes the fields right away, and the key once the hash is empty.
+    pub fn hexpire_at(
+        &self,
+        key: &[u8],
+        fields: &[Bytes],
+        deadline: u64,
+        cond: ExpireCondition,
+    ) -> Result<Vec<FieldExpiry>, DBError> {
+        let mut data = self.write()?;
+
+        let hash = match hash_mut(&mut data, key)? {
+            Some(hash) => hash,
+            None => return Ok(vec![FieldExpiry::NoField; fields.len()]),
+        };
+        let expired = deadline <= now_ms();
+        let results = fields
+            .iter()
+            .map(|field| {
+                if !hash.fields.contains_key(field) {
+                    FieldExpiry::NoField
+                } else if !cond.allows(hash.expires_at(field), deadline) {
+                    FieldExpiry::NotMet
+                } else if expired {
+                    hash.remove(field);
+                    FieldExpiry::Deleted
+                } else {
+                    hash.set_expires_at(field, Some(deadline));
+                    FieldExpiry::Updated
+                }
+            })
+            .collect();
+
+        if hash.is_empty() {
+            data.remove(key);
+        } else if hash.has_volatile_fields() {
+            data.add_volatile_hash(key);
+        }
+        Ok(results)
+    }
+
+    /// Get the unix time in milliseconds at which each of the fields expires.
+    /// A missing field is returned as `None`, and a field without TTL as `Some(None)`.
+    pub fn hexpires_at(&self, key: &[u8], fields: &[Bytes]) -> Result<Vec<Option<Option<u64>>>, DBError> {
+        self.with_hash(key, |hash| {
+            let now = now_ms();
+            fields
+                .iter()
+                .map(|field| {
+                    let hash = hash?;
+                    hash.get(field, now)?;
+                    Some(hash.expires_at(field))
+                })
+                .collect()
+        })
+    }
+
+    /// Remove the TTL of each of the fields. A missing field is returned as `None`,
+    /// otherwise whether it had a TTL.
+    pub fn hpersist(&self, key: &[u8], fields: &[Bytes]) -> Result<Vec<Option<bool>>, DBError> {
+        let mut data = self.write()?;
+
+        let hash = match hash_mut(&mut data, key)? {
+            Some(hash) => hash,
+            None => return Ok(vec![None; fields.len()]),
+        };
+        Ok(fields
+            .iter()
+            .map(|field| {
+                hash.fields.contains_key(field).then(|| hash.set_expires_at(field, None).is_some())
+            })
+            .collect())
+    }
+
+    // Runs `f` with the hash stored at the key under the read lock,
+    // or `None` if the key doesn't exist.
+    fn with_hash<T>(&self, key: &[u8], f: impl FnOnce(Option<&Hash>) -> T) -> Result<T, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(f(None)),
+            Some(Entry { value: Value::Hash(hash), .. }) => Ok(f(Some(hash))),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+}
+
+/// Returns the hash stored at the key, or `None` if the key doesn't exist.
+/// The expired fields are removed first, and the key too if none is left.
+fn hash_mut<'a>(data: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Hash>, DBError> {
+    let empty = match data.get_mut(key) {
+        None => return Ok(None),
+        Some(Entry { value: Value::Hash(hash), .. }) => {
+            hash.remove_expired(now_ms());
+            hash.is_empty()
+        }
+        Some(_) => return Err(DBError::WrongType),
+    };
+    if empty {
+        data.remove(key);
+        return Ok(None);
+    }
+
+    match data.get_mut(key) {
+        Some(Entry { value: Value::Hash(hash), .. }) => Ok(Some(hash)),
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the hash stored at the key, creating an empty one if missing.
+fn hash_or_insert(data: &mut Keyspace, key: Bytes) -> Result<&mut Hash, DBError> {
+    hash_mut(data, &key)?;
+    match &mut data.get_or_insert_with(key, || Value::Hash(Hash::default())).value {
+        Value::Hash(hash) => Ok(hash),
+        _ => Err(DBError::WrongType),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(pairs: &[(&'static str, &'static str)]) -> Vec<(Bytes, Bytes)> {
+        pairs.iter().map(|(field, value)| (Bytes::from(*field), Bytes::from(*value))).collect()
+    }
+
+    #[test]
+    fn hrandfield_picks_distinct_fields_for_positive_counts() {
+        let db = DB::new();
+        let fields = pairs(&[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]);
+        db.hset(Bytes::from("h"), fields.clone()).unwrap();
+
+        let mut picked = db.hrandfield(b"h", 3).unwrap();
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 3);
+        assert!(picked.iter().all(|pair| fields.contains(pair)));
+
+        let mut picked = db.hrandfield(b"h", 10).unwrap();
+        picked.sort();
+        assert_eq!(picked, fields);
+        assert!(db.hrandfield(b"missing", 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hrandfield_repeats_fields_for_large_negative_counts() {
+        let db = DB::new();
+        let fields = pairs(&[("a", "1"), ("b", "2")]);
+        db.hset(Bytes::from("h"), fields.clone()).unwrap();
+
+        let picked = db.hrandfield(b"h", -1024 * 1024).unwrap();
+        assert_eq!(picked.len(), 1024 * 1024);
+        assert!(picked.iter().all(|pair| fields.contains(pair)));
+        assert!(fields.iter().all(|pair| picked.contains(pair)));
+        assert!(db.hrandfield(b"missing", -1024 * 1024).unwrap().is_empty());
+    }
+
+    fn fields(names: &str) -> Vec<Bytes> {
+        names.split(' ').map(|name| Bytes::from(name.to_string())).collect()
+    }
+
+    fn cond(nx: bool, gt: bool) -> ExpireCondition {
+        ExpireCondition { nx, gt, ..Default::default() }
+    }
+
+    // Makes the TTL of the field elapse, without removing it.
+    fn elapse(db: &DB, key: &str, field: &str) {
+        let mut data = db.write().unwrap();
+        match data.get_mut(key.as_bytes()) {
+            Some(Entry { value: Value::Hash(hash), .. }) => {
+                hash.set_expires_at(field.as_bytes(), Some(now_ms() - 1));
+            }
+            _ => panic!("no hash at {}", key),
+        }
+    }
+
+    // Returns the number of fields stored in the hash, including the expired ones.
+    fn stored_fields(db: &DB, key: &str) -> usize {
+        let data = db.read().unwrap();
+        match data.get(key.as_bytes()) {
+            Some(Entry { value: Value::Hash(hash), .. }) => hash.fields.len(),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn sets_and_reports_field_ttls() {
+        let db = DB::new();
+        db.hset(Bytes::from("h"), pairs(&[("a", "1"), ("b", "2")])).unwrap();
+        let later = now_ms() + 100_000;
+
+        let results = db.hexpire_at(b"h", &fields("a c"), later, cond(false, false)).unwrap();
+        assert_eq!(results, [FieldExpiry::Updated, FieldExpiry::NoField]);
+        // a field without TTL has an infinite one
+        let results = db.hexpire_at(b"h", &fields("a b"), later + 1, cond(false, true)).unwrap();
+        assert_eq!(results, [FieldExpiry::Updated, FieldExpiry::NotMet]);
+        let results = db.hexpire_at(b"h", &fields("a b"), later, cond(true, false)).unwrap();
+        assert_eq!(results, [FieldExpiry::NotMet, FieldExpiry::Updated]);
+        let results = db.hexpire_at(b"missing", &fields("a"), later, cond(false, false)).unwrap();
+        assert_eq!(results, [FieldExpiry::NoField]);
+
+        let ttls = db.hexpires_at(b"h", &fields("a b c")).unwrap();
+        assert_eq!(ttls, [Some(Some(later + 1)), Some(Some(later)), None]);
+        assert_eq!(db.hpersist(b"h", &fields("a a c")).unwrap(), [Some(true), Some(false), None]);
+        assert_eq!(db.hexpires_at(b"h", &fields("a b")).unwrap(), [Some(None), Some(Some(later))]);
+        assert_eq!(db.hpersist(b"missing", &fields("a")).unwrap(), [None]);
+
+        // setting a field clears its TTL
+        db.hset(Bytes::from("h"), pairs(&[("b", "3")])).unwrap();
+        assert_eq!(db.hexpires_at(b"h", &fields("b")).unwrap(), [Some(None)]);
+        assert!(db.read().unwrap().get(b"h").is_some_and(|entry| entry.expires_at().is_none()));
+    }
+
+    #[test]
+    fn deletes_fields_expiring_in_the_past() {
+        let db = DB::new();
+        db.hset(Bytes::from("h"), pairs(&[("a", "1"), ("b", "2")])).unwrap();
+
+        let results = db.hexpire_at(b"h", &fields("a"), 0, cond(false, false)).unwrap();
+        assert_eq!(results, [FieldExpiry::Deleted]);
+        assert_eq!(db.hgetall(b"h").unwrap(), pairs(&[("b", "2")]));
+
+        // deleting the last field deletes the key
+        let results = db.hexpire_at(b"h", &fields("b a"), now_ms() - 1, cond(false, false)).unwrap();
+        assert_eq!(results, [FieldExpiry::Deleted, FieldExpiry::NoField]);
+        assert_eq!(db.expires_at(b"h").unwrap(), None);
+    }
+
+    #[test]
+    fn hides_expired_fields_until_the_next_write_reaps_them() {
+        let db = DB::new();
+        db.hset(Bytes::from("h"), pairs(&[("a", "1"), ("b", "2"), ("c", "3")])).unwrap();
+        db.hexpire_at(b"h", &fields("a b"), now_ms() + 100_000, cond(false, false)).unwrap();
+        elapse(&db, "h", "a");
+
+        assert_eq!(db.hmget(b"h", &fields("a b")).unwrap(), [None, Some(Bytes::from("2"))]);
+        assert_eq!(db.hlen(b"h").unwrap(), 2);
+        assert_eq!(db.hexpires_at(b"h", &fields("a")).unwrap(), [None]);
+        assert_eq!(stored_fields(&db, "h"), 3);
+
+        // the write removes the expired field before running
+        assert_eq!(db.hpersist(b"h", &fields("a b")).unwrap(), [None, Some(true)]);
+        assert_eq!(stored_fields(&db, "h"), 2);
+        db.hset(Bytes::from("h"), pairs(&[("a", "4")])).unwrap();
+        assert_eq!(db.hexpires_at(b"h", &fields("a")).unwrap(), [Some(None)]);
+    }
+
+    #[test]
+    fn deletes_the_key_once_its_last_field_expires() {
+        let db = DB::new();
+        db.hset(Bytes::from("h"), pairs(&[("a", "1"), ("b", "2")])).unwrap();
+        db.hexpire_at(b"h", &fields("a b"), now_ms() + 100_000, cond(false, false)).unwrap();
+        elapse(&db, "h", "a");
+        assert_eq!(db.hlen(b"h").unwrap(), 1);
+
+        elapse(&db, "h", "b");
+        assert_eq!(db.hlen(b"h").unwrap(), 0);
+        assert!(db.hgetall(b"h").unwrap().is_empty());
+        // the read removed the key
+        assert_eq!(stored_fields(&db, "h"), 0);
+        assert_eq!(db.expires_at(b"h").unwrap(), None);
+
+        // and so does a write reaping the fields
+        db.hset(Bytes::from("h"), pairs(&[("a", "1")])).unwrap();
+        db.hexpire_at(b"h", &fields("a"), now_ms() + 100_000, cond(false, false)).unwrap();
+        elapse(&db, "h", "a");
+        assert_eq!(db.hpersist(b"h", &fields("a")).unwrap(), [None]);
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (0, 0, 0));
+    }
+
+    #[test]
+    fn expires_fields_actively() {
+        let db = DB::new();
+        let later = now_ms() + 100_000;
+        db.hset(Bytes::from("h1"), pairs(&[("a", "1"), ("b", "2"), ("c", "3")])).unwrap();
+        db.hexpire_at(b"h1", &fields("a b c"), later, cond(false, false)).unwrap();
+        // hashes without fields having a TTL are never sampled
+        db.hset(Bytes::from("h2"), pairs(&[("a", "1"), ("b", "2")])).unwrap();
+        elapse(&db, "h1", "a");
+        elapse(&db, "h1", "b");
+
+        // the sampled hash had expired fields, two of them
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (1, 1, 2));
+        assert_eq!(stored_fields(&db, "h1"), 1);
+        elapse(&db, "h1", "c");
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (1, 1, 1));
+        assert_eq!(stored_fields(&db, "h1"), 0);
+
+        db.hexpire_at(b"h2", &fields("a"), later, cond(false, false)).unwrap();
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (1, 0, 0));
+        elapse(&db, "h2", "a");
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (1, 1, 1));
+        assert_eq!(db.hgetall(b"h2").unwrap(), pairs(&[("b", "2")]));
+        // the hash has no fields with a TTL anymore
+        assert_eq!(db.write().unwrap().expire_random_hash_fields(20), (0, 0, 0));
+    }
+}
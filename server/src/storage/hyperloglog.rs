@@ -0,0 +1,716 @@
+use bytes::{Bytes, BytesMut};
+use crate::storage::db::{Entry, Value, DB};
+use crate::storage::string::update_bytes;
+use crate::storage::DBError;
+
+// HyperLogLogs are strings laid out exactly like the ones of Redis, so that they can be
+// moved between the two with GET and SET:
+//
+// - a 16 bytes header: the magic `HYLL`, the encoding, 3 unused bytes and the cached
+//   cardinality as a little endian u64, whose most significant bit is set when stale.
+// - 16384 registers of 6 bits in the dense encoding, starting from the least
+//   significant bits of each byte.
+// - or a run length encoding of the registers in the sparse encoding, made of the
+//   opcodes ZERO `00xxxxxx` (1 to 64 zero registers), XZERO `01xxxxxx yyyyyyyy`
+//   (1 to 16384 zero registers) and VAL `1vvvvvxx` (1 to 4 registers set to 1 to 32).
+
+/// The number of bits of the hash selecting the register.
+const HLL_P: u32 = 14;
+/// The number of bits of the hash in which the run of zeros is counted.
+const HLL_Q: usize = 64 - HLL_P as usize;
+const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_HDR_SIZE: usize = 16;
+const HLL_DENSE_SIZE: usize = HLL_HDR_SIZE + (HLL_REGISTERS * 6).div_ceil(8);
+const HLL_DENSE: u8 = 0;
+const HLL_SPARSE: u8 = 1;
+/// The sparse encoding is converted to the dense one past this length, header included,
+/// same as the default `hll-sparse-max-bytes` of Redis.
+const HLL_SPARSE_MAX_BYTES: usize = 3000;
+const HLL_SPARSE_VAL_MAX_VALUE: u8 = 32;
+const HLL_SPARSE_VAL_MAX_LEN: usize = 4;
+const HLL_SPARSE_ZERO_MAX_LEN: usize = 64;
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+/// The seed of the hash of the elements, the one of Redis.
+const HLL_HASH_SEED: u64 = 0xadc8_3b19;
+
+/// An opcode of the sparse encoding, with the number of registers it covers.
+#[derive(Debug, Clone, Copy)]
+enum Opcode {
+    Zero(usize),
+    XZero(usize),
+    Val { value: u8, len: usize },
+}
+
+impl Opcode {
+    /// Decodes the opcode at `p`. A missing second byte of XZERO reads as zero.
+    fn at(hll: &[u8], p: usize) -> Opcode {
+        let byte = hll[p];
+        match byte & 0xc0 {
+            0x00 => Opcode::Zero((byte & 0x3f) as usize + 1),
+            0x40 => {
+                let low = hll.get(p + 1).copied().unwrap_or(0);
+                Opcode::XZero((((byte & 0x3f) as usize) << 8 | low as usize) + 1)
+            }
+            _ => Opcode::Val { value: (byte >> 2 & 0x1f) + 1, len: (byte & 0x3) as usize + 1 },
+        }
+    }
+
+    /// The number of registers covered by the opcode.
+    fn span(&self) -> usize {
+        match *self {
+            Opcode::Zero(len) | Opcode::XZero(len) | Opcode::Val { len, .. } => len,
+        }
+    }
+
+    /// The number of bytes of the opcode.
+    fn size(&self) -> usize {
+        match self {
+            Opcode::XZero(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl DB {
+    /// Add the elements to the HyperLogLog stored at the key, creating it if missing.
+    /// Returns whether the key was created or a register was updated, i.e. whether the
+    /// estimated cardinality may have changed.
+    pub fn pfadd(&self, key: Bytes, elements: &[Bytes]) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        let mut created = false;
+        let entry = data.get_or_insert_with(key, || {
+            created = true;
+            Value::String(empty_hll())
+        });
+        let s = match &mut entry.value {
+            Value::String(s) => s,
+            _ => return Err(DBError::WrongType),
+        };
+        validate(s)?;
+
+        let updated = update_bytes(s, |hll| {
+            let mut updated = false;
+            for element in elements {
+                let (index, count) = pattern(element);
+                updated |= set_register(hll, index, count)?;
+            }
+            if updated {
+                invalidate_cache(hll);
+            }
+            Ok(updated)
+        })?;
+        Ok(created || updated)
+    }
+
+    /// Estimate the number of unique elements added to the HyperLogLog stored at the key,
+    /// or to the union of the HyperLogLogs when there are several keys. Missing keys
+    /// count as empty.
+    ///
+    /// The estimate of a single key is cached in its header until the next update.
+    pub fn pfcount(&self, keys: &[Bytes]) -> Result<u64, DBError> {
+        if let [key] = keys {
+            let mut data = self.write()?;
+
+            let s = match data.get_mut(key) {
+                None => return Ok(0),
+                Some(Entry { value: Value::String(s), .. }) => s,
+                Some(_) => return Err(DBError::WrongType),
+            };
+            validate(s)?;
+            if let Some(count) = cached_count(s) {
+                return Ok(count);
+            }
+
+            let mut registers = vec![0; HLL_REGISTERS];
+            merge(&mut registers, s)?;
+            let count = estimate(&registers);
+            update_bytes(s, |hll| hll[8..HLL_HDR_SIZE].copy_from_slice(&count.to_le_bytes()));
+            return Ok(count);
+        }
+
+        let data = self.read()?;
+        let mut registers = vec![0; HLL_REGISTERS];
+        for key in keys {
+            match data.get(key) {
+                None => {}
+                Some(Entry { value: Value::String(s), .. }) => {
+                    validate(s)?;
+                    merge(&mut registers, s)?;
+                }
+                Some(_) => return Err(DBError::WrongType),
+            }
+        }
+        Ok(estimate(&registers))
+    }
+
+    /// Merge the HyperLogLogs stored at the keys into the one stored at `dest`, creating
+    /// it if missing. The union includes `dest` itself, and is dense if any of the merged
+    /// HyperLogLogs is.
+    pub fn pfmerge(&self, dest: Bytes, keys: &[Bytes]) -> Result<(), DBError> {
+        let mut data = self.write()?;
+
+        let mut registers = vec![0; HLL_REGISTERS];
+        let mut dense = false;
+        for key in std::iter::once(&dest).chain(keys) {
+            match data.get(key) {
+                None => {}
+                Some(Entry { value: Value::String(s), .. }) => {
+                    validate(s)?;
+                    dense |= s[4] == HLL_DENSE;
+                    merge(&mut registers, s)?;
+                }
+                Some(_) => return Err(DBError::WrongType),
+            }
+        }
+
+        let entry = data.get_or_insert_with(dest, || Value::String(empty_hll()));
+        let s = match &mut entry.value {
+            Value::String(s) => s,
+            _ => return Err(DBError::WrongType),
+        };
+        update_bytes(s, |hll| {
+            if dense {
+                sparse_to_dense(hll)?;
+                for (index, &count) in registers.iter().enumerate() {
+                    dense_set(&mut hll[HLL_HDR_SIZE..], index, count);
+                }
+            } else {
+                for (index, &count) in registers.iter().enumerate().filter(|(_, count)| **count > 0) {
+                    set_register(hll, index, count)?;
+                }
+            }
+            invalidate_cache(hll);
+            Ok(())
+        })
+    }
+}
+
+/// A new HyperLogLog: sparse, with all the registers set to zero.
+fn empty_hll() -> Bytes {
+    let mut hll = Vec::with_capacity(HLL_HDR_SIZE + 2);
+    hll.extend_from_slice(b"HYLL");
+    hll.extend_from_slice(&[HLL_SPARSE, 0, 0, 0]);
+    hll.extend_from_slice(&[0; 8]);
+    push_zeros(&mut hll, HLL_REGISTERS);
+    Bytes::from(hll)
+}
+
+/// Checks that the string has the header of a HyperLogLog, and the length of the dense
+/// encoding when it's dense. The sparse encoding is only checked while decoded.
+fn validate(s: &[u8]) -> Result<(), DBError> {
+    let valid = s.len() >= HLL_HDR_SIZE
+        && s.starts_with(b"HYLL")
+        && match s[4] {
+            HLL_DENSE => s.len() == HLL_DENSE_SIZE,
+            HLL_SPARSE => true,
+            _ => false,
+        };
+    if valid { Ok(()) } else { Err(DBError::NotHyperLogLog) }
+}
+
+fn cached_count(hll: &[u8]) -> Option<u64> {
+    let mut count = [0; 8];
+    count.copy_from_slice(&hll[8..HLL_HDR_SIZE]);
+    (count[7] & 0x80 == 0).then(|| u64::from_le_bytes(count))
+}
+
+fn invalidate_cache(hll: &mut [u8]) {
+    hll[15] |= 0x80;
+}
+
+/// Returns the register of the element, and the value it's set to at least: the
+/// position of the first bit set in the rest of its hash.
+fn pattern(element: &[u8]) -> (usize, u8) {
+    let hash = murmur_hash64a(element, HLL_HASH_SEED);
+    let index = (hash & (HLL_REGISTERS as u64 - 1)) as usize;
+    // the extra bit bounds the count to Q + 1
+    let bits = hash >> HLL_P | 1 << HLL_Q;
+    (index, bits.trailing_zeros() as u8 + 1)
+}
+
+/// MurmurHash2, 64 bit version, reading the blocks as little endian like Redis does, so
+/// that the registers don't depend on the architecture.
+fn murmur_hash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let mut blocks = key.chunks_exact(8);
+    for block in &mut blocks {
+        let mut k = [0; 8];
+        k.copy_from_slice(block);
+        let mut k = u64::from_le_bytes(k).wrapping_mul(M);
+        k ^= k >> R;
+        h ^= k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        for (i, &byte) in tail.iter().enumerate() {
+            h ^= (byte as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^ h >> R
+}
+
+/// Sets the register to `count` if it's lower. Returns whether it was updated.
+fn set_register(hll: &mut BytesMut, index: usize, count: u8) -> Result<bool, DBError> {
+    if hll[4] == HLL_SPARSE {
+        return sparse_set(hll, index, count);
+    }
+
+    let registers = &mut hll[HLL_HDR_SIZE..];
+    if dense_get(registers, index) >= count {
+        return Ok(false);
+    }
+    dense_set(registers, index, count);
+    Ok(true)
+}
+
+fn dense_get(registers: &[u8], index: usize) -> u8 {
+    let (byte, shift) = (index * 6 / 8, index * 6 % 8);
+    let low = registers[byte] as u16;
+    let high = registers.get(byte + 1).copied().unwrap_or(0) as u16;
+    ((low >> shift | high << (8 - shift)) & 0x3f) as u8
+}
+
+fn dense_set(registers: &mut [u8], index: usize, count: u8) {
+    let (byte, shift) = (index * 6 / 8, index * 6 % 8);
+    let count = count as u16;
+    registers[byte] = registers[byte] & !((0x3f << shift) as u8) | (count << shift) as u8;
+    if let Some(high) = registers.get_mut(byte + 1) {
+        *high = *high & !((0x3f >> (8 - shift)) as u8) | (count >> (8 - shift)) as u8;
+    }
+}
+
+/// Sets the register of a sparse HyperLogLog to `count` if it's lower, splitting the
+/// opcode covering it in place and merging the VAL opcodes around it, the way Redis does
+/// so that both produce the same bytes. Converts it to dense when `count` is too large
+/// for a VAL opcode, or when it grows too long.
+fn sparse_set(hll: &mut BytesMut, index: usize, count: u8) -> Result<bool, DBError> {
+    if count > HLL_SPARSE_VAL_MAX_VALUE {
+        return promote(hll, index, count);
+    }
+
+    // find the opcode covering the register
+    let (mut p, mut first, mut prev) = (HLL_HDR_SIZE, 0, None);
+    let op = loop {
+        if p >= hll.len() {
+            return Err(DBError::CorruptedHyperLogLog);
+        }
+        let op = Opcode::at(hll, p);
+        if index < first + op.span() {
+            break op;
+        }
+        prev = Some(p);
+        p += op.size();
+        first += op.span();
+    };
+
+    match op {
+        Opcode::Val { value, .. } if value >= count => return Ok(false),
+        Opcode::Val { len: 1, .. } | Opcode::Zero(1) => hll[p] = val_opcode(count, 1),
+        _ => {
+            let last = first + op.span() - 1;
+            let mut seq = Vec::with_capacity(5);
+            match op {
+                Opcode::Val { value, .. } => {
+                    if index != first {
+                        seq.push(val_opcode(value, index - first));
+                    }
+                    seq.push(val_opcode(count, 1));
+                    if index != last {
+                        seq.push(val_opcode(value, last - index));
+                    }
+                }
+                _ => {
+                    if index != first {
+                        push_zeros(&mut seq, index - first);
+                    }
+                    seq.push(val_opcode(count, 1));
+                    if index != last {
+                        push_zeros(&mut seq, last - index);
+                    }
+                }
+            }
+
+            if seq.len() > op.size() && hll.len() + seq.len() - op.size() > HLL_SPARSE_MAX_BYTES {
+                return promote(hll, index, count);
+            }
+            splice(hll, p, op.size(), &seq);
+        }
+    }
+
+    // merge the adjacent VAL opcodes with the same value, scanning up to 5 opcodes
+    // from the one before the update
+    let mut p = prev.unwrap_or(HLL_HDR_SIZE);
+    let mut scan = 5;
+    while p < hll.len() && scan > 0 {
+        scan -= 1;
+        let (value, len) = match Opcode::at(hll, p) {
+            Opcode::Val { value, len } => (value, len),
+            op => {
+                p += op.size();
+                continue;
+            }
+        };
+        match hll.get(p + 1).map(|_| Opcode::at(hll, p + 1)) {
+            Some(Opcode::Val { value: next, len: next_len })
+                if next == value && len + next_len <= HLL_SPARSE_VAL_MAX_LEN =>
+            {
+                // stay on the merged opcode to try merging it with the next one
+                splice(hll, p, 2, &[val_opcode(value, len + next_len)]);
+            }
+            _ => p += 1,
+        }
+    }
+    Ok(true)
+}
+
+/// Converts the sparse HyperLogLog to dense, then sets the register.
+fn promote(hll: &mut BytesMut, index: usize, count: u8) -> Result<bool, DBError> {
+    sparse_to_dense(hll)?;
+    dense_set(&mut hll[HLL_HDR_SIZE..], index, count);
+    Ok(true)
+}
+
+/// Converts the HyperLogLog to the dense encoding, unless it already is.
+fn sparse_to_dense(hll: &mut BytesMut) -> Result<(), DBError> {
+    if hll[4] == HLL_DENSE {
+        return Ok(());
+    }
+
+    let mut registers = vec![0; HLL_REGISTERS];
+    merge(&mut registers, hll)?;
+    let mut dense = BytesMut::zeroed(HLL_DENSE_SIZE);
+    dense[..HLL_HDR_SIZE].copy_from_slice(&hll[..HLL_HDR_SIZE]);
+    dense[4] = HLL_DENSE;
+    for (index, &count) in registers.iter().enumerate() {
+        dense_set(&mut dense[HLL_HDR_SIZE..], index, count);
+    }
+    *hll = dense;
+    Ok(())
+}
+
+/// Raises each of the registers to the value of the same register of the HyperLogLog.
+fn merge(registers: &mut [u8], hll: &[u8]) -> Result<(), DBError> {
+    if hll[4] == HLL_DENSE {
+        for (index, register) in registers.iter_mut().enumerate() {
+            *register = (*register).max(dense_get(&hll[HLL_HDR_SIZE..], index));
+        }
+        return Ok(());
+    }
+
+    let (mut p, mut index) = (HLL_HDR_SIZE, 0);
+    while p < hll.len() {
+        let op = Opcode::at(hll, p);
+        if let Opcode::Val { value, len } = op {
+            if index + len > HLL_REGISTERS {
+                break;
+            }
+            for register in &mut registers[index..index + len] {
+                *register = (*register).max(value);
+            }
+        }
+        p += op.size();
+        index += op.span();
+    }
+    if index != HLL_REGISTERS {
+        return Err(DBError::CorruptedHyperLogLog);
+    }
+    Ok(())
+}
+
+/// Estimates the cardinality from the registers, with the improved estimator of Otmar
+/// Ertl used by Redis ("New cardinality estimation algorithms for HyperLogLog
+/// sketches").
+fn estimate(registers: &[u8]) -> u64 {
+    let mut histogram = [0u32; 64];
+    for &register in registers {
+        histogram[register as usize] += 1;
+    }
+
+    let m = HLL_REGISTERS as f64;
+    let mut z = m * tau((m - histogram[HLL_Q + 1] as f64) / m);
+    for &count in histogram[1..=HLL_Q].iter().rev() {
+        z += count as f64;
+        z *= 0.5;
+    }
+    z += m * sigma(histogram[0] as f64 / m);
+    (HLL_ALPHA_INF * m * m / z).round() as u64
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x >= 1.0 {
+        return f64::INFINITY;
+    }
+    let (mut y, mut z) = (1.0, x);
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x <= 0.0 || x >= 1.0 {
+        return 0.0;
+    }
+    let (mut y, mut z) = (1.0, 1.0 - x);
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+/// Appends the opcode of a run of zero registers, XZERO when too long for ZERO.
+fn push_zeros(seq: &mut Vec<u8>, len: usize) {
+    let len = len - 1;
+    if len < HLL_SPARSE_ZERO_MAX_LEN {
+        seq.push(len as u8);
+    } else {
+        seq.extend_from_slice(&[0x40 | (len >> 8) as u8, len as u8]);
+    }
+}
+
+fn val_opcode(value: u8, len: usize) -> u8 {
+    0x80 | (value - 1) << 2 | (len - 1) as u8
+}
+
+/// Replaces the `len` bytes at `p` with `seq`.
+fn splice(hll: &mut BytesMut, p: usize, len: usize, seq: &[u8]) {
+    let end = hll.len();
+    let new_len = end - len + seq.len();
+    if new_len > end {
+        hll.resize(new_len, 0);
+    }
+    hll.copy_within(p + len..end, p + seq.len());
+    hll.truncate(new_len);
+    hll[p..p + seq.len()].copy_from_slice(seq);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A sparse HyperLogLog made of the given opcodes.
+    fn sparse(opcodes: &[u8]) -> BytesMut {
+        let mut hll = BytesMut::from(&b"HYLL\x01\x00\x00\x00"[..]);
+        hll.extend_from_slice(&[0; 8]);
+        hll.extend_from_slice(opcodes);
+        hll
+    }
+
+    fn elements(names: &[&'static str]) -> Vec<Bytes> {
+        names.iter().map(|name| Bytes::from(*name)).collect()
+    }
+
+    fn registers_of(hll: &[u8]) -> Vec<u8> {
+        let mut registers = vec![0; HLL_REGISTERS];
+        merge(&mut registers, hll).unwrap();
+        registers
+    }
+
+    #[test]
+    fn hashes_like_redis() {
+        assert_eq!(murmur_hash64a(b"", HLL_HASH_SEED), 0xd8df_ea65_85bc_9732);
+        assert_eq!(murmur_hash64a(b"a", HLL_HASH_SEED), 0x53d2_470a_9b43_b1a7);
+        assert_eq!(murmur_hash64a(b"foo", HLL_HASH_SEED), 0xe646_09b8_b014_1cb4);
+        assert_eq!(murmur_hash64a(b"hello world", HLL_HASH_SEED), 0xa919_bc30_51f6_24b7);
+        assert_eq!(murmur_hash64a(b"0123456789abcdef", HLL_HASH_SEED), 0x9f85_6542_8eaa_573d);
+
+        assert_eq!(pattern(b"a"), (12711, 2));
+        assert_eq!(pattern(b"foo"), (7348, 5));
+    }
+
+    #[test]
+    fn pfadd_writes_the_sparse_bytes_of_redis() {
+        let db = DB::new();
+        let key = Bytes::from("hll");
+
+        assert!(db.pfadd(key.clone(), &[]).unwrap());
+        assert_eq!(db.get(&key).unwrap().unwrap(), &b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\0\x7f\xff"[..]);
+
+        // XZERO 12711, VAL 2 and XZERO 3672, with the cache invalidated
+        assert!(db.pfadd(key.clone(), &[Bytes::from("a")]).unwrap());
+        assert_eq!(
+            db.get(&key).unwrap().unwrap(),
+            &b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\x80\x71\xa6\x84\x4e\x57"[..]
+        );
+        assert!(!db.pfadd(key, &[Bytes::from("a")]).unwrap());
+    }
+
+    #[test]
+    fn splits_the_opcode_covering_the_register() {
+        // in a run of zeros
+        let mut hll = BytesMut::from(&empty_hll()[..]);
+        assert!(sparse_set(&mut hll, 100, 3).unwrap());
+        assert_eq!(hll[HLL_HDR_SIZE..], [0x40, 0x63, 0x88, 0x7f, 0x9a]);
+
+        // in a run of equal values
+        let mut hll = sparse(&[0x87, 0x7f, 0xfb]);
+        assert!(sparse_set(&mut hll, 1, 5).unwrap());
+        assert_eq!(hll[HLL_HDR_SIZE..], [0x84, 0x90, 0x85, 0x7f, 0xfb]);
+
+        // never lowering a register
+        assert!(!sparse_set(&mut hll, 2, 2).unwrap());
+        assert!(!sparse_set(&mut hll, 1, 4).unwrap());
+        assert_eq!(hll[HLL_HDR_SIZE..], [0x84, 0x90, 0x85, 0x7f, 0xfb]);
+    }
+
+    #[test]
+    fn merges_adjacent_val_opcodes() {
+        // VAL 2, ZERO 1, VAL 2 becomes a single VAL 2 of length 3
+        let mut hll = sparse(&[0x84, 0x00, 0x84, 0x7f, 0xfc]);
+        assert!(sparse_set(&mut hll, 1, 2).unwrap());
+        assert_eq!(hll[HLL_HDR_SIZE..], [0x86, 0x7f, 0xfc]);
+
+        // up to 4 registers per opcode
+        let mut hll = sparse(&[0x86, 0x00, 0x84, 0x7f, 0xfb]);
+        assert!(sparse_set(&mut hll, 3, 2).unwrap());
+        assert_eq!(hll[HLL_HDR_SIZE..], [0x87, 0x84, 0x7f, 0xfb]);
+    }
+
+    #[test]
+    fn promotes_to_dense_past_the_sparse_max_bytes() {
+        // every register set alone adds a ZERO and a VAL opcode
+        let mut hll = BytesMut::from(&empty_hll()[..]);
+        for i in 0..1491 {
+            assert!(sparse_set(&mut hll, 4 * i, 1).unwrap());
+        }
+        assert_eq!(hll[4], HLL_SPARSE);
+        assert_eq!(hll.len(), 2999);
+
+        let registers = registers_of(&hll);
+        assert!(sparse_set(&mut hll, 4 * 1491, 1).unwrap());
+        assert_eq!(hll[4], HLL_DENSE);
+        assert_eq!(hll.len(), HLL_DENSE_SIZE);
+        let mut expected = registers;
+        expected[4 * 1491] = 1;
+        assert_eq!(registers_of(&hll), expected);
+    }
+
+    #[test]
+    fn promotes_to_dense_for_values_too_large_for_val() {
+        let mut hll = sparse(&[0xfc, 0x7f, 0xfe]);
+        assert!(sparse_set(&mut hll, 1, HLL_SPARSE_VAL_MAX_VALUE + 1).unwrap());
+        assert_eq!(hll[4], HLL_DENSE);
+        assert_eq!(hll.len(), HLL_DENSE_SIZE);
+
+        let registers = registers_of(&hll);
+        assert_eq!(registers[..3], [32, 33, 0]);
+        assert_eq!(registers.iter().filter(|register| **register > 0).count(), 2);
+    }
+
+    #[test]
+    fn packs_dense_registers_across_bytes() {
+        let mut registers = vec![0; HLL_DENSE_SIZE - HLL_HDR_SIZE];
+        for index in 0..4 {
+            dense_set(&mut registers, index, 0x3f);
+        }
+        assert_eq!(registers[..4], [0xff, 0xff, 0xff, 0x00]);
+
+        dense_set(&mut registers, 1, 0);
+        assert_eq!(registers[..3], [0x3f, 0xf0, 0xff]);
+        assert_eq!([0, 1, 2, 3].map(|index| dense_get(&registers, index)), [0x3f, 0, 0x3f, 0x3f]);
+
+        dense_set(&mut registers, HLL_REGISTERS - 1, 0x2a);
+        assert_eq!(registers[registers.len() - 2..], [0x00, 0xa8]);
+        assert_eq!(dense_get(&registers, HLL_REGISTERS - 1), 0x2a);
+        assert_eq!(dense_get(&registers, HLL_REGISTERS - 2), 0);
+    }
+
+    #[test]
+    fn caches_the_count_until_updated() {
+        let db = DB::new();
+        let key = Bytes::from("hll");
+        let elements = elements(&["a", "b", "c", "d", "e", "f", "g"]);
+        db.pfadd(key.clone(), &elements).unwrap();
+        let header = |db: &DB| db.get(&key).unwrap().unwrap()[8..HLL_HDR_SIZE].to_vec();
+        assert_eq!(header(&db)[7] & 0x80, 0x80);
+
+        assert_eq!(db.pfcount(std::slice::from_ref(&key)).unwrap(), 7);
+        assert_eq!(header(&db), 7u64.to_le_bytes());
+
+        // adding a known element keeps the cache
+        db.pfadd(key.clone(), &elements[..1]).unwrap();
+        assert_eq!(header(&db), 7u64.to_le_bytes());
+
+        db.pfadd(key.clone(), &[Bytes::from("h")]).unwrap();
+        assert_eq!(header(&db)[7] & 0x80, 0x80);
+        assert_eq!(db.pfcount(std::slice::from_ref(&key)).unwrap(), 8);
+        assert_eq!(header(&db), 8u64.to_le_bytes());
+    }
+
+    #[test]
+    fn counts_the_union_of_several_keys() {
+        let db = DB::new();
+        let (a, b) = (Bytes::from("a"), Bytes::from("b"));
+        db.pfadd(a.clone(), &elements(&["a", "b", "c", "d", "e", "f", "g"])).unwrap();
+        db.pfadd(b.clone(), &elements(&["e", "f", "g", "h", "i", "j", "k"])).unwrap();
+        let before = (db.get(&a).unwrap(), db.get(&b).unwrap());
+
+        assert_eq!(db.pfcount(&[a.clone(), b.clone()]).unwrap(), 11);
+        assert_eq!(db.pfcount(&[a.clone(), Bytes::from("missing"), b.clone()]).unwrap(), 11);
+        assert_eq!(db.pfcount(&[Bytes::from("missing"), Bytes::from("other")]).unwrap(), 0);
+        // the union isn't cached in the keys
+        assert_eq!((db.get(&a).unwrap(), db.get(&b).unwrap()), before);
+    }
+
+    #[test]
+    fn merges_a_dense_source_into_a_sparse_destination() {
+        let db = DB::new();
+        let (dest, src) = (Bytes::from("dest"), Bytes::from("src"));
+        db.pfadd(dest.clone(), &[Bytes::from("a"), Bytes::from("foo")]).unwrap();
+
+        let mut dense = BytesMut::zeroed(HLL_DENSE_SIZE);
+        dense[..4].copy_from_slice(b"HYLL");
+        dense[4] = HLL_DENSE;
+        dense_set(&mut dense[HLL_HDR_SIZE..], 5, 40);
+        dense_set(&mut dense[HLL_HDR_SIZE..], 7348, 3);
+        db.mset(vec![(src.clone(), dense.freeze())], false).unwrap();
+        let union = db.pfcount(&[dest.clone(), src.clone()]).unwrap();
+
+        db.pfmerge(dest.clone(), std::slice::from_ref(&src)).unwrap();
+        let hll = db.get(&dest).unwrap().unwrap();
+        assert_eq!(hll[4], HLL_DENSE);
+        assert_eq!(hll.len(), HLL_DENSE_SIZE);
+        assert_eq!(hll[15] & 0x80, 0x80);
+
+        let registers = registers_of(&hll);
+        assert_eq!([registers[5], registers[7348], registers[12711]], [40, 5, 2]);
+        assert_eq!(registers.iter().filter(|register| **register > 0).count(), 3);
+        assert_eq!(db.pfcount(&[dest]).unwrap(), union);
+    }
+
+    #[test]
+    fn rejects_corrupted_sparse_encodings() {
+        let db = DB::new();
+        let corrupted = [
+            // covering one register too few
+            &b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\x80\x7f\xfe"[..],
+            // overrunning the last register
+            &b"HYLL\x01\0\0\0\0\0\0\0\0\0\0\x80\x7f\xfe\x81"[..],
+        ];
+        for hll in corrupted {
+            let key = Bytes::from("hll");
+            db.mset(vec![(key.clone(), Bytes::from(hll))], false).unwrap();
+            let err = db.pfcount(&[key]).unwrap_err();
+            assert_eq!(err.to_string(), "INVALIDOBJ Corrupted HLL object detected");
+        }
+    }
+}
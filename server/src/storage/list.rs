@@ -0,0 +1,492 @@
+use bytes::Bytes;
+use std::collections::VecDeque;
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::DBError;
+
+/// One of the two ends of a list.
+#[derive(Debug, Clone, Copy)]
+pub enum ListEnd {
+    Left,
+    Right,
+}
+
+impl DB {
+    /// Left push elements associated with a key.
+    pub fn lpush(&self, key: Bytes, values: Vec<Bytes>) -> Result<usize, DBError> {
+        self.push(key, values, ListEnd::Left)
+    }
+
+    /// Right push elements associated with a key.
+    pub fn rpush(&self, key: Bytes, values: Vec<Bytes>) -> Result<usize, DBError> {
+        self.push(key, values, ListEnd::Right)
+    }
+
+    /// Push elements to one end of the list stored at the key, creating it if missing.
+    /// Clients blocked on the key are served right away. Returns the length of the list.
+    pub fn push(&self, key: Bytes, values: Vec<Bytes>, end: ListEnd) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let len = data.push_list(key.clone(), values, end)?;
+        data.serve_blocked(key);
+        Ok(len)
+    }
+
+    /// Pop up to `count` elements from one end of the list stored at the key.
+    /// Returns `None` if the key doesn't exist.
+    pub fn pop(&self, key: &[u8], end: ListEnd, count: usize) -> Result<Option<Vec<Bytes>>, DBError> {
+        self.write()?.pop_list(key, end, count)
+    }
+
+    /// Atomically pop an element from one end of the `src` list and push it to one end
+    /// of the `dest` list, which may be the same. Returns `None` if `src` doesn't exist.
+    pub fn lmove(&self, src: &[u8], dest: Bytes, from: ListEnd, to: ListEnd) -> Result<Option<Bytes>, DBError> {
+        let mut data = self.write()?;
+
+        let elem = data.move_list(src, dest.clone(), from, to)?;
+        if elem.is_some() {
+            data.serve_blocked(dest);
+        }
+        Ok(elem)
+    }
+
+    /// Get the length of the list stored at the key, zero if missing.
+    pub fn llen(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_list(key, |list| list.map_or(0, VecDeque::len))
+    }
+
+    /// Get the element at the index. Negative indexes count from the tail of the list.
+    pub fn lindex(&self, key: &[u8], index: i64) -> Result<Option<Bytes>, DBError> {
+        self.with_list(key, |list| {
+            let list = list?;
+            list.get(resolve_index(list.len(), index)?).cloned()
+        })
+    }
+
+    /// Set the element at the index. Negative indexes count from the tail of the list.
+    pub fn lset(&self, key: &[u8], index: i64, value: Bytes) -> Result<(), DBError> {
+        let mut data = self.write()?;
+
+        let list = match data.get_mut(key) {
+            None => return Err(DBError::Other(String::from("no such key"))),
+            Some(Entry { value: Value::List(list), .. }) => list,
+            Some(_) => return Err(DBError::WrongType),
+        };
+        match resolve_index(list.len(), index) {
+            Some(i) => {
+                list[i] = value;
+                Ok(())
+            }
+            None => Err(DBError::Other(String::from("index out of range"))),
+        }
+    }
+
+    /// Insert the value before or after the first occurrence of `pivot`.
+    /// Returns the length of the list, -1 if the pivot wasn't found, or 0 if the key doesn't exist.
+    pub fn linsert(&self, key: &[u8], before: bool, pivot: &[u8], value: Bytes) -> Result<i64, DBError> {
+        let mut data = self.write()?;
+
+        let list = match data.get_mut(key) {
+            None => return Ok(0),
+            Some(Entry { value: Value::List(list), .. }) => list,
+            Some(_) => return Err(DBError::WrongType),
+        };
+        match list.iter().position(|item| item == pivot) {
+            Some(i) => {
+                list.insert(if before { i } else { i + 1 }, value);
+                Ok(list.len() as i64)
+            }
+            None => Ok(-1),
+        }
+    }
+
+    /// Remove occurrences of the value: the first `count` ones from the head when positive,
+    /// the last `-count` ones from the tail when negative, or all of them when zero.
+    /// Returns the number of removed elements.
+    pub fn lrem(&self, key: &[u8], count: i64, value: &[u8]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let removed = with_list_mut(&mut data, key, |list| {
+            let limit = match count {
+                0 => usize::MAX,
+                count => count.unsigned_abs().try_into().unwrap_or(usize::MAX),
+            };
+            let mut removed = 0;
+            let mut keep = |item: &Bytes| {
+                let matched = removed < limit && item == value;
+                removed += matched as usize;
+                !matched
+            };
+
+            if count >= 0 {
+                list.retain(keep);
+            } else {
+                // walk from the tail, keeping the elements in their original order
+                let mut kept: VecDeque<Bytes> = VecDeque::with_capacity(list.len());
+                while let Some(item) = list.pop_back() {
+                    if keep(&item) {
+                        kept.push_front(item);
+                    }
+                }
+                *list = kept;
+            }
+            removed
+        })?;
+        Ok(removed.unwrap_or(0))
+    }
+
+    /// Trim the list to the elements between `start` and `stop`, both included.
+    /// Negative offsets count from the tail of the list.
+    pub fn ltrim(&self, key: &[u8], start: i64, stop: i64) -> Result<(), DBError> {
+        let mut data = self.write()?;
+
+        with_list_mut(&mut data, key, |list| match resolve_range(list.len(), start, stop) {
+            Some((start, stop)) => {
+                list.truncate(stop + 1);
+                list.drain(..start);
+            }
+            None => list.clear(),
+        })?;
+        Ok(())
+    }
+
+    /// Get the elements between `start` and `stop`, both included.
+    /// Negative offsets count from the tail of the list.
+    pub fn lrange(&self, key: &[u8], start: i64, stop: i64) -> Result<Vec<Bytes>, DBError> {
+        self.with_list(key, |list| {
+            let list = match list {
+                Some(list) => list,
+                None => return Vec::new(),
+            };
+            match resolve_range(list.len(), start, stop) {
+                Some((start, stop)) => list.range(start..=stop).cloned().collect(),
+                None => Vec::new(),
+            }
+        })
+    }
+
+    /// Find the indexes of the elements equal to the value.
+    ///
+    /// The `rank` selects the first match to return, counting from the head when positive
+    /// or from the tail when negative, and at most `count` matches are returned (all of
+    /// them when zero). Only the first `maxlen` elements scanned are compared (all of them
+    /// when zero).
+    pub fn lpos(
+        &self,
+        key: &[u8],
+        value: &[u8],
+        rank: i64,
+        count: usize,
+        maxlen: usize,
+    ) -> Result<Vec<usize>, DBError> {
+        self.with_list(key, |list| {
+            let list = match list {
+                Some(list) => list,
+                None => return Vec::new(),
+            };
+            let count = if count == 0 { usize::MAX } else { count };
+            let maxlen = if maxlen == 0 { usize::MAX } else { maxlen };
+            let skip = (rank.unsigned_abs() - 1).try_into().unwrap_or(usize::MAX);
+
+            let indexes: Box<dyn Iterator<Item = usize>> = match rank > 0 {
+                true => Box::new(0..list.len()),
+                false => Box::new((0..list.len()).rev()),
+            };
+            indexes
+                .take(maxlen)
+                .filter(|&i| list[i] == value)
+                .skip(skip)
+                .take(count)
+                .collect()
+        })
+    }
+
+    // Runs `f` with the list stored at the key under the read lock,
+    // or `None` if the key doesn't exist.
+    fn with_list<T>(&self, key: &[u8], f: impl FnOnce(Option<&VecDeque<Bytes>>) -> T) -> Result<T, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(f(None)),
+            Some(Entry { value: Value::List(list), .. }) => Ok(f(Some(list))),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+}
+
+impl Keyspace {
+    /// Push elements to one end of the list stored at the key, creating it if missing.
+    /// Returns the length of the list.
+    pub(crate) fn push_list(&mut self, key: Bytes, values: Vec<Bytes>, end: ListEnd) -> Result<usize, DBError> {
+        let entry = self.get_or_insert_with(key, || Value::List(VecDeque::new()));
+
+        if let Value::List(list) = &mut entry.value {
+            for item in values {
+                match end {
+                    ListEnd::Left => list.push_front(item),
+                    ListEnd::Right => list.push_back(item),
+                }
+            }
+            return Ok(list.len())
+        }
+
+        Err(DBError::WrongType)
+    }
+
+    /// Pop up to `count` elements from one end of the list stored at the key.
+    /// Returns `None` if the key doesn't exist.
+    pub(crate) fn pop_list(&mut self, key: &[u8], end: ListEnd, count: usize) -> Result<Option<Vec<Bytes>>, DBError> {
+        with_list_mut(self, key, |list| {
+            let count = count.min(list.len());
+            match end {
+                ListEnd::Left => list.drain(..count).collect(),
+                ListEnd::Right => list.drain(list.len() - count..).rev().collect(),
+            }
+        })
+    }
+
+    /// Pop an element from one end of the `src` list and push it to one end of the `dest`
+    /// list, which may be the same. Returns `None` if `src` doesn't exist.
+    pub(crate) fn move_list(
+        &mut self,
+        src: &[u8],
+        dest: Bytes,
+        from: ListEnd,
+        to: ListEnd,
+    ) -> Result<Option<Bytes>, DBError> {
+        match self.get(src) {
+            None => return Ok(None),
+            Some(Entry { value: Value::List(_), .. }) => {}
+            Some(_) => return Err(DBError::WrongType),
+        }
+        // check the destination before popping, so that nothing is lost on error
+        if let Some(entry) = self.get(&dest) {
+            if !matches!(entry.value, Value::List(_)) {
+                return Err(DBError::WrongType);
+            }
+        }
+
+        if src == dest.as_ref() {
+            // rotate the list in place, so the key keeps its TTL even with a single element
+            let elem = with_list_mut(self, src, |list| {
+                let elem = match from {
+                    ListEnd::Left => list.pop_front(),
+                    ListEnd::Right => list.pop_back(),
+                }?;
+                match to {
+                    ListEnd::Left => list.push_front(elem.clone()),
+                    ListEnd::Right => list.push_back(elem.clone()),
+                }
+                Some(elem)
+            })?;
+            return Ok(elem.flatten());
+        }
+
+        let elem = match self.pop_list(src, from, 1)?.and_then(|mut elems| elems.pop()) {
+            Some(elem) => elem,
+            None => return Ok(None),
+        };
+        self.push_list(dest, vec![elem.clone()], to)?;
+        Ok(Some(elem))
+    }
+}
+
+/// Runs `f` with the list stored at the key, removing the key if the list is left empty.
+/// Returns `None` if the key doesn't exist.
+fn with_list_mut<T>(
+    data: &mut Keyspace,
+    key: &[u8],
+    f: impl FnOnce(&mut VecDeque<Bytes>) -> T,
+) -> Result<Option<T>, DBError> {
+    let list = match data.get_mut(key) {
+        None => return Ok(None),
+        Some(Entry { value: Value::List(list), .. }) => list,
+        Some(_) => return Err(DBError::WrongType),
+    };
+
+    let result = f(list);
+    if list.is_empty() {
+        data.remove(key);
+    }
+    Ok(Some(result))
+}
+
+/// Converts an index which may count from the tail into an index from the head,
+/// or `None` if it's out of range.
+fn resolve_index(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (index >= 0 && index < len as i64).then_some(index as usize)
+}
+
+/// Converts a range which may count from the tail into indexes from the head,
+/// clamped to the list, or `None` if the range is empty.
+pub(crate) fn resolve_range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 { (len + start).max(0) } else { start };
+    let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+    (start <= stop && start < len).then_some((start as usize, stop as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::blocking::BlockingOp;
+    use crate::utils::now_ms;
+
+    fn db_with(key: &'static str, elems: &str) -> DB {
+        let db = DB::new();
+        db.rpush(Bytes::from(key), values(elems)).unwrap();
+        db
+    }
+
+    fn values(elems: &str) -> Vec<Bytes> {
+        elems.split(' ').map(|elem| Bytes::from(elem.to_string())).collect()
+    }
+
+    fn lmove(db: &DB, src: &str, dest: &'static str, from: ListEnd, to: ListEnd) -> Option<Bytes> {
+        db.lmove(src.as_bytes(), Bytes::from(dest), from, to).unwrap()
+    }
+
+    fn list(db: &DB, key: &str) -> Vec<Bytes> {
+        db.lrange(key.as_bytes(), 0, -1).unwrap()
+    }
+
+    #[test]
+    fn pushes_and_pops_at_both_ends() {
+        let db = DB::new();
+        assert_eq!(db.lpush(Bytes::from("l"), values("b a")).unwrap(), 2);
+        assert_eq!(db.rpush(Bytes::from("l"), values("c d")).unwrap(), 4);
+        assert_eq!(list(&db, "l"), values("a b c d"));
+
+        assert_eq!(db.pop(b"l", ListEnd::Left, 1).unwrap(), Some(values("a")));
+        assert_eq!(db.pop(b"l", ListEnd::Right, 2).unwrap(), Some(values("d c")));
+        assert_eq!(db.pop(b"l", ListEnd::Right, 5).unwrap(), Some(values("b")));
+        // the emptied list is deleted
+        assert_eq!(db.pop(b"l", ListEnd::Left, 1).unwrap(), None);
+        assert_eq!(db.llen(b"l").unwrap(), 0);
+    }
+
+    #[test]
+    fn reads_and_writes_by_index() {
+        let db = db_with("l", "a b c");
+        assert_eq!(db.lindex(b"l", 0).unwrap(), Some(Bytes::from("a")));
+        assert_eq!(db.lindex(b"l", -1).unwrap(), Some(Bytes::from("c")));
+        assert_eq!(db.lindex(b"l", 3).unwrap(), None);
+        assert_eq!(db.lindex(b"l", -4).unwrap(), None);
+
+        db.lset(b"l", -2, Bytes::from("B")).unwrap();
+        assert_eq!(list(&db, "l"), values("a B c"));
+        assert_eq!(db.lset(b"l", 3, Bytes::from("d")).unwrap_err().to_string(), "ERR index out of range");
+        assert_eq!(db.lset(b"missing", 0, Bytes::from("d")).unwrap_err().to_string(), "ERR no such key");
+
+        assert_eq!(db.lrange(b"l", 1, 100).unwrap(), values("B c"));
+        assert_eq!(db.lrange(b"l", -100, 0).unwrap(), values("a"));
+        assert!(db.lrange(b"l", 2, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn inserts_around_a_pivot() {
+        let db = db_with("l", "a c a");
+        assert_eq!(db.linsert(b"l", true, b"c", Bytes::from("b")).unwrap(), 4);
+        assert_eq!(db.linsert(b"l", false, b"a", Bytes::from("x")).unwrap(), 5);
+        assert_eq!(list(&db, "l"), values("a x b c a"));
+        assert_eq!(db.linsert(b"l", true, b"z", Bytes::from("y")).unwrap(), -1);
+        assert_eq!(db.linsert(b"missing", true, b"a", Bytes::from("y")).unwrap(), 0);
+    }
+
+    #[test]
+    fn removes_occurrences_from_either_end() {
+        let db = db_with("l", "a b a c a");
+        assert_eq!(db.lrem(b"l", 1, b"a").unwrap(), 1);
+        assert_eq!(list(&db, "l"), values("b a c a"));
+        assert_eq!(db.lrem(b"l", -1, b"a").unwrap(), 1);
+        assert_eq!(list(&db, "l"), values("b a c"));
+        assert_eq!(db.lrem(b"l", 0, b"z").unwrap(), 0);
+
+        let db = db_with("l", "a b a");
+        assert_eq!(db.lrem(b"l", 0, b"a").unwrap(), 2);
+        assert_eq!(db.lrem(b"l", i64::MIN, b"b").unwrap(), 1);
+        assert_eq!(db.llen(b"l").unwrap(), 0);
+    }
+
+    #[test]
+    fn trims_to_a_range() {
+        let db = db_with("l", "a b c d e");
+        db.ltrim(b"l", 1, -2).unwrap();
+        assert_eq!(list(&db, "l"), values("b c d"));
+        db.ltrim(b"l", -100, 100).unwrap();
+        assert_eq!(list(&db, "l"), values("b c d"));
+        db.ltrim(b"l", 2, 1).unwrap();
+        assert_eq!(db.llen(b"l").unwrap(), 0);
+    }
+
+    #[test]
+    fn finds_the_positions_of_an_element() {
+        let db = db_with("l", "a b c 1 2 3 c c");
+        assert_eq!(db.lpos(b"l", b"c", 1, 1, 0).unwrap(), [2]);
+        assert_eq!(db.lpos(b"l", b"c", 1, 0, 0).unwrap(), [2, 6, 7]);
+        assert_eq!(db.lpos(b"l", b"c", 2, 0, 0).unwrap(), [6, 7]);
+        assert_eq!(db.lpos(b"l", b"c", -1, 2, 0).unwrap(), [7, 6]);
+        assert_eq!(db.lpos(b"l", b"c", -3, 1, 0).unwrap(), [2]);
+        assert!(db.lpos(b"l", b"c", 4, 1, 0).unwrap().is_empty());
+        assert!(db.lpos(b"l", b"c", i64::MIN, 1, 0).unwrap().is_empty());
+
+        // MAXLEN limits the elements scanned, from the end the search starts at
+        assert_eq!(db.lpos(b"l", b"c", 1, 0, 3).unwrap(), [2]);
+        assert!(db.lpos(b"l", b"c", 1, 0, 2).unwrap().is_empty());
+        assert_eq!(db.lpos(b"l", b"c", -1, 0, 2).unwrap(), [7, 6]);
+        assert!(db.lpos(b"missing", b"c", 1, 0, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn moves_elements_between_lists() {
+        let db = db_with("src", "a b c");
+        assert_eq!(lmove(&db, "src", "dest", ListEnd::Right, ListEnd::Left), Some(Bytes::from("c")));
+        assert_eq!(lmove(&db, "src", "dest", ListEnd::Left, ListEnd::Right), Some(Bytes::from("a")));
+        assert_eq!(list(&db, "src"), values("b"));
+        assert_eq!(list(&db, "dest"), values("c a"));
+
+        // moving the last element deletes the source
+        lmove(&db, "src", "dest", ListEnd::Left, ListEnd::Left);
+        assert_eq!(db.llen(b"src").unwrap(), 0);
+        assert_eq!(list(&db, "dest"), values("b c a"));
+        assert_eq!(lmove(&db, "src", "dest", ListEnd::Left, ListEnd::Left), None);
+    }
+
+    #[test]
+    fn rotates_a_list_moved_onto_itself() {
+        let db = db_with("l", "a b c");
+        let deadline = now_ms() + 100_000;
+        db.write().unwrap().set_expires_at(b"l", Some(deadline));
+
+        assert_eq!(lmove(&db, "l", "l", ListEnd::Right, ListEnd::Left), Some(Bytes::from("c")));
+        assert_eq!(list(&db, "l"), values("c a b"));
+        assert_eq!(lmove(&db, "l", "l", ListEnd::Left, ListEnd::Left), Some(Bytes::from("c")));
+        assert_eq!(list(&db, "l"), values("c a b"));
+        assert_eq!(db.expires_at(b"l").unwrap(), Some(Some(deadline)));
+
+        let db = db_with("l", "a");
+        db.write().unwrap().set_expires_at(b"l", Some(deadline));
+        lmove(&db, "l", "l", ListEnd::Left, ListEnd::Right);
+        assert_eq!(db.expires_at(b"l").unwrap(), Some(Some(deadline)));
+    }
+
+    #[test]
+    fn keeps_the_element_when_the_destination_has_another_type() {
+        let db = db_with("src", "a");
+        db.sadd(Bytes::from("s"), values("m")).unwrap();
+        assert!(db.lmove(b"src", Bytes::from("s"), ListEnd::Left, ListEnd::Left).is_err());
+        assert_eq!(list(&db, "src"), values("a"));
+        assert!(db.lmove(b"s", Bytes::from("src"), ListEnd::Left, ListEnd::Left).is_err());
+    }
+
+    #[test]
+    fn pops_from_the_first_non_empty_list() {
+        let db = db_with("b", "x y z");
+        let keys = values("a b");
+        let op = BlockingOp::Pop { end: ListEnd::Right, count: 2 };
+        let served = db.pop_first(&keys, &op).unwrap().unwrap();
+        assert_eq!((served.key, served.elems), (Bytes::from("b"), values("z y")));
+
+        let op = BlockingOp::Pop { end: ListEnd::Left, count: 10 };
+        assert_eq!(db.pop_first(&keys, &op).unwrap().unwrap().elems, values("x"));
+        assert!(db.pop_first(&keys, &op).unwrap().is_none());
+    }
+}
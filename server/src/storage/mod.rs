@@ -1,14 +1,46 @@
 use core::fmt;
 
+pub mod bitmap;
+pub mod blocking;
 pub mod db;
+pub mod expire;
+pub mod hash;
+pub mod hyperloglog;
+pub mod list;
+pub mod set;
+pub mod skiplist;
+pub mod sorted_set;
+pub mod stream;
+pub mod string;
 
 /// Represents errors that can occur during DB operations.
+/// Like `CommandError`, every error renders the Redis reply text including its prefix.
 #[derive(Debug)]
 pub enum DBError {
     /// Represents an error where wrong data type is encountered against a key.
     /// For e.g. If you try to perform list related operation (such as lpush, rpush) on a key
     /// which stores a string value.
     WrongType,
+    /// The value stored at the key can't be parsed as an integer.
+    NotInteger,
+    /// The value stored at the key can't be parsed as a floating point number.
+    NotFloat,
+    /// The result of an integer operation doesn't fit into 64 bits.
+    Overflow,
+    /// The result of a floating point operation is NaN or infinite.
+    NanOrInfinity,
+    /// The string would grow beyond the maximum allowed length.
+    StringTooLong,
+    /// A consumer group with the same name already exists on the stream.
+    BusyGroup,
+    /// The stream or its consumer group doesn't exist. Holds the message after the prefix,
+    /// which depends on the command.
+    NoGroup(String),
+    /// The string stored at the key isn't a HyperLogLog.
+    NotHyperLogLog,
+    /// The string stored at the key looks like a HyperLogLog, but its registers can't
+    /// be decoded.
+    CorruptedHyperLogLog,
     /// Represents any other error with a descriptive message.
     Other(String),
 }
@@ -17,9 +49,22 @@ impl fmt::Display for DBError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DBError::WrongType => {
-                "WRONG TYPE Operation against a key holding the wrong kind of value".fmt(f)
+                "WRONGTYPE Operation against a key holding the wrong kind of value".fmt(f)
             }
-            DBError::Other(msg) => msg.as_str().fmt(f),
+            DBError::NotInteger => "ERR value is not an integer or out of range".fmt(f),
+            DBError::NotFloat => "ERR value is not a valid float".fmt(f),
+            DBError::Overflow => "ERR increment or decrement would overflow".fmt(f),
+            DBError::NanOrInfinity => "ERR increment would produce NaN or Infinity".fmt(f),
+            DBError::StringTooLong => {
+                "ERR string exceeds maximum allowed size (proto-max-bulk-len)".fmt(f)
+            }
+            DBError::BusyGroup => "BUSYGROUP Consumer Group name already exists".fmt(f),
+            DBError::NoGroup(msg) => write!(f, "NOGROUP {}", msg),
+            DBError::NotHyperLogLog => {
+                "WRONGTYPE Key is not a valid HyperLogLog string value.".fmt(f)
+            }
+            DBError::CorruptedHyperLogLog => "INVALIDOBJ Corrupted HLL object detected".fmt(f),
+            DBError::Other(msg) => write!(f, "ERR {}", msg),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,446 @@
+use bytes::Bytes;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::DBError;
+use crate::utils;
+
+/// The `Set` struct holds the members of a set.
+///
+/// Members are kept in a vector along with their position in it, so that random
+/// members can be picked and popped in constant time, like the volatile keys.
+#[derive(Debug, Clone, Default)]
+pub struct Set {
+    members: Vec<Bytes>,
+    positions: HashMap<Bytes, usize>,
+}
+
+/// The operations combining sets.
+#[derive(Debug, Clone, Copy)]
+pub enum SetOp {
+    /// The members of all the sets.
+    Inter,
+    /// The members of any of the sets.
+    Union,
+    /// The members of the first set which aren't in any of the others.
+    Diff,
+}
+
+impl Set {
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, member: &Q) -> bool
+    where
+        Bytes: Borrow<Q>,
+    {
+        self.positions.contains_key(member)
+    }
+
+    /// Adds the member. Returns false if it was already present.
+    pub fn insert(&mut self, member: Bytes) -> bool {
+        if self.positions.contains_key(&member) {
+            return false;
+        }
+        self.positions.insert(member.clone(), self.members.len());
+        self.members.push(member);
+        true
+    }
+
+    /// Removes the member. Returns false if it wasn't present.
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, member: &Q) -> bool
+    where
+        Bytes: Borrow<Q>,
+    {
+        match self.positions.remove(member) {
+            Some(pos) => {
+                self.remove_at(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Bytes> {
+        self.members.iter()
+    }
+
+    /// Returns a random member. The set must not be empty.
+    fn random(&self) -> &Bytes {
+        &self.members[utils::random_index(self.members.len())]
+    }
+
+    /// Removes and returns a random member, or `None` if the set is empty.
+    fn pop_random(&mut self) -> Option<Bytes> {
+        if self.members.is_empty() {
+            return None;
+        }
+        let member = self.remove_at(utils::random_index(self.members.len()));
+        self.positions.remove(&member);
+        Some(member)
+    }
+
+    // Removes the member at `pos`, moving the last member into the hole.
+    fn remove_at(&mut self, pos: usize) -> Bytes {
+        let member = self.members.swap_remove(pos);
+        if let Some(moved) = self.members.get(pos) {
+            self.positions.insert(moved.clone(), pos);
+        }
+        member
+    }
+}
+
+impl FromIterator<Bytes> for Set {
+    fn from_iter<I: IntoIterator<Item = Bytes>>(iter: I) -> Self {
+        let mut set = Set::default();
+        for member in iter {
+            set.insert(member);
+        }
+        set
+    }
+}
+
+impl IntoIterator for Set {
+    type Item = Bytes;
+    type IntoIter = std::vec::IntoIter<Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Set {
+    type Item = &'a Bytes;
+    type IntoIter = std::slice::Iter<'a, Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.iter()
+    }
+}
+
+impl DB {
+    /// Add the members to the set stored at the key, creating it if missing.
+    /// Returns the number of members added, not counting the ones already present.
+    pub fn sadd(&self, key: Bytes, members: Vec<Bytes>) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let set = match &mut data.get_or_insert_with(key, || Value::Set(Set::default())).value {
+            Value::Set(set) => set,
+            _ => return Err(DBError::WrongType),
+        };
+        Ok(members.into_iter().filter(|member| set.insert(member.clone())).count())
+    }
+
+    /// Remove the members from the set, and the key once the set is empty.
+    /// Returns the number of removed members.
+    pub fn srem(&self, key: &[u8], members: &[Bytes]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let set = match set_mut(&mut data, key)? {
+            Some(set) => set,
+            None => return Ok(0),
+        };
+        let removed = members.iter().filter(|member| set.remove(*member)).count();
+        if set.is_empty() {
+            data.remove(key);
+        }
+        Ok(removed)
+    }
+
+    /// Get all the members of the set.
+    pub fn smembers(&self, key: &[u8]) -> Result<Vec<Bytes>, DBError> {
+        self.with_set(key, |set| set.into_iter().flatten().cloned().collect())
+    }
+
+    /// Check whether each of the members is part of the set.
+    pub fn smismember(&self, key: &[u8], members: &[Bytes]) -> Result<Vec<bool>, DBError> {
+        self.with_set(key, |set| {
+            members
+                .iter()
+                .map(|member| set.is_some_and(|set| set.contains(member)))
+                .collect()
+        })
+    }
+
+    /// Get the number of members of the set, zero if missing.
+    pub fn scard(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_set(key, |set| set.map_or(0, Set::len))
+    }
+
+    /// Remove and return up to `count` random members of the set, deleting the key
+    /// once the set is empty.
+    pub fn spop(&self, key: &[u8], count: usize) -> Result<Vec<Bytes>, DBError> {
+        let mut data = self.write()?;
+
+        let set = match set_mut(&mut data, key)? {
+            Some(set) => set,
+            None => return Ok(Vec::new()),
+        };
+        let popped: Vec<Bytes> = std::iter::from_fn(|| set.pop_random()).take(count).collect();
+        if set.is_empty() {
+            data.remove(key);
+        }
+        Ok(popped)
+    }
+
+    /// Get random members of the set.
+    ///
+    /// With a positive `count` the members are distinct, so fewer than `count` are returned
+    /// when the set is smaller. With a negative one exactly `-count` members are returned,
+    /// possibly several times.
+    pub fn srandmember(&self, key: &[u8], count: i64) -> Result<Vec<Bytes>, DBError> {
+        self.with_set(key, |set| {
+            let set = match set {
+                Some(set) if !set.is_empty() => set,
+                _ => return Vec::new(),
+            };
+
+            if count < 0 {
+                // the parser bounds the number of repeated members
+                return (0..count.unsigned_abs()).map(|_| set.random().clone()).collect();
+            }
+
+            let count = (count as usize).min(set.len());
+            if count == set.len() {
+                return set.iter().cloned().collect();
+            }
+            // pick the members when they're few, otherwise the ones left out
+            if count <= set.len() / 2 {
+                random_positions(set.len(), count)
+                    .into_iter()
+                    .map(|pos| set.members[pos].clone())
+                    .collect()
+            } else {
+                let skipped = random_positions(set.len(), set.len() - count);
+                let mut picked: Vec<Bytes> = set
+                    .iter()
+                    .enumerate()
+                    .filter(|(pos, _)| !skipped.contains(pos))
+                    .map(|(_, member)| member.clone())
+                    .collect();
+                utils::shuffle(&mut picked);
+                picked
+            }
+        })
+    }
+
+    /// Atomically move the member from the `src` set to the `dest` set, creating it if
+    /// missing. Returns false if the member isn't part of `src`.
+    pub fn smove(&self, src: &[u8], dest: Bytes, member: Bytes) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        // both keys must hold sets, even if there's nothing to move
+        set_mut(&mut data, &dest)?;
+        let set = match set_mut(&mut data, src)? {
+            Some(set) => set,
+            None => return Ok(false),
+        };
+        if src == dest.as_ref() {
+            return Ok(set.contains(&member));
+        }
+
+        if !set.remove(&member) {
+            return Ok(false);
+        }
+        if set.is_empty() {
+            data.remove(src);
+        }
+        match &mut data.get_or_insert_with(dest, || Value::Set(Set::default())).value {
+            Value::Set(set) => set.insert(member),
+            _ => unreachable!("the type of dest was checked"),
+        };
+        Ok(true)
+    }
+
+    /// Get the result of the operation combining the sets stored at the keys.
+    /// Missing keys are treated as empty sets.
+    pub fn set_op(&self, op: SetOp, keys: &[Bytes]) -> Result<Vec<Bytes>, DBError> {
+        let data = self.read()?;
+
+        Ok(combine(&data, op, keys)?.into_iter().collect())
+    }
+
+    /// Store the result of the operation combining the sets stored at the keys into `dest`,
+    /// replacing it. An empty result deletes `dest`. Returns the size of the result.
+    pub fn set_op_store(&self, op: SetOp, dest: Bytes, keys: &[Bytes]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let result = combine(&data, op, keys)?;
+        let len = result.len();
+        if result.is_empty() {
+            data.remove(&dest);
+        } else {
+            data.insert(dest, Entry::new(Value::Set(result)));
+        }
+        Ok(len)
+    }
+
+    /// Get the size of the intersection of the sets stored at the keys, without computing it.
+    /// The count stops at `limit`, unless it's zero.
+    pub fn sintercard(&self, keys: &[Bytes], limit: usize) -> Result<usize, DBError> {
+        let data = self.read()?;
+
+        let sets = match lookup_sets(&data, keys)? {
+            Some(sets) => sets,
+            None => return Ok(0),
+        };
+        let (smallest, others) = split_smallest(sets);
+        let count = smallest
+            .iter()
+            .filter(|member| others.iter().all(|set| set.contains(*member)))
+            .take(if limit == 0 { usize::MAX } else { limit })
+            .count();
+        Ok(count)
+    }
+
+    // Runs `f` with the set stored at the key under the read lock,
+    // or `None` if the key doesn't exist.
+    fn with_set<T>(&self, key: &[u8], f: impl FnOnce(Option<&Set>) -> T) -> Result<T, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(f(None)),
+            Some(Entry { value: Value::Set(set), .. }) => Ok(f(Some(set))),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+}
+
+/// Returns the set stored at the key, or `None` if the key doesn't exist.
+fn set_mut<'a>(data: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Set>, DBError> {
+    match data.get_mut(key) {
+        None => Ok(None),
+        Some(Entry { value: Value::Set(set), .. }) => Ok(Some(set)),
+        Some(_) => Err(DBError::WrongType),
+    }
+}
+
+/// Returns the sets stored at the keys, or `None` for each missing key.
+/// Fails if any of the keys holds another type.
+fn lookup_all<'a>(data: &'a Keyspace, keys: &[Bytes]) -> Result<Vec<Option<&'a Set>>, DBError> {
+    keys.iter()
+        .map(|key| match data.get(key) {
+            None => Ok(None),
+            Some(Entry { value: Value::Set(set), .. }) => Ok(Some(set)),
+            Some(_) => Err(DBError::WrongType),
+        })
+        .collect()
+}
+
+/// Returns the sets stored at the keys, or `None` if any of them is missing,
+/// which makes their intersection empty.
+fn lookup_sets<'a>(data: &'a Keyspace, keys: &[Bytes]) -> Result<Option<Vec<&'a Set>>, DBError> {
+    Ok(lookup_all(data, keys)?.into_iter().collect())
+}
+
+/// Returns the smallest of the sets and the others. `sets` must not be empty.
+fn split_smallest(mut sets: Vec<&Set>) -> (&Set, Vec<&Set>) {
+    let smallest = (0..sets.len()).min_by_key(|i| sets[*i].len()).unwrap();
+    let smallest = sets.swap_remove(smallest);
+    (smallest, sets)
+}
+
+/// Combines the sets stored at the keys.
+fn combine(data: &Keyspace, op: SetOp, keys: &[Bytes]) -> Result<Set, DBError> {
+    let result = match op {
+        SetOp::Inter => match lookup_sets(data, keys)? {
+            Some(sets) => {
+                // only the members of the smallest set need to be checked
+                let (smallest, others) = split_smallest(sets);
+                smallest
+                    .iter()
+                    .filter(|member| others.iter().all(|set| set.contains(*member)))
+                    .cloned()
+                    .collect()
+            }
+            None => Set::default(),
+        },
+        SetOp::Union => lookup_all(data, keys)?.into_iter().flatten().flatten().cloned().collect(),
+        SetOp::Diff => {
+            let sets = lookup_all(data, keys)?;
+            match sets.split_first() {
+                Some((Some(first), others)) => first
+                    .iter()
+                    .filter(|member| !others.iter().flatten().any(|set| set.contains(*member)))
+                    .cloned()
+                    .collect(),
+                _ => Set::default(),
+            }
+        }
+    };
+    Ok(result)
+}
+
+/// Picks `count` distinct random positions in `0..len`, `count` being at most half of
+/// `len` so that few picks are rejected.
+fn random_positions(len: usize, count: usize) -> HashSet<usize> {
+    let mut positions = HashSet::with_capacity(count);
+    while positions.len() < count {
+        positions.insert(utils::random_index(len));
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(members: &[&'static str]) -> Vec<Bytes> {
+        members.iter().map(|member| Bytes::from(*member)).collect()
+    }
+
+    #[test]
+    fn srandmember_picks_distinct_members_for_positive_counts() {
+        let db = DB::new();
+        let all = members(&["a", "b", "c", "d", "e", "f"]);
+        db.sadd(Bytes::from("s"), all.clone()).unwrap();
+
+        for count in [1, 2, 3, 4, 5] {
+            let mut picked = db.srandmember(b"s", count).unwrap();
+            picked.sort();
+            picked.dedup();
+            assert_eq!(picked.len(), count as usize);
+            assert!(picked.iter().all(|member| all.contains(member)));
+        }
+        let mut picked = db.srandmember(b"s", 10).unwrap();
+        picked.sort();
+        assert_eq!(picked, all);
+    }
+
+    #[test]
+    fn srandmember_repeats_members_for_large_negative_counts() {
+        let db = DB::new();
+        let all = members(&["a", "b"]);
+        db.sadd(Bytes::from("s"), all.clone()).unwrap();
+
+        let picked = db.srandmember(b"s", -1024 * 1024).unwrap();
+        assert_eq!(picked.len(), 1024 * 1024);
+        assert!(picked.iter().all(|member| all.contains(member)));
+        assert!(all.iter().all(|member| picked.contains(member)));
+        assert!(db.srandmember(b"missing", -1024 * 1024).unwrap().is_empty());
+    }
+
+    #[test]
+    fn spop_removes_the_members_and_the_emptied_key() {
+        let db = DB::new();
+        let all = members(&["a", "b", "c"]);
+        db.sadd(Bytes::from("s"), all.clone()).unwrap();
+
+        let popped = db.spop(b"s", 2).unwrap();
+        assert_eq!(popped.len(), 2);
+        assert_eq!(db.scard(b"s").unwrap(), 1);
+        let mut left = db.smembers(b"s").unwrap();
+        left.extend(popped);
+        left.sort();
+        assert_eq!(left, all);
+
+        assert_eq!(db.spop(b"s", 5).unwrap().len(), 1);
+        // the key is gone, rather than holding an empty set
+        assert_eq!(db.get(&Bytes::from("s")).unwrap(), None);
+        assert!(db.spop(b"s", 1).unwrap().is_empty());
+    }
+}
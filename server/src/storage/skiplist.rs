@@ -0,0 +1,316 @@
+use crate::utils;
+
+/// The maximum number of levels of a node, same as Redis, enough for 2^64 keys.
+const MAX_LEVEL: usize = 32;
+
+/// The `SkipList` struct holds distinct keys in order, like the skiplist of the sorted sets
+/// of Redis.
+///
+/// Every link records the number of keys it skips over, its span, so that the rank of a
+/// key and the key at a rank are found in O(log n), as are the bounds of a range. The
+/// nodes live in a vector and link to each other by index, the first one being the head,
+/// which holds no key.
+#[derive(Debug, Clone)]
+pub struct SkipList<K> {
+    nodes: Vec<Node<K>>,
+    /// The indexes of the removed nodes, reused by the next inserts.
+    free: Vec<usize>,
+    /// The last node, to iterate backwards.
+    tail: Option<usize>,
+    /// The number of levels in use, at least one.
+    level: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<K> {
+    key: Option<K>,
+    backward: Option<usize>,
+    levels: Vec<Link>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    forward: Option<usize>,
+    span: usize,
+}
+
+/// An iterator over a range of ranks of a skiplist, from both ends.
+pub struct Iter<'a, K> {
+    list: &'a SkipList<K>,
+    front: Option<usize>,
+    back: Option<usize>,
+    len: usize,
+}
+
+impl<K> Default for SkipList<K> {
+    fn default() -> Self {
+        let levels = vec![Link { forward: None, span: 0 }; MAX_LEVEL];
+        let head = Node { key: None, backward: None, levels };
+        SkipList { nodes: vec![head], free: Vec::new(), tail: None, level: 1, len: 0 }
+    }
+}
+
+impl<K: Ord> SkipList<K> {
+    /// Adds the key, which must not be present already.
+    pub fn insert(&mut self, key: K) {
+        // the last node before the key at each level, and its rank counted from 1
+        let mut update = [0; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            while let Some(next) = self.forward_if(x, i, |next| *next < key) {
+                rank[i] += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            update[i] = x;
+        }
+
+        let level = random_level();
+        if level > self.level {
+            for i in self.level..level {
+                self.nodes[0].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let backward = (update[0] != 0).then_some(update[0]);
+        let node = Node { key: Some(key), backward, levels: Vec::with_capacity(level) };
+        let x = match self.free.pop() {
+            Some(x) => {
+                self.nodes[x] = node;
+                x
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        for i in 0..level {
+            let prev = self.nodes[update[i]].levels[i];
+            // the new node is `rank[0] - rank[i]` keys past the previous one at this level
+            let before = rank[0] - rank[i];
+            self.nodes[x].levels.push(Link { forward: prev.forward, span: prev.span - before });
+            self.nodes[update[i]].levels[i] = Link { forward: Some(x), span: before + 1 };
+        }
+        for (i, &prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[prev].levels[i].span += 1;
+        }
+
+        match self.nodes[x].levels[0].forward {
+            Some(next) => self.nodes[next].backward = Some(x),
+            None => self.tail = Some(x),
+        }
+        self.len += 1;
+    }
+
+    /// Removes the key. Returns it if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<K> {
+        let mut update = [0; MAX_LEVEL];
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward_if(x, i, |next| next < key) {
+                x = next;
+            }
+            update[i] = x;
+        }
+
+        let x = self.forward_if(x, 0, |next| next == key)?;
+        for (i, &prev) in update.iter().enumerate().take(self.level) {
+            let removed = self.nodes[x].levels.get(i).copied();
+            let link = &mut self.nodes[prev].levels[i];
+            match removed {
+                Some(removed) if link.forward == Some(x) => {
+                    link.forward = removed.forward;
+                    link.span = link.span + removed.span - 1;
+                }
+                _ => link.span -= 1,
+            }
+        }
+        match self.nodes[x].levels[0].forward {
+            Some(next) => self.nodes[next].backward = self.nodes[x].backward,
+            None => self.tail = self.nodes[x].backward,
+        }
+        while self.level > 1 && self.nodes[0].levels[self.level - 1].forward.is_none() {
+            self.level -= 1;
+        }
+        self.len -= 1;
+
+        let removed = Node { key: None, backward: None, levels: Vec::new() };
+        let node = std::mem::replace(&mut self.nodes[x], removed);
+        self.free.push(x);
+        node.key
+    }
+
+    /// Returns the number of keys at the start of the list for which `pred` holds. `pred`
+    /// must hold for a key only if it holds for all the keys before it.
+    pub fn count_while(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut rank = 0;
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward_if(x, i, &pred) {
+                rank += self.nodes[x].levels[i].span;
+                x = next;
+            }
+        }
+        rank
+    }
+
+    /// Iterates over the keys in order.
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter { list: self, front: self.nodes[0].levels[0].forward, back: self.tail, len: self.len }
+    }
+
+    /// Iterates over the keys with a rank in `start..end`, in order.
+    pub fn range(&self, start: usize, end: usize) -> Iter<'_, K> {
+        let end = end.min(self.len);
+        if start >= end {
+            return Iter { list: self, front: None, back: None, len: 0 };
+        }
+        Iter { list: self, front: self.at(start), back: self.at(end - 1), len: end - start }
+    }
+
+    /// Returns the first key.
+    pub fn first(&self) -> Option<&K> {
+        self.nodes[0].levels[0].forward.map(|x| self.key(x))
+    }
+
+    /// Returns the last key.
+    pub fn last(&self) -> Option<&K> {
+        self.tail.map(|x| self.key(x))
+    }
+
+    // Returns the node at the rank, counted from 0.
+    fn at(&self, rank: usize) -> Option<usize> {
+        let mut traversed = 0;
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward_if(x, i, |_| true) {
+                if traversed + self.nodes[x].levels[i].span > rank + 1 {
+                    break;
+                }
+                traversed += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            if traversed == rank + 1 {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    // Returns the node following `x` at the level, if `pred` holds for its key.
+    fn forward_if(&self, x: usize, level: usize, pred: impl Fn(&K) -> bool) -> Option<usize> {
+        self.nodes[x].levels[level].forward.filter(|next| pred(self.key(*next)))
+    }
+
+    fn key(&self, x: usize) -> &K {
+        self.nodes[x].key.as_ref().expect("only the head has no key")
+    }
+}
+
+impl<'a, K: Ord> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.front?;
+        self.front = self.list.nodes[x].levels[0].forward;
+        self.len -= 1;
+        Some(self.list.key(x))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K: Ord> DoubleEndedIterator for Iter<'_, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.back?;
+        self.back = self.list.nodes[x].backward;
+        self.len -= 1;
+        Some(self.list.key(x))
+    }
+}
+
+impl<K: Ord> ExactSizeIterator for Iter<'_, K> {}
+
+/// Returns the number of levels of a new node: each level is kept with a probability of
+/// 1/4, like Redis does.
+fn random_level() -> usize {
+    let mut level = 1;
+    while level < MAX_LEVEL && utils::random_u64() & 3 == 0 {
+        level += 1;
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks the skiplist against the keys it should hold, in order.
+    fn check(list: &SkipList<u32>, keys: &[u32]) {
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), keys);
+        let reversed: Vec<u32> = keys.iter().rev().copied().collect();
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), reversed);
+        assert_eq!(list.first(), keys.first());
+        assert_eq!(list.last(), keys.last());
+        for (rank, key) in keys.iter().enumerate() {
+            assert_eq!(list.range(rank, rank + 1).collect::<Vec<_>>(), [key]);
+            assert_eq!(list.count_while(|k| k < key), rank);
+        }
+    }
+
+    #[test]
+    fn keeps_ranks_through_inserts_and_removes() {
+        let mut list = SkipList::default();
+        let mut keys = Vec::new();
+        for _ in 0..2000 {
+            let key = utils::random_index(500) as u32;
+            match keys.binary_search(&key) {
+                Ok(i) => {
+                    assert_eq!(list.remove(&key), Some(key));
+                    keys.remove(i);
+                }
+                Err(i) => {
+                    list.insert(key);
+                    keys.insert(i, key);
+                }
+            }
+        }
+        check(&list, &keys);
+
+        assert_eq!(list.remove(&1000), None);
+        for key in keys.clone() {
+            list.remove(&key);
+        }
+        check(&list, &[]);
+        assert_eq!(list.level, 1);
+    }
+
+    #[test]
+    fn iterates_over_ranges_of_ranks() {
+        let mut list = SkipList::default();
+        for key in (0..100).rev() {
+            list.insert(key);
+        }
+        assert_eq!(list.range(10, 15).copied().collect::<Vec<_>>(), [10, 11, 12, 13, 14]);
+        assert_eq!(list.range(10, 15).rev().copied().collect::<Vec<_>>(), [14, 13, 12, 11, 10]);
+        assert_eq!(list.range(98, 1000).copied().collect::<Vec<_>>(), [98, 99]);
+        assert_eq!(list.range(5, 5).count(), 0);
+        assert_eq!(list.range(100, 200).count(), 0);
+
+        // meeting in the middle
+        let mut range = list.range(0, 3);
+        assert_eq!((range.next(), range.next_back()), (Some(&0), Some(&2)));
+        assert_eq!((range.next(), range.next_back()), (Some(&1), None));
+    }
+}
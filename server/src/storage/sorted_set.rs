@@ -0,0 +1,777 @@
+use bytes::Bytes;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::list::resolve_range;
+use crate::storage::set::{Set, SetOp};
+use crate::storage::skiplist::SkipList;
+use crate::storage::DBError;
+
+/// The `SortedSet` struct holds members ordered by score, then lexicographically.
+///
+/// Members are indexed twice: by name to get their score, and in a skiplist ordered by
+/// score, which finds ranks and the bounds of ranges in O(log n) like Redis does.
+#[derive(Debug, Clone, Default)]
+pub struct SortedSet {
+    scores: HashMap<Bytes, f64>,
+    ordered: SkipList<(Score, Bytes)>,
+}
+
+/// A score, totally ordered. Scores are never NaN, and negative zero is stored as zero
+/// so that both compare equal.
+#[derive(Debug, Clone, Copy)]
+struct Score(f64);
+
+/// A bound of a score range, such as `1.5` or `(1.5` when exclusive.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreBound {
+    pub value: f64,
+    pub exclusive: bool,
+}
+
+/// A bound of a lexicographical range: `-`, `+`, `[member` or `(member`.
+#[derive(Debug, Clone)]
+pub enum LexBound {
+    /// Lower than any member.
+    Min,
+    /// Greater than any member.
+    Max,
+    Inclusive(Bytes),
+    Exclusive(Bytes),
+}
+
+/// The selection of a range of members, as done by ZRANGE.
+#[derive(Debug, Clone)]
+pub enum ZRangeBy {
+    /// Members between two ranks, which may count from the end.
+    Rank(i64, i64),
+    /// Members with a score between `min` and `max`.
+    Score(ScoreBound, ScoreBound),
+    /// Members between `min` and `max` lexicographically, when all the scores are equal.
+    Lex(LexBound, LexBound),
+}
+
+/// A range query over a sorted set.
+#[derive(Debug, Clone)]
+pub struct ZRange {
+    pub by: ZRangeBy,
+    /// Iterate from the highest score. Ranks then count from the highest score too.
+    pub rev: bool,
+    /// The offset of the first member returned and the maximum number of members,
+    /// negative for all. Only used with score and lex ranges.
+    pub limit: Option<(i64, i64)>,
+}
+
+/// One of the two ends of a sorted set.
+#[derive(Debug, Clone, Copy)]
+pub enum ZSetEnd {
+    /// The members with the lowest scores.
+    Min,
+    /// The members with the highest scores.
+    Max,
+}
+
+/// How the scores of a member found in several sorted sets are combined by ZUNION
+/// and ZINTER.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+/// An input of ZUNION, ZINTER and ZDIFF: a sorted set, or a set whose members all
+/// have a score of 1.
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Set(&'a Set),
+    SortedSet(&'a SortedSet),
+}
+
+/// The flags of ZADD.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZAddFlags {
+    /// Only add new members.
+    pub nx: bool,
+    /// Only update existing members.
+    pub xx: bool,
+    /// Only update existing members if the new score is greater.
+    pub gt: bool,
+    /// Only update existing members if the new score is less.
+    pub lt: bool,
+}
+
+impl Score {
+    fn new(score: f64) -> Score {
+        // turns -0 into 0
+        Score(score + 0.0)
+    }
+}
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl LexBound {
+    fn above_min(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => true,
+            LexBound::Max => false,
+            LexBound::Inclusive(min) => member >= min.as_ref(),
+            LexBound::Exclusive(min) => member > min.as_ref(),
+        }
+    }
+
+    fn below_max(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(max) => member <= max.as_ref(),
+            LexBound::Exclusive(max) => member < max.as_ref(),
+        }
+    }
+}
+
+impl Aggregate {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            // inf + -inf is NaN, which isn't a valid score
+            Aggregate::Sum => non_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+impl<'a> Source<'a> {
+    fn len(&self) -> usize {
+        match self {
+            Source::Set(set) => set.len(),
+            Source::SortedSet(zset) => zset.len(),
+        }
+    }
+
+    fn score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            Source::Set(set) => set.contains(member).then_some(1.0),
+            Source::SortedSet(zset) => zset.score(member),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&'a Bytes, f64)> + 'a> {
+        match *self {
+            Source::Set(set) => Box::new(set.iter().map(|member| (member, 1.0))),
+            Source::SortedSet(zset) => Box::new(zset.iter()),
+        }
+    }
+}
+
+impl ZAddFlags {
+    /// Checks if a member with the `current` score, if any, can be set to `score`.
+    fn allows(&self, current: Option<f64>, score: f64) -> bool {
+        match current {
+            None => !self.xx,
+            Some(current) => {
+                !(self.nx || self.gt && score <= current || self.lt && score >= current)
+            }
+        }
+    }
+}
+
+impl SortedSet {
+    /// Returns the number of members.
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Checks if the sorted set has no members.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Returns the score of the member.
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Adds the member or updates its score. Returns the previous score.
+    pub fn insert(&mut self, member: Bytes, score: f64) -> Option<f64> {
+        let score = Score::new(score);
+        let previous = self.scores.insert(member.clone(), score.0);
+        if let Some(previous) = previous {
+            self.ordered.remove(&(Score(previous), member.clone()));
+        }
+        self.ordered.insert((score, member));
+        previous
+    }
+
+    /// Removes the member, returning its score.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let (member, score) = self.scores.remove_entry(member)?;
+        self.ordered.remove(&(Score(score), member));
+        Some(score)
+    }
+
+    /// Removes up to `count` members from one end, returning them with their scores,
+    /// starting from that end.
+    pub fn pop(&mut self, end: ZSetEnd, count: usize) -> Vec<(Bytes, f64)> {
+        let mut popped = Vec::new();
+        while popped.len() < count {
+            let first = match end {
+                ZSetEnd::Min => self.ordered.first(),
+                ZSetEnd::Max => self.ordered.last(),
+            };
+            let (score, member) = match first {
+                Some(first) => first.clone(),
+                None => break,
+            };
+            self.ordered.remove(&(score, member.clone()));
+            self.scores.remove(&member);
+            popped.push((member, score.0));
+        }
+        popped
+    }
+
+    /// Returns the rank of the member and its score. The rank counts from the highest
+    /// score when `rev` is true.
+    pub fn rank(&self, member: &[u8], rev: bool) -> Option<(usize, f64)> {
+        let (member, score) = self.scores.get_key_value(member)?;
+        let rank = self.ordered.count_while(|(s, m)| (*s, m) < (Score(*score), member));
+        Some((if rev { self.len() - 1 - rank } else { rank }, *score))
+    }
+
+    /// Iterates over the members and their scores, ordered by score.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&Bytes, f64)> {
+        self.ordered.iter().map(|(score, member)| (member, score.0))
+    }
+
+    /// Returns the number of members with a score in the range.
+    pub fn count_by_score(&self, min: ScoreBound, max: ScoreBound) -> usize {
+        let (start, end) = self.score_ranks(min, max);
+        end.saturating_sub(start)
+    }
+
+    /// Iterates over the members of the range query, in the order of the query.
+    pub fn range<'a>(&'a self, range: &'a ZRange) -> Box<dyn Iterator<Item = (&'a Bytes, f64)> + 'a> {
+        // the ranks of the members in the range, counting from the lowest score
+        let (mut start, mut end) = match &range.by {
+            ZRangeBy::Rank(start, stop) => match resolve_range(self.len(), *start, *stop) {
+                Some((start, stop)) if range.rev => (self.len() - 1 - stop, self.len() - start),
+                Some((start, stop)) => (start, stop + 1),
+                None => (0, 0),
+            },
+            ZRangeBy::Score(min, max) => self.score_ranks(*min, *max),
+            ZRangeBy::Lex(min, max) => (
+                self.ordered.count_while(|(_, member)| !min.above_min(member)),
+                self.ordered.count_while(|(_, member)| max.below_max(member)),
+            ),
+        };
+        end = end.max(start);
+
+        match range.limit {
+            Some((offset, _)) if offset < 0 => end = start,
+            Some((offset, count)) => {
+                let offset = (offset as usize).min(end - start);
+                let count = if count < 0 { usize::MAX } else { count as usize };
+                if range.rev {
+                    end -= offset;
+                    start = start.max(end.saturating_sub(count));
+                } else {
+                    start += offset;
+                    end = end.min(start.saturating_add(count));
+                }
+            }
+            None => {}
+        }
+
+        let members = self.ordered.range(start, end).map(|(score, member)| (member, score.0));
+        if range.rev { Box::new(members.rev()) } else { Box::new(members) }
+    }
+
+    // Returns the ranks of the first member with a score in the range, and of the first
+    // member above it.
+    fn score_ranks(&self, min: ScoreBound, max: ScoreBound) -> (usize, usize) {
+        let start = self.ordered.count_while(|(score, _)| {
+            if min.exclusive { score.0 <= min.value } else { score.0 < min.value }
+        });
+        let end = self.ordered.count_while(|(score, _)| {
+            if max.exclusive { score.0 < max.value } else { score.0 <= max.value }
+        });
+        (start, end)
+    }
+}
+
+impl DB {
+    /// Add the members with their scores to the sorted set stored at the key, or update
+    /// the scores of the existing ones, as allowed by the flags. Clients blocked on the key
+    /// are served right away.
+    /// Returns the number of members added and the number of members whose score changed.
+    pub fn zadd(&self, key: Bytes, flags: ZAddFlags, pairs: Vec<(f64, Bytes)>) -> Result<(usize, usize), DBError> {
+        let mut data = self.write()?;
+
+        let zset = zset_or_insert(&mut data, key.clone())?;
+        let (mut added, mut changed) = (0, 0);
+        for (score, member) in pairs {
+            let current = zset.score(&member);
+            if !flags.allows(current, score) {
+                continue;
+            }
+            match current {
+                None => added += 1,
+                Some(current) if current != score => changed += 1,
+                Some(_) => continue,
+            }
+            zset.insert(member, score);
+        }
+
+        // XX may leave the new sorted set empty
+        if zset.is_empty() {
+            data.remove(&key);
+        } else {
+            data.serve_blocked(key);
+        }
+        Ok((added, changed))
+    }
+
+    /// Increment the score of the member by `delta`, adding it if missing, as allowed by
+    /// the flags. Clients blocked on the key are served right away.
+    /// Returns the new score, or `None` if the flags prevented the update.
+    pub fn zincrby(&self, key: Bytes, flags: ZAddFlags, delta: f64, member: Bytes) -> Result<Option<f64>, DBError> {
+        let mut data = self.write()?;
+
+        let current = zset_mut(&mut data, &key)?.and_then(|zset| zset.score(&member));
+        let score = current.unwrap_or(0.0) + delta;
+        if score.is_nan() {
+            return Err(DBError::Other(String::from("resulting score is not a number (NaN)")));
+        }
+        if !flags.allows(current, score) {
+            return Ok(None);
+        }
+
+        zset_or_insert(&mut data, key.clone())?.insert(member, score);
+        data.serve_blocked(key);
+        Ok(Some(score))
+    }
+
+    /// Get the score of each of the members. Missing members are returned as `None`.
+    pub fn zmscore(&self, key: &[u8], members: &[Bytes]) -> Result<Vec<Option<f64>>, DBError> {
+        self.with_zset(key, |zset| {
+            members
+                .iter()
+                .map(|member| zset.and_then(|zset| zset.score(member)))
+                .collect()
+        })
+    }
+
+    /// Get the rank of the member and its score. The rank counts from the highest score
+    /// when `rev` is true.
+    pub fn zrank(&self, key: &[u8], member: &[u8], rev: bool) -> Result<Option<(usize, f64)>, DBError> {
+        self.with_zset(key, |zset| zset.and_then(|zset| zset.rank(member, rev)))
+    }
+
+    /// Remove the members from the sorted set, and the key once it's empty.
+    /// Returns the number of removed members.
+    pub fn zrem(&self, key: &[u8], members: &[Bytes]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let zset = match zset_mut(&mut data, key)? {
+            Some(zset) => zset,
+            None => return Ok(0),
+        };
+        let removed = members.iter().filter(|member| zset.remove(member).is_some()).count();
+        if zset.is_empty() {
+            data.remove(key);
+        }
+        Ok(removed)
+    }
+
+    /// Get the number of members of the sorted set, zero if missing.
+    pub fn zcard(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_zset(key, |zset| zset.map_or(0, SortedSet::len))
+    }
+
+    /// Get the number of members with a score in the range.
+    pub fn zcount(&self, key: &[u8], min: ScoreBound, max: ScoreBound) -> Result<usize, DBError> {
+        self.with_zset(key, |zset| zset.map_or(0, |zset| zset.count_by_score(min, max)))
+    }
+
+    /// Get the members of the range query and their scores.
+    pub fn zrange(&self, key: &[u8], range: &ZRange) -> Result<Vec<(Bytes, f64)>, DBError> {
+        self.with_zset(key, |zset| {
+            zset.into_iter()
+                .flat_map(|zset| zset.range(range))
+                .map(|(member, score)| (member.clone(), score))
+                .collect()
+        })
+    }
+
+    /// Remove the members of the range query from the sorted set, and the key once it's
+    /// empty. Returns the number of removed members.
+    pub fn zremrange(&self, key: &[u8], range: &ZRange) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let zset = match zset_mut(&mut data, key)? {
+            Some(zset) => zset,
+            None => return Ok(0),
+        };
+        let members: Vec<Bytes> = zset.range(range).map(|(member, _)| member.clone()).collect();
+        for member in &members {
+            zset.remove(member);
+        }
+        if zset.is_empty() {
+            data.remove(key);
+        }
+        Ok(members.len())
+    }
+
+    /// Store the members of the range query over the `src` sorted set into `dest`,
+    /// replacing it. An empty result deletes `dest`. Returns the number of members stored.
+    pub fn zrangestore(&self, dest: Bytes, src: &[u8], range: &ZRange) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let result = match data.get(src) {
+            None => SortedSet::default(),
+            Some(Entry { value: Value::SortedSet(zset), .. }) => {
+                let mut result = SortedSet::default();
+                for (member, score) in zset.range(range) {
+                    result.insert(member.clone(), score);
+                }
+                result
+            }
+            Some(_) => return Err(DBError::WrongType),
+        };
+        Ok(store(&mut data, dest, result))
+    }
+
+    /// Remove and return up to `count` members from one end of the sorted set, deleting
+    /// the key once it's empty.
+    pub fn zpop(&self, key: &[u8], end: ZSetEnd, count: usize) -> Result<Vec<(Bytes, f64)>, DBError> {
+        Ok(self.write()?.pop_zset(key, end, count)?.unwrap_or_default())
+    }
+
+    /// Get the result of the operation combining the sorted sets stored at the keys,
+    /// ordered by score. Sets are combined as sorted sets whose scores are all 1, and
+    /// missing keys as empty sorted sets.
+    ///
+    /// The scores of each key are multiplied by its weight. A member found in several
+    /// keys gets their scores combined by `aggregate`, except for differences, which keep
+    /// the scores of the first key.
+    pub fn zset_op(
+        &self,
+        op: SetOp,
+        keys: &[Bytes],
+        weights: &[f64],
+        aggregate: Aggregate,
+    ) -> Result<Vec<(Bytes, f64)>, DBError> {
+        let data = self.read()?;
+
+        let result = combine(&data, op, keys, weights, aggregate)?;
+        Ok(result.iter().map(|(member, score)| (member.clone(), score)).collect())
+    }
+
+    /// Store the result of the operation combining the sorted sets stored at the keys into
+    /// `dest`, replacing it. An empty result deletes `dest`. Returns the size of the result.
+    pub fn zset_op_store(
+        &self,
+        op: SetOp,
+        dest: Bytes,
+        keys: &[Bytes],
+        weights: &[f64],
+        aggregate: Aggregate,
+    ) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let result = combine(&data, op, keys, weights, aggregate)?;
+        Ok(store(&mut data, dest, result))
+    }
+
+    /// Get the size of the intersection of the sorted sets stored at the keys, without
+    /// computing it. The count stops at `limit`, unless it's zero.
+    pub fn zintercard(&self, keys: &[Bytes], limit: usize) -> Result<usize, DBError> {
+        let data = self.read()?;
+
+        let mut sources = match lookup_sources(&data, keys)?.into_iter().collect::<Option<Vec<_>>>() {
+            Some(sources) => sources,
+            None => return Ok(0),
+        };
+        sources.sort_by_key(Source::len);
+        let (smallest, others) = sources.split_first().unwrap();
+        let count = smallest
+            .iter()
+            .filter(|(member, _)| others.iter().all(|other| other.score(member).is_some()))
+            .take(if limit == 0 { usize::MAX } else { limit })
+            .count();
+        Ok(count)
+    }
+
+    // Runs `f` with the sorted set stored at the key under the read lock,
+    // or `None` if the key doesn't exist.
+    fn with_zset<T>(&self, key: &[u8], f: impl FnOnce(Option<&SortedSet>) -> T) -> Result<T, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(f(None)),
+            Some(Entry { value: Value::SortedSet(zset), .. }) => Ok(f(Some(zset))),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+}
+
+impl Keyspace {
+    /// Pop up to `count` members from one end of the sorted set stored at the key,
+    /// deleting it once empty. Returns `None` if the key doesn't exist.
+    pub(crate) fn pop_zset(&mut self, key: &[u8], end: ZSetEnd, count: usize) -> Result<Option<Vec<(Bytes, f64)>>, DBError> {
+        let zset = match zset_mut(self, key)? {
+            Some(zset) => zset,
+            None => return Ok(None),
+        };
+        let popped = zset.pop(end, count);
+        if zset.is_empty() {
+            self.remove(key);
+        }
+        Ok(Some(popped))
+    }
+}
+
+/// Returns the sorted set stored at the key, or `None` if the key doesn't exist.
+fn zset_mut<'a>(data: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut SortedSet>, DBError> {
+    match data.get_mut(key) {
+        None => Ok(None),
+        Some(Entry { value: Value::SortedSet(zset), .. }) => Ok(Some(zset)),
+        Some(_) => Err(DBError::WrongType),
+    }
+}
+
+/// Returns the sorted set stored at the key, creating an empty one if missing.
+fn zset_or_insert(data: &mut Keyspace, key: Bytes) -> Result<&mut SortedSet, DBError> {
+    match &mut data.get_or_insert_with(key, || Value::SortedSet(SortedSet::default())).value {
+        Value::SortedSet(zset) => Ok(zset),
+        _ => Err(DBError::WrongType),
+    }
+}
+
+/// Stores the sorted set into `dest`, replacing it, or deletes `dest` if it's empty.
+/// Clients blocked on `dest` are served right away. Returns the size of the sorted set.
+fn store(data: &mut Keyspace, dest: Bytes, zset: SortedSet) -> usize {
+    let len = zset.len();
+    if zset.is_empty() {
+        data.remove(&dest);
+    } else {
+        data.insert(dest.clone(), Entry::new(Value::SortedSet(zset)));
+        data.serve_blocked(dest);
+    }
+    len
+}
+
+/// Returns the sorted sets or sets stored at the keys, or `None` for each missing key.
+/// Fails if any of the keys holds another type.
+fn lookup_sources<'a>(data: &'a Keyspace, keys: &[Bytes]) -> Result<Vec<Option<Source<'a>>>, DBError> {
+    keys.iter()
+        .map(|key| match data.get(key) {
+            None => Ok(None),
+            Some(Entry { value: Value::SortedSet(zset), .. }) => Ok(Some(Source::SortedSet(zset))),
+            Some(Entry { value: Value::Set(set), .. }) => Ok(Some(Source::Set(set))),
+            Some(_) => Err(DBError::WrongType),
+        })
+        .collect()
+}
+
+/// Combines the sorted sets stored at the keys, weighting their scores.
+fn combine(
+    data: &Keyspace,
+    op: SetOp,
+    keys: &[Bytes],
+    weights: &[f64],
+    aggregate: Aggregate,
+) -> Result<SortedSet, DBError> {
+    let sources = lookup_sources(data, keys)?;
+    // 0 * inf is NaN, which isn't a valid score
+    let weighted = |score: f64, weight: f64| non_nan(score * weight);
+
+    let mut result = SortedSet::default();
+    match op {
+        SetOp::Inter => {
+            let sources = sources.into_iter().zip(weights).map(|(source, weight)| source.map(|source| (source, *weight)));
+            if let Some(mut sources) = sources.collect::<Option<Vec<_>>>() {
+                // only the members of the smallest sorted set need to be checked
+                sources.sort_by_key(|(source, _)| source.len());
+                let ((smallest, weight), others) = sources.split_first().unwrap();
+                'members: for (member, score) in smallest.iter() {
+                    let mut score = weighted(score, *weight);
+                    for (other, weight) in others {
+                        match other.score(member) {
+                            Some(other) => score = aggregate.apply(score, weighted(other, *weight)),
+                            None => continue 'members,
+                        }
+                    }
+                    result.insert(member.clone(), score);
+                }
+            }
+        }
+        SetOp::Union => {
+            let mut scores: HashMap<&Bytes, f64> = HashMap::new();
+            for (source, weight) in sources.iter().zip(weights) {
+                for (member, score) in source.iter().flat_map(Source::iter) {
+                    let score = weighted(score, *weight);
+                    scores
+                        .entry(member)
+                        .and_modify(|current| *current = aggregate.apply(*current, score))
+                        .or_insert(score);
+                }
+            }
+            for (member, score) in scores {
+                result.insert(member.clone(), score);
+            }
+        }
+        SetOp::Diff => {
+            if let Some((Some(first), others)) = sources.split_first() {
+                for (member, score) in first.iter() {
+                    if !others.iter().flatten().any(|other| other.score(member).is_some()) {
+                        result.insert(member.clone(), score);
+                    }
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Turns NaN into 0.
+fn non_nan(score: f64) -> f64 {
+    if score.is_nan() { 0.0 } else { score }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    fn zset(pairs: &[(f64, &'static str)]) -> SortedSet {
+        let mut zset = SortedSet::default();
+        for (score, member) in pairs {
+            zset.insert(Bytes::from(*member), *score);
+        }
+        zset
+    }
+
+    fn members<'a>(members: impl Iterator<Item = (&'a Bytes, f64)>) -> Vec<&'a [u8]> {
+        members.map(|(member, _)| member.as_ref()).collect()
+    }
+
+    // The members of the range query found by walking the whole sorted set.
+    fn walk<'a>(zset: &'a SortedSet, range: &ZRange) -> Vec<&'a [u8]> {
+        let mut all: Vec<(&Bytes, f64)> = zset.iter().collect();
+        if range.rev {
+            all.reverse();
+        }
+        let selected: Vec<(&Bytes, f64)> = match &range.by {
+            ZRangeBy::Rank(start, stop) => match resolve_range(all.len(), *start, *stop) {
+                Some((start, stop)) => all[start..=stop].to_vec(),
+                None => Vec::new(),
+            },
+            ZRangeBy::Score(min, max) => all
+                .into_iter()
+                .filter(|(_, score)| {
+                    (if min.exclusive { *score > min.value } else { *score >= min.value })
+                        && (if max.exclusive { *score < max.value } else { *score <= max.value })
+                })
+                .collect(),
+            ZRangeBy::Lex(min, max) => all
+                .into_iter()
+                .filter(|(member, _)| min.above_min(member) && max.below_max(member))
+                .collect(),
+        };
+        let selected = match range.limit {
+            Some((offset, _)) if offset < 0 => Vec::new(),
+            Some((offset, count)) => selected
+                .into_iter()
+                .skip(offset as usize)
+                .take(if count < 0 { usize::MAX } else { count as usize })
+                .collect(),
+            None => selected,
+        };
+        members(selected.into_iter())
+    }
+
+    #[test]
+    fn ranks_members_by_score_then_member() {
+        let zset = zset(&[(2.0, "c"), (1.0, "b"), (2.0, "a"), (-0.0, "z"), (f64::INFINITY, "inf")]);
+        assert_eq!(members(zset.iter()), [&b"z"[..], b"b", b"a", b"c", b"inf"]);
+        for (rank, member) in members(zset.iter()).into_iter().enumerate() {
+            assert_eq!(zset.rank(member, false).unwrap().0, rank);
+            assert_eq!(zset.rank(member, true).unwrap().0, 4 - rank);
+        }
+        assert_eq!(zset.rank(b"a", false), Some((2, 2.0)));
+        assert_eq!(zset.rank(b"missing", false), None);
+    }
+
+    #[test]
+    fn ranges_match_a_walk_of_the_sorted_set() {
+        let mut scored = SortedSet::default();
+        let mut lex = SortedSet::default();
+        for _ in 0..300 {
+            let member = Bytes::from(format!("m{:03}", utils::random_index(1000)));
+            scored.insert(member.clone(), utils::random_index(50) as f64 - 25.0);
+            lex.insert(member, 0.0);
+        }
+        let values = [-30.0, -10.5, -3.0, 0.0, 7.0, 24.0, f64::INFINITY];
+        let score = |i: usize| ScoreBound { value: values[i % values.len()], exclusive: i.is_multiple_of(2) };
+        let word = |i: usize| match i % 4 {
+            0 => LexBound::Min,
+            1 => LexBound::Max,
+            2 => LexBound::Inclusive(Bytes::from(format!("m{:03}", i * 37 % 1000))),
+            _ => LexBound::Exclusive(Bytes::from(format!("m{:03}", i * 53 % 1000))),
+        };
+        let limits = [None, Some((0, 10)), Some((5, -1)), Some((-1, 3)), Some((250, 100)), Some((3, 0))];
+
+        for i in 0..200 {
+            let start = utils::random_index(700) as i64 - 350;
+            let stop = utils::random_index(700) as i64 - 350;
+            let queries = [
+                (&scored, ZRangeBy::Rank(start, stop), None),
+                (&scored, ZRangeBy::Score(score(i), score(i / 7)), limits[i % limits.len()]),
+                (&lex, ZRangeBy::Lex(word(i), word(i / 4 + 1)), limits[i / 3 % limits.len()]),
+            ];
+            for (zset, by, limit) in queries {
+                for rev in [false, true] {
+                    let range = ZRange { by: by.clone(), rev, limit };
+                    assert_eq!(members(zset.range(&range)), walk(zset, &range), "{:?}", range);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn counts_members_by_score() {
+        let zset = zset(&[(1.0, "a"), (2.0, "b"), (2.0, "c"), (3.0, "d")]);
+        let bound = |value: f64, exclusive: bool| ScoreBound { value, exclusive };
+        assert_eq!(zset.count_by_score(bound(2.0, false), bound(2.0, false)), 2);
+        assert_eq!(zset.count_by_score(bound(1.0, true), bound(3.0, true)), 2);
+        assert_eq!(zset.count_by_score(bound(f64::NEG_INFINITY, false), bound(f64::INFINITY, false)), 4);
+        assert_eq!(zset.count_by_score(bound(3.0, false), bound(1.0, false)), 0);
+    }
+
+    #[test]
+    fn pops_from_both_ends() {
+        let mut zset = zset(&[(1.0, "a"), (2.0, "b"), (3.0, "c")]);
+        assert_eq!(zset.pop(ZSetEnd::Max, 1), [(Bytes::from("c"), 3.0)]);
+        assert_eq!(zset.pop(ZSetEnd::Min, 5), [(Bytes::from("a"), 1.0), (Bytes::from("b"), 2.0)]);
+        assert!(zset.is_empty());
+        assert_eq!(zset.iter().count(), 0);
+    }
+}
@@ -0,0 +1,1421 @@
+use bytes::Bytes;
+use std::cmp::Ordering;
+use std::collections::btree_map::Entry as MapEntry;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Bound;
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::DBError;
+use crate::utils::now_ms;
+
+/// The ID of a stream entry: the unix time in milliseconds at which it was added, and a
+/// sequence number telling apart the entries added during the same millisecond.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+/// An entry of a stream: its ID and its fields with their values, in the order given.
+/// Entries always have fields, except the ones read from the history of a consumer which
+/// were deleted from the stream since their delivery.
+#[derive(Debug, Clone)]
+pub struct StreamEntry {
+    pub id: StreamId,
+    pub fields: Vec<(Bytes, Bytes)>,
+}
+
+/// The `Stream` struct holds entries ordered by ID.
+///
+/// IDs only grow: a new entry must have an ID greater than the last one ever added,
+/// even if that entry was deleted since. Unlike the other types, an empty stream is
+/// kept, so that it remembers its last ID and its consumer groups.
+#[derive(Debug, Clone, Default)]
+pub struct Stream {
+    entries: BTreeMap<StreamId, Vec<(Bytes, Bytes)>>,
+    /// The ID of the last entry added.
+    last_id: StreamId,
+    /// The number of entries ever added.
+    entries_added: u64,
+    /// The greatest ID of the entries deleted by XDEL, 0-0 if none.
+    max_deleted_id: StreamId,
+    groups: BTreeMap<Bytes, ConsumerGroup>,
+}
+
+/// A consumer group: its consumers share the entries of the stream, each entry being
+/// delivered to one of them, and pending until the consumer acknowledges it.
+#[derive(Debug, Clone)]
+pub struct ConsumerGroup {
+    /// The ID of the last entry delivered to the group.
+    last_id: StreamId,
+    /// The number of entries read by the group, used to tell its lag. `None` when it
+    /// can't be known, e.g. once the last ID was set to an arbitrary one.
+    entries_read: Option<u64>,
+    /// The pending entries list: the entries delivered and not acknowledged yet.
+    pending: BTreeMap<StreamId, PendingEntry>,
+    consumers: BTreeMap<Bytes, Consumer>,
+}
+
+/// An entry delivered to a consumer and not acknowledged yet.
+#[derive(Debug, Clone)]
+pub struct PendingEntry {
+    pub consumer: Bytes,
+    /// The unix time in milliseconds of the last delivery.
+    pub delivery_time: u64,
+    /// The number of times the entry was delivered.
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Consumer {
+    /// The unix time in milliseconds of the last attempted interaction.
+    seen_time: u64,
+    /// The unix time in milliseconds of the last successful interaction, `None` if none.
+    active_time: Option<u64>,
+    /// The IDs of the entries pending for the consumer.
+    pending: BTreeSet<StreamId>,
+}
+
+/// The ID of a new entry, as given to XADD.
+#[derive(Debug, Clone, Copy)]
+pub enum XAddId {
+    /// `*`: generated from the current time.
+    Auto,
+    /// `ms-*`: the sequence number is generated.
+    AutoSeq(u64),
+    /// `ms-seq`.
+    Explicit(StreamId),
+}
+
+/// Which entries trimming evicts from a stream.
+#[derive(Debug, Clone, Copy)]
+pub enum TrimStrategy {
+    /// Evict the oldest entries, keeping at most this many.
+    MaxLen(u64),
+    /// Evict the entries with an ID lower than this one.
+    MinId(StreamId),
+}
+
+/// The trimming done by XTRIM and XADD.
+///
+/// Redis trims whole nodes of its radix tree with `~`, evicting at most `limit` entries.
+/// Entries aren't grouped into nodes here, so `~` trims exactly, within that limit.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamTrim {
+    pub strategy: TrimStrategy,
+    /// The maximum number of entries evicted, `None` for no limit.
+    pub limit: Option<usize>,
+}
+
+/// The options of XCLAIM. XAUTOCLAIM only takes the minimum idle time and JUSTID.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClaimOptions {
+    /// Only claim the entries idle for at least this many milliseconds.
+    pub min_idle: u64,
+    /// The unix time in milliseconds set as the last delivery, now if `None`.
+    pub delivery_time: Option<u64>,
+    /// The delivery count set. If `None`, the count is incremented unless `justid` is set.
+    pub retry_count: Option<u64>,
+    /// Add the entries missing from the pending entries list, if they exist.
+    pub force: bool,
+    pub justid: bool,
+    /// The last ID of the group is set to this one if greater.
+    pub last_id: Option<StreamId>,
+}
+
+/// Which pending entries of a group XPENDING lists.
+#[derive(Debug, Clone)]
+pub struct PendingFilter {
+    /// The lowest ID, included.
+    pub start: StreamId,
+    /// The greatest ID, included.
+    pub end: StreamId,
+    pub count: usize,
+    /// Only list the entries pending for this consumer.
+    pub consumer: Option<Bytes>,
+    /// Only list the entries idle for at least this many milliseconds.
+    pub min_idle: u64,
+}
+
+/// The summary of the pending entries of a group, as replied by XPENDING.
+#[derive(Debug)]
+pub struct PendingSummary {
+    pub count: usize,
+    /// The lowest and the greatest IDs of the pending entries, `None` if there are none.
+    pub range: Option<(StreamId, StreamId)>,
+    /// The consumers having pending entries with their number, by name.
+    pub consumers: Vec<(Bytes, usize)>,
+}
+
+/// What XINFO STREAM tells about a stream.
+#[derive(Debug)]
+pub struct StreamInfo {
+    pub length: usize,
+    pub last_id: StreamId,
+    pub max_deleted_id: StreamId,
+    pub entries_added: u64,
+    pub first_id: StreamId,
+    pub first_entry: Option<StreamEntry>,
+    pub last_entry: Option<StreamEntry>,
+    /// The first entries, only listed by XINFO STREAM FULL.
+    pub entries: Vec<StreamEntry>,
+    pub groups: Vec<GroupInfo>,
+}
+
+/// What XINFO GROUPS and XINFO STREAM FULL tell about a consumer group.
+#[derive(Debug)]
+pub struct GroupInfo {
+    pub name: Bytes,
+    pub last_id: StreamId,
+    pub entries_read: Option<u64>,
+    /// The number of entries the group has yet to read, `None` if it can't be known.
+    pub lag: Option<u64>,
+    pub pending_count: usize,
+    /// The first pending entries, only listed by XINFO STREAM FULL.
+    pub pending: Vec<(StreamId, PendingEntry)>,
+    pub consumers: Vec<ConsumerInfo>,
+}
+
+/// What XINFO CONSUMERS and XINFO STREAM FULL tell about a consumer.
+#[derive(Debug)]
+pub struct ConsumerInfo {
+    pub name: Bytes,
+    pub seen_time: u64,
+    pub active_time: Option<u64>,
+    pub pending_count: usize,
+    /// The first pending entries, only listed by XINFO STREAM FULL.
+    pub pending: Vec<(StreamId, PendingEntry)>,
+}
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId { ms: u64::MAX, seq: u64::MAX };
+
+    /// Returns the smallest ID greater than this one, or `None` for the maximum ID.
+    pub fn next(self) -> Option<StreamId> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(StreamId { ms: self.ms, seq }),
+            None => Some(StreamId { ms: self.ms.checked_add(1)?, seq: 0 }),
+        }
+    }
+
+    /// Returns the greatest ID lower than this one, or `None` for the minimum ID.
+    pub fn prev(self) -> Option<StreamId> {
+        match self.seq.checked_sub(1) {
+            Some(seq) => Some(StreamId { ms: self.ms, seq }),
+            None => Some(StreamId { ms: self.ms.checked_sub(1)?, seq: u64::MAX }),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl Stream {
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the ID of the last entry added, which may have been deleted since.
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// Returns the ID the next entry gets, failing if it isn't greater than the last ID.
+    fn next_id(&self, id: XAddId) -> Result<StreamId, DBError> {
+        let smaller = || DBError::Other(String::from(
+            "The ID specified in XADD is equal or smaller than the target stream top item",
+        ));
+        let id = match id {
+            XAddId::Auto => {
+                let now = now_ms();
+                if now > self.last_id.ms {
+                    StreamId { ms: now, seq: 0 }
+                } else {
+                    // the clock went backwards, or many entries were added in the same ms
+                    self.last_id.next().ok_or_else(|| DBError::Other(String::from(
+                        "The stream has exhausted the last possible ID, unable to add more items",
+                    )))?
+                }
+            }
+            XAddId::AutoSeq(ms) if ms > self.last_id.ms => StreamId { ms, seq: 0 },
+            XAddId::AutoSeq(ms) if ms == self.last_id.ms => match self.last_id.seq.checked_add(1) {
+                Some(seq) => StreamId { ms, seq },
+                None => return Err(smaller()),
+            },
+            XAddId::AutoSeq(_) => return Err(smaller()),
+            XAddId::Explicit(StreamId::MIN) => {
+                return Err(DBError::Other(String::from("The ID specified in XADD must be greater than 0-0")));
+            }
+            XAddId::Explicit(id) if id <= self.last_id => return Err(smaller()),
+            XAddId::Explicit(id) => id,
+        };
+        Ok(id)
+    }
+
+    /// Adds an entry, which must have an ID greater than the last one.
+    fn push(&mut self, id: StreamId, fields: Vec<(Bytes, Bytes)>) {
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        self.entries_added += 1;
+    }
+
+    /// Returns the ID of the first entry, or 0-0 if the stream is empty.
+    fn first_id(&self) -> StreamId {
+        self.entries.first_key_value().map_or(StreamId::MIN, |(id, _)| *id)
+    }
+
+    /// Iterates over the entries with an ID between `start` and `end` included, by ID.
+    pub fn range(&self, start: StreamId, end: StreamId) -> impl DoubleEndedIterator<Item = StreamEntry> + '_ {
+        let range = (start <= end).then(|| self.entries.range(start..=end));
+        range
+            .into_iter()
+            .flatten()
+            .map(|(id, fields)| StreamEntry { id: *id, fields: fields.clone() })
+    }
+
+    /// Returns up to `count` entries with an ID greater than `after`, all with no count.
+    pub fn read_after(&self, after: StreamId, count: Option<usize>) -> Vec<StreamEntry> {
+        match after.next() {
+            Some(start) => self.range(start, StreamId::MAX).take(count.unwrap_or(usize::MAX)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Checks if the stream has entries with an ID greater than `after`.
+    pub fn has_entries_after(&self, after: StreamId) -> bool {
+        self.entries.last_key_value().is_some_and(|(id, _)| *id > after)
+    }
+
+    /// Removes the entry. Returns false if there's no entry with this ID.
+    pub fn remove(&mut self, id: StreamId) -> bool {
+        if self.entries.remove(&id).is_none() {
+            return false;
+        }
+        self.max_deleted_id = self.max_deleted_id.max(id);
+        true
+    }
+
+    /// Evicts the oldest entries as told by `trim`. Returns the number of evicted entries.
+    pub fn trim(&mut self, trim: &StreamTrim) -> usize {
+        let limit = trim.limit.unwrap_or(usize::MAX);
+        let mut evicted = 0;
+        while evicted < limit {
+            let evict = match (trim.strategy, self.entries.first_key_value()) {
+                (TrimStrategy::MaxLen(max), Some(_)) => self.entries.len() as u64 > max,
+                (TrimStrategy::MinId(min), Some((id, _))) => *id < min,
+                (_, None) => false,
+            };
+            if !evict {
+                break;
+            }
+            self.entries.pop_first();
+            evicted += 1;
+        }
+        evicted
+    }
+
+    /// Checks if entries with an ID greater than or equal to `start` were deleted, in which
+    /// case the number of entries read by a group can't be derived from its last ID.
+    fn has_deleted_from(&self, start: StreamId) -> bool {
+        !self.entries.is_empty()
+            && self.max_deleted_id != StreamId::MIN
+            && self.first_id() <= self.max_deleted_id
+            && start <= self.max_deleted_id
+    }
+
+    /// Returns the number of entries added up to the one with the ID included, or `None`
+    /// if deleted entries make it impossible to tell.
+    fn entries_added_until(&self, id: StreamId) -> Option<u64> {
+        if self.entries_added == 0 || (self.entries.is_empty() && id <= self.last_id) {
+            return Some(self.entries_added);
+        }
+        match id.cmp(&self.last_id) {
+            Ordering::Equal => return Some(self.entries_added),
+            Ordering::Greater => return None,
+            Ordering::Less => {}
+        }
+
+        // unless entries were deleted past the first one, the missing ones were trimmed
+        let first = self.first_id();
+        if self.max_deleted_id == StreamId::MIN || self.max_deleted_id < first {
+            let trimmed = self.entries_added - self.len() as u64;
+            match id.cmp(&first) {
+                Ordering::Less => return Some(trimmed),
+                Ordering::Equal => return Some(trimmed + 1),
+                Ordering::Greater => {}
+            }
+        }
+        None
+    }
+
+    /// Returns the number of entries the group has yet to read, or `None` if deleted entries
+    /// make it impossible to tell.
+    fn lag(&self, group: &ConsumerGroup) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+        let read = match group.entries_read {
+            Some(read) if !self.has_deleted_from(group.last_id) => read,
+            _ => self.entries_added_until(group.last_id)?,
+        };
+        Some(self.entries_added.saturating_sub(read))
+    }
+
+    /// Checks if a client blocked on the group has entries to read: entries with an ID
+    /// greater than the last one delivered to the group. A destroyed group is ready too,
+    /// so that its blocked clients get an error.
+    pub fn has_entries_for(&self, group: &[u8]) -> bool {
+        self.groups.get(group).is_none_or(|group| self.has_entries_after(group.last_id))
+    }
+
+    /// Delivers to the consumer up to `count` entries with an ID greater than the last one
+    /// delivered to the group, which are pending until acknowledged unless `noack` is set.
+    /// The consumer is created if missing. Returns `None` if there's no such group.
+    fn read_group(
+        &mut self,
+        group: &[u8],
+        consumer: &Bytes,
+        count: Option<usize>,
+        noack: bool,
+        now: u64,
+    ) -> Option<Vec<StreamEntry>> {
+        let entries = self.read_after(self.groups.get(group)?.last_id, count);
+
+        // keep counting the entries read as long as none were deleted past the last ID
+        let mut entries_read = self.groups.get(group)?.entries_read;
+        for entry in entries.iter() {
+            entries_read = match entries_read {
+                Some(read) if !self.has_deleted_from(entry.id) => Some(read + 1),
+                _ => self.entries_added_until(entry.id),
+            };
+        }
+
+        let group = self.groups.get_mut(group)?;
+        group.consumer(consumer, now);
+        if let Some(last) = entries.last() {
+            group.last_id = last.id;
+            group.entries_read = entries_read;
+            group.consumer(consumer, now).active_time = Some(now);
+        }
+        if !noack {
+            for entry in entries.iter() {
+                let pending = group.assign(entry.id, consumer, now);
+                pending.delivery_time = now;
+                pending.delivery_count = 1;
+            }
+        }
+        Some(entries)
+    }
+
+    /// Delivers again to the consumer up to `count` of its pending entries with an ID
+    /// greater than `after`. The entries deleted from the stream since are returned with no
+    /// fields. The consumer is created if missing. Returns `None` if there's no such group.
+    fn read_group_history(
+        &mut self,
+        group: &[u8],
+        consumer: &Bytes,
+        after: StreamId,
+        count: Option<usize>,
+        now: u64,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get_mut(group)?;
+        let ids: Vec<_> = group
+            .consumer(consumer, now)
+            .pending
+            .range((Bound::Excluded(after), Bound::Unbounded))
+            .take(count.unwrap_or(usize::MAX))
+            .copied()
+            .collect();
+
+        let entries = ids
+            .into_iter()
+            .map(|id| match self.entries.get(&id) {
+                Some(fields) => {
+                    if let Some(pending) = group.pending.get_mut(&id) {
+                        pending.delivery_time = now;
+                        pending.delivery_count += 1;
+                    }
+                    StreamEntry { id, fields: fields.clone() }
+                }
+                None => StreamEntry { id, fields: Vec::new() },
+            })
+            .collect();
+        Some(entries)
+    }
+
+    /// Assigns to the consumer the pending entries idle for long enough, as told by the
+    /// options. Entries deleted from the stream since are removed from the pending entries
+    /// list. The consumer is created if missing. Returns the claimed entries, or `None` if
+    /// there's no such group.
+    fn claim(
+        &mut self,
+        group: &[u8],
+        consumer: &Bytes,
+        ids: &[StreamId],
+        options: &ClaimOptions,
+        now: u64,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get_mut(group)?;
+        if let Some(last_id) = options.last_id {
+            group.last_id = group.last_id.max(last_id);
+        }
+        group.consumer(consumer, now);
+        let delivery_time = options.delivery_time.filter(|time| *time <= now).unwrap_or(now);
+
+        let mut claimed = Vec::new();
+        for id in ids.iter().copied() {
+            let fields = self.entries.get(&id);
+            match group.pending.get(&id) {
+                Some(pending) if now.saturating_sub(pending.delivery_time) < options.min_idle => continue,
+                Some(_) => {}
+                None if options.force && fields.is_some() => {}
+                None => continue,
+            }
+            let fields = match fields {
+                Some(fields) => fields,
+                None => {
+                    group.remove_pending(id);
+                    continue;
+                }
+            };
+
+            let pending = group.assign(id, consumer, now);
+            pending.delivery_time = delivery_time;
+            match options.retry_count {
+                Some(count) => pending.delivery_count = count,
+                None if !options.justid => pending.delivery_count += 1,
+                None => {}
+            }
+            group.consumer(consumer, now).active_time = Some(now);
+            claimed.push(StreamEntry { id, fields: fields.clone() });
+        }
+        Some(claimed)
+    }
+
+    /// Assigns to the consumer up to `count` pending entries idle for long enough, scanning
+    /// at most ten times as many from `start`. Entries deleted from
+    /// the stream since are removed from the pending entries list, and count as claimed.
+    /// The consumer is created if missing. Returns the ID to continue the scan from, 0-0
+    /// once done, the claimed entries and the IDs of the deleted ones, or `None` if
+    /// there's no such group.
+    fn autoclaim(
+        &mut self,
+        group: &[u8],
+        consumer: &Bytes,
+        start: StreamId,
+        count: usize,
+        options: &ClaimOptions,
+        now: u64,
+    ) -> Option<(StreamId, Vec<StreamEntry>, Vec<StreamId>)> {
+        let group = self.groups.get_mut(group)?;
+        group.consumer(consumer, now);
+
+        let (mut claimed, mut deleted) = (Vec::new(), Vec::new());
+        let (mut count, mut attempts) = (count, count.saturating_mul(10));
+        let mut cursor = Some(start);
+        let next = loop {
+            let id = match cursor.and_then(|cursor| group.pending.range(cursor..).next()) {
+                Some((id, _)) => *id,
+                None => break StreamId::MIN,
+            };
+            if count == 0 || attempts == 0 {
+                break id;
+            }
+            attempts -= 1;
+            cursor = id.next();
+
+            let fields = match self.entries.get(&id) {
+                Some(fields) => fields,
+                None => {
+                    group.remove_pending(id);
+                    deleted.push(id);
+                    count -= 1;
+                    continue;
+                }
+            };
+            if group.pending.get(&id).is_some_and(|pending| now.saturating_sub(pending.delivery_time) < options.min_idle) {
+                continue;
+            }
+
+            let pending = group.assign(id, consumer, now);
+            pending.delivery_time = now;
+            if !options.justid {
+                pending.delivery_count += 1;
+            }
+            group.consumer(consumer, now).active_time = Some(now);
+            claimed.push(StreamEntry { id, fields: fields.clone() });
+            count -= 1;
+        };
+        Some((next, claimed, deleted))
+    }
+
+    /// Returns what XINFO STREAM tells about the stream. The first `limit` entries, and
+    /// pending entries of each group and consumer, are listed for XINFO STREAM FULL.
+    fn info(&self, limit: usize) -> StreamInfo {
+        StreamInfo {
+            length: self.len(),
+            last_id: self.last_id,
+            max_deleted_id: self.max_deleted_id,
+            entries_added: self.entries_added,
+            first_id: self.first_id(),
+            first_entry: self.range(StreamId::MIN, StreamId::MAX).next(),
+            last_entry: self.range(StreamId::MIN, StreamId::MAX).next_back(),
+            entries: self.range(StreamId::MIN, StreamId::MAX).take(limit).collect(),
+            groups: self.groups_info(limit),
+        }
+    }
+
+    /// Returns what XINFO GROUPS tells about each group, listing the first `limit` pending
+    /// entries of the groups and consumers.
+    fn groups_info(&self, limit: usize) -> Vec<GroupInfo> {
+        self.groups
+            .iter()
+            .map(|(name, group)| GroupInfo {
+                name: name.clone(),
+                last_id: group.last_id,
+                entries_read: group.entries_read,
+                lag: self.lag(group),
+                pending_count: group.pending.len(),
+                pending: group.pending.iter().take(limit).map(|(id, pending)| (*id, pending.clone())).collect(),
+                consumers: group
+                    .consumers
+                    .iter()
+                    .map(|(name, consumer)| ConsumerInfo {
+                        name: name.clone(),
+                        seen_time: consumer.seen_time,
+                        active_time: consumer.active_time,
+                        pending_count: consumer.pending.len(),
+                        pending: consumer
+                            .pending
+                            .iter()
+                            .take(limit)
+                            .filter_map(|id| Some((*id, group.pending.get(id)?.clone())))
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl ConsumerGroup {
+    fn new(last_id: StreamId, entries_read: Option<u64>) -> ConsumerGroup {
+        ConsumerGroup { last_id, entries_read, pending: BTreeMap::new(), consumers: BTreeMap::new() }
+    }
+
+    /// Returns the consumer, creating it if missing, and marks it as seen.
+    fn consumer(&mut self, name: &Bytes, now: u64) -> &mut Consumer {
+        let consumer = self.consumers.entry(name.clone()).or_insert_with(|| Consumer::new(now));
+        consumer.seen_time = now;
+        consumer
+    }
+
+    /// Assigns the entry to the consumer, which must exist, adding it to the pending entries
+    /// list if missing. Returns the pending entry, to update its delivery.
+    fn assign(&mut self, id: StreamId, consumer: &Bytes, now: u64) -> &mut PendingEntry {
+        let pending = self.pending.entry(id).or_insert_with(|| PendingEntry {
+            consumer: consumer.clone(),
+            delivery_time: now,
+            delivery_count: 1,
+        });
+        if pending.consumer != *consumer {
+            if let Some(owner) = self.consumers.get_mut(&pending.consumer) {
+                owner.pending.remove(&id);
+            }
+            pending.consumer = consumer.clone();
+        }
+        if let Some(owner) = self.consumers.get_mut(consumer) {
+            owner.pending.insert(id);
+        }
+        pending
+    }
+
+    /// Removes the entry from the pending entries list. Returns false if it wasn't pending.
+    fn remove_pending(&mut self, id: StreamId) -> bool {
+        let pending = match self.pending.remove(&id) {
+            Some(pending) => pending,
+            None => return false,
+        };
+        if let Some(owner) = self.consumers.get_mut(&pending.consumer) {
+            owner.pending.remove(&id);
+        }
+        true
+    }
+}
+
+impl Consumer {
+    fn new(now: u64) -> Consumer {
+        Consumer { seen_time: now, active_time: None, pending: BTreeSet::new() }
+    }
+}
+
+impl DB {
+    /// Add an entry to the stream stored at the key, creating it if missing unless
+    /// `nomkstream` is set, then trim the stream. Clients blocked on the key are served
+    /// right away. Returns the ID of the entry, or `None` if the key doesn't exist and
+    /// `nomkstream` is set.
+    pub fn xadd(
+        &self,
+        key: Bytes,
+        id: XAddId,
+        fields: Vec<(Bytes, Bytes)>,
+        trim: Option<StreamTrim>,
+        nomkstream: bool,
+    ) -> Result<Option<StreamId>, DBError> {
+        let mut data = self.write()?;
+
+        // the ID is checked before creating the stream, so that nothing is created on error
+        let id = match stream_mut(&mut data, &key)? {
+            Some(stream) => stream.next_id(id)?,
+            None if nomkstream => return Ok(None),
+            None => Stream::default().next_id(id)?,
+        };
+
+        let stream = stream_or_insert(&mut data, key.clone())?;
+        stream.push(id, fields);
+        if let Some(trim) = trim {
+            stream.trim(&trim);
+        }
+        data.serve_blocked(key);
+        Ok(Some(id))
+    }
+
+    /// Get the number of entries of the stream, zero if missing.
+    pub fn xlen(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_stream(key, |stream| stream.map_or(0, Stream::len))
+    }
+
+    /// Get up to `count` entries with an ID between `start` and `end` included, from the
+    /// last one when `rev` is true. Returns `None` if the key doesn't exist.
+    pub fn xrange(
+        &self,
+        key: &[u8],
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+        rev: bool,
+    ) -> Result<Option<Vec<StreamEntry>>, DBError> {
+        self.with_stream(key, |stream| {
+            let stream = stream?;
+            let count = count.unwrap_or(usize::MAX);
+            if rev {
+                Some(stream.range(start, end).rev().take(count).collect())
+            } else {
+                Some(stream.range(start, end).take(count).collect())
+            }
+        })
+    }
+
+    /// Remove the entries from the stream. Returns the number of removed entries.
+    pub fn xdel(&self, key: &[u8], ids: &[StreamId]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        match stream_mut(&mut data, key)? {
+            Some(stream) => Ok(ids.iter().filter(|id| stream.remove(**id)).count()),
+            None => Ok(0),
+        }
+    }
+
+    /// Trim the stream. Returns the number of evicted entries.
+    pub fn xtrim(&self, key: &[u8], trim: &StreamTrim) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        match stream_mut(&mut data, key)? {
+            Some(stream) => Ok(stream.trim(trim)),
+            None => Ok(0),
+        }
+    }
+
+    /// Get up to `count` entries with an ID greater than the given one from each of the
+    /// streams, for the streams having such entries. `None` stands for the last ID of the
+    /// stream, which has nothing after it.
+    pub fn xread(
+        &self,
+        keys: &[Bytes],
+        ids: &[Option<StreamId>],
+        count: Option<usize>,
+    ) -> Result<Vec<(Bytes, Vec<StreamEntry>)>, DBError> {
+        let data = self.read()?;
+
+        let mut read = Vec::new();
+        for (key, id) in keys.iter().zip(ids) {
+            if let Some(entries) = data.read_stream(key, *id, count)? {
+                read.push((key.clone(), entries));
+            }
+        }
+        Ok(read)
+    }
+
+    /// Create a consumer group whose last delivered ID is `id`, `None` standing for the last
+    /// ID of the stream. The stream is created if missing when `mkstream` is set.
+    pub fn xgroup_create(
+        &self,
+        key: Bytes,
+        group: Bytes,
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    ) -> Result<(), DBError> {
+        let mut data = self.write()?;
+
+        if !mkstream {
+            existing_stream(&mut data, &key)?;
+        }
+        let stream = stream_or_insert(&mut data, key)?;
+        if stream.groups.contains_key(&group) {
+            return Err(DBError::BusyGroup);
+        }
+        let id = id.unwrap_or(stream.last_id);
+        stream.groups.insert(group, ConsumerGroup::new(id, entries_read));
+        Ok(())
+    }
+
+    /// Destroy the consumer group. Its blocked consumers are unblocked with an error.
+    /// Returns false if there's no such group.
+    pub fn xgroup_destroy(&self, key: Bytes, group: &[u8]) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        let destroyed = existing_stream(&mut data, &key)?.groups.remove(group).is_some();
+        if destroyed {
+            data.serve_blocked(key);
+        }
+        Ok(destroyed)
+    }
+
+    /// Set the ID of the last entry delivered to the group, `None` standing for the last ID
+    /// of the stream.
+    pub fn xgroup_setid(
+        &self,
+        key: &[u8],
+        group: &[u8],
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    ) -> Result<(), DBError> {
+        let mut data = self.write()?;
+
+        let stream = existing_stream(&mut data, key)?;
+        let last_id = stream.last_id;
+        let cg = stream.groups.get_mut(group).ok_or_else(|| no_such_group(key, group))?;
+        cg.last_id = id.unwrap_or(last_id);
+        cg.entries_read = entries_read;
+        Ok(())
+    }
+
+    /// Create a consumer in the group. Returns false if it already exists.
+    pub fn xgroup_createconsumer(&self, key: &[u8], group: &[u8], consumer: Bytes) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        let stream = existing_stream(&mut data, key)?;
+        let cg = stream.groups.get_mut(group).ok_or_else(|| no_such_group(key, group))?;
+        match cg.consumers.entry(consumer) {
+            MapEntry::Vacant(entry) => {
+                entry.insert(Consumer::new(now_ms()));
+                Ok(true)
+            }
+            MapEntry::Occupied(_) => Ok(false),
+        }
+    }
+
+    /// Delete the consumer from the group, along with its pending entries. Returns the
+    /// number of entries it had pending.
+    pub fn xgroup_delconsumer(&self, key: &[u8], group: &[u8], consumer: &[u8]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let stream = existing_stream(&mut data, key)?;
+        let cg = stream.groups.get_mut(group).ok_or_else(|| no_such_group(key, group))?;
+        let consumer = match cg.consumers.remove(consumer) {
+            Some(consumer) => consumer,
+            None => return Ok(0),
+        };
+        for id in consumer.pending.iter() {
+            cg.pending.remove(id);
+        }
+        Ok(consumer.pending.len())
+    }
+
+    /// Read from each of the streams as the consumer of the group, which is created if
+    /// missing. A `None` ID reads up to `count` entries never delivered to the group, which
+    /// are pending until acknowledged unless `noack` is set. Other IDs read the entries
+    /// pending for the consumer with a greater ID. Returns the entries read from each
+    /// stream, skipping the streams without new entries. Fails with NOGROUP if one of the
+    /// streams doesn't have the group.
+    pub fn xreadgroup(
+        &self,
+        group: &[u8],
+        consumer: &Bytes,
+        keys: &[Bytes],
+        ids: &[Option<StreamId>],
+        count: Option<usize>,
+        noack: bool,
+    ) -> Result<Vec<(Bytes, Vec<StreamEntry>)>, DBError> {
+        let mut data = self.write()?;
+
+        // nothing is read unless every stream has the group
+        for key in keys {
+            if !stream_mut(&mut data, key)?.is_some_and(|stream| stream.groups.contains_key(group)) {
+                return Err(DBError::NoGroup(format!(
+                    "No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(group),
+                )));
+            }
+        }
+
+        let now = now_ms();
+        let mut read = Vec::new();
+        for (key, id) in keys.iter().zip(ids) {
+            let stream = existing_stream(&mut data, key)?;
+            let entries = match id {
+                None => stream.read_group(group, consumer, count, noack, now).filter(|entries| !entries.is_empty()),
+                Some(id) => stream.read_group_history(group, consumer, *id, count, now),
+            };
+            if let Some(entries) = entries {
+                read.push((key.clone(), entries));
+            }
+        }
+        Ok(read)
+    }
+
+    /// Acknowledge the entries pending in the group. Returns the number of entries which
+    /// were pending.
+    pub fn xack(&self, key: &[u8], group: &[u8], ids: &[StreamId]) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let cg = match stream_mut(&mut data, key)?.and_then(|stream| stream.groups.get_mut(group)) {
+            Some(cg) => cg,
+            None => return Ok(0),
+        };
+        Ok(ids.iter().filter(|id| cg.remove_pending(**id)).count())
+    }
+
+    /// Get the summary of the entries pending in the group.
+    pub fn xpending_summary(&self, key: &[u8], group: &[u8]) -> Result<PendingSummary, DBError> {
+        self.with_group(key, group, |cg| PendingSummary {
+            count: cg.pending.len(),
+            range: cg.pending.first_key_value().zip(cg.pending.last_key_value()).map(|(first, last)| (*first.0, *last.0)),
+            consumers: cg
+                .consumers
+                .iter()
+                .filter(|(_, consumer)| !consumer.pending.is_empty())
+                .map(|(name, consumer)| (name.clone(), consumer.pending.len()))
+                .collect(),
+        })
+    }
+
+    /// Get the entries pending in the group which match the filter.
+    pub fn xpending(&self, key: &[u8], group: &[u8], filter: &PendingFilter) -> Result<Vec<(StreamId, PendingEntry)>, DBError> {
+        let now = now_ms();
+        self.with_group(key, group, |cg| {
+            let (start, end) = (filter.start, filter.end);
+            if start > end {
+                return Vec::new();
+            }
+            let ids: Box<dyn Iterator<Item = &StreamId>> = match &filter.consumer {
+                None => Box::new(cg.pending.range(start..=end).map(|(id, _)| id)),
+                Some(consumer) => match cg.consumers.get(consumer) {
+                    Some(consumer) => Box::new(consumer.pending.range(start..=end)),
+                    None => return Vec::new(),
+                },
+            };
+            ids.filter_map(|id| Some((*id, cg.pending.get(id)?)))
+                .filter(|(_, pending)| now.saturating_sub(pending.delivery_time) >= filter.min_idle)
+                .take(filter.count)
+                .map(|(id, pending)| (id, pending.clone()))
+                .collect()
+        })
+    }
+
+    /// Assign the entries pending in the group to the consumer, which is created if
+    /// missing, as told by the options. Returns the claimed entries.
+    pub fn xclaim(
+        &self,
+        key: &[u8],
+        group: &[u8],
+        consumer: &Bytes,
+        ids: &[StreamId],
+        options: &ClaimOptions,
+    ) -> Result<Vec<StreamEntry>, DBError> {
+        let mut data = self.write()?;
+
+        stream_mut(&mut data, key)?
+            .and_then(|stream| stream.claim(group, consumer, ids, options, now_ms()))
+            .ok_or_else(|| no_such_key_or_group(key, group))
+    }
+
+    /// Assign to the consumer, which is created if missing, up to `count` entries pending in
+    /// the group idle for long enough, scanning from `start`. Returns the ID to continue the
+    /// scan from, 0-0 once done, the claimed entries, and the IDs of the entries deleted
+    /// from the stream, which were removed from the group.
+    pub fn xautoclaim(
+        &self,
+        key: &[u8],
+        group: &[u8],
+        consumer: &Bytes,
+        start: StreamId,
+        count: usize,
+        options: &ClaimOptions,
+    ) -> Result<(StreamId, Vec<StreamEntry>, Vec<StreamId>), DBError> {
+        let mut data = self.write()?;
+
+        stream_mut(&mut data, key)?
+            .and_then(|stream| stream.autoclaim(group, consumer, start, count, options, now_ms()))
+            .ok_or_else(|| no_such_key_or_group(key, group))
+    }
+
+    /// Get what XINFO STREAM tells about the stream. With `full`, XINFO STREAM FULL, up to
+    /// that many entries and pending entries of each group and consumer are listed.
+    pub fn xinfo_stream(&self, key: &[u8], full: Option<usize>) -> Result<StreamInfo, DBError> {
+        self.with_stream(key, |stream| match stream {
+            Some(stream) => Ok(stream.info(full.unwrap_or(0))),
+            None => Err(no_such_key()),
+        })?
+    }
+
+    /// Get what XINFO GROUPS tells about the groups of the stream.
+    pub fn xinfo_groups(&self, key: &[u8]) -> Result<Vec<GroupInfo>, DBError> {
+        self.with_stream(key, |stream| match stream {
+            Some(stream) => Ok(stream.groups_info(0)),
+            None => Err(no_such_key()),
+        })?
+    }
+
+    /// Get what XINFO CONSUMERS tells about the consumers of the group.
+    pub fn xinfo_consumers(&self, key: &[u8], group: &[u8]) -> Result<Vec<ConsumerInfo>, DBError> {
+        self.with_stream(key, |stream| {
+            let stream = stream.ok_or_else(no_such_key)?;
+            if !stream.groups.contains_key(group) {
+                return Err(no_such_group(key, group));
+            }
+            let info = stream.groups_info(0).into_iter().find(|info| info.name == group);
+            Ok(info.map(|info| info.consumers).unwrap_or_default())
+        })?
+    }
+
+    // Runs `f` with the consumer group of the stream stored at the key under the read
+    // lock, failing with NOGROUP if there's no such stream or group.
+    fn with_group<T>(&self, key: &[u8], group: &[u8], f: impl FnOnce(&ConsumerGroup) -> T) -> Result<T, DBError> {
+        self.with_stream(key, |stream| match stream.and_then(|stream| stream.groups.get(group)) {
+            Some(cg) => Ok(f(cg)),
+            None => Err(no_such_key_or_group(key, group)),
+        })?
+    }
+
+    // Runs `f` with the stream stored at the key under the read lock,
+    // or `None` if the key doesn't exist.
+    fn with_stream<T>(&self, key: &[u8], f: impl FnOnce(Option<&Stream>) -> T) -> Result<T, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(f(None)),
+            Some(Entry { value: Value::Stream(stream), .. }) => Ok(f(Some(stream))),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+}
+
+impl Keyspace {
+    /// Get up to `count` entries with an ID greater than `after` from the stream stored
+    /// at the key, `None` standing for its last ID. Returns `None` if the key doesn't exist
+    /// or there are no such entries.
+    pub(crate) fn read_stream(
+        &self,
+        key: &[u8],
+        after: Option<StreamId>,
+        count: Option<usize>,
+    ) -> Result<Option<Vec<StreamEntry>>, DBError> {
+        let stream = match self.get(key) {
+            None => return Ok(None),
+            Some(Entry { value: Value::Stream(stream), .. }) => stream,
+            Some(_) => return Err(DBError::WrongType),
+        };
+        let entries = match after {
+            Some(after) => stream.read_after(after, count),
+            None => Vec::new(),
+        };
+        Ok((!entries.is_empty()).then_some(entries))
+    }
+
+    /// Delivers to the consumer of the group up to `count` entries never delivered to the
+    /// group from the stream stored at the key, as XREADGROUP does with the `>` ID. Returns
+    /// `None` if the key doesn't exist or there are no such entries. Fails with NOGROUP if
+    /// the group was destroyed.
+    pub(crate) fn read_group(
+        &mut self,
+        key: &[u8],
+        group: &[u8],
+        consumer: &Bytes,
+        count: Option<usize>,
+        noack: bool,
+    ) -> Result<Option<Vec<StreamEntry>>, DBError> {
+        let stream = match stream_mut(self, key)? {
+            Some(stream) => stream,
+            None => return Ok(None),
+        };
+        match stream.read_group(group, consumer, count, noack, now_ms()) {
+            Some(entries) => Ok((!entries.is_empty()).then_some(entries)),
+            None => Err(DBError::NoGroup(String::from(
+                "the consumer group this client was blocked on no longer exists",
+            ))),
+        }
+    }
+
+    /// Returns the ID of the last entry added to the stream stored at the key,
+    /// or 0-0 if the key doesn't exist.
+    pub(crate) fn last_stream_id(&self, key: &[u8]) -> Result<StreamId, DBError> {
+        match self.get(key) {
+            None => Ok(StreamId::MIN),
+            Some(Entry { value: Value::Stream(stream), .. }) => Ok(stream.last_id()),
+            Some(_) => Err(DBError::WrongType),
+        }
+    }
+}
+
+/// Returns the stream stored at the key, or `None` if the key doesn't exist.
+fn stream_mut<'a>(data: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Stream>, DBError> {
+    match data.get_mut(key) {
+        None => Ok(None),
+        Some(Entry { value: Value::Stream(stream), .. }) => Ok(Some(stream)),
+        Some(_) => Err(DBError::WrongType),
+    }
+}
+
+/// Returns the stream stored at the key, creating an empty one if missing.
+fn stream_or_insert(data: &mut Keyspace, key: Bytes) -> Result<&mut Stream, DBError> {
+    stream_mut(data, &key)?;
+    match &mut data.get_or_insert_with(key, || Value::Stream(Stream::default())).value {
+        Value::Stream(stream) => Ok(stream),
+        _ => Err(DBError::WrongType),
+    }
+}
+
+/// Returns the stream stored at the key, failing if the key doesn't exist, as XGROUP does.
+fn existing_stream<'a>(data: &'a mut Keyspace, key: &[u8]) -> Result<&'a mut Stream, DBError> {
+    stream_mut(data, key)?.ok_or_else(|| DBError::Other(String::from(
+        "The XGROUP subcommand requires the key to exist. \
+         Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.",
+    )))
+}
+
+fn no_such_key() -> DBError {
+    DBError::Other(String::from("no such key"))
+}
+
+fn no_such_group(key: &[u8], group: &[u8]) -> DBError {
+    DBError::NoGroup(format!(
+        "No such consumer group '{}' for key name '{}'",
+        String::from_utf8_lossy(group),
+        String::from_utf8_lossy(key),
+    ))
+}
+
+fn no_such_key_or_group(key: &[u8], group: &[u8]) -> DBError {
+    DBError::NoGroup(format!(
+        "No such key '{}' or consumer group '{}'",
+        String::from_utf8_lossy(key),
+        String::from_utf8_lossy(group),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(ms: u64, seq: u64) -> StreamId {
+        StreamId { ms, seq }
+    }
+
+    // Builds a stream holding entries with the IDs, whose field is their position.
+    fn stream(ids: &[(u64, u64)]) -> Stream {
+        let mut stream = Stream::default();
+        for (i, (ms, seq)) in ids.iter().enumerate() {
+            stream.push(id(*ms, *seq), vec![(Bytes::from("f"), Bytes::from(i.to_string()))]);
+        }
+        stream
+    }
+
+    fn ids(entries: impl IntoIterator<Item = StreamEntry>) -> Vec<StreamId> {
+        entries.into_iter().map(|entry| entry.id).collect()
+    }
+
+    fn error(result: Result<StreamId, DBError>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    const SMALLER: &str = "ERR The ID specified in XADD is equal or smaller than the target stream top item";
+
+    #[test]
+    fn generates_the_next_ids() {
+        let empty = Stream::default();
+        assert_eq!(empty.next_id(XAddId::AutoSeq(0)).unwrap(), id(0, 1));
+        assert_eq!(empty.next_id(XAddId::AutoSeq(5)).unwrap(), id(5, 0));
+        assert_eq!(empty.next_id(XAddId::Explicit(id(0, 1))).unwrap(), id(0, 1));
+        let zero = error(empty.next_id(XAddId::Explicit(id(0, 0))));
+        assert_eq!(zero, "ERR The ID specified in XADD must be greater than 0-0");
+
+        let s = stream(&[(5, 3)]);
+        assert_eq!(s.next_id(XAddId::AutoSeq(5)).unwrap(), id(5, 4));
+        assert_eq!(s.next_id(XAddId::AutoSeq(6)).unwrap(), id(6, 0));
+        assert_eq!(error(s.next_id(XAddId::AutoSeq(4))), SMALLER);
+        assert_eq!(error(s.next_id(XAddId::Explicit(id(5, 3)))), SMALLER);
+        assert_eq!(s.next_id(XAddId::Explicit(id(5, 4))).unwrap(), id(5, 4));
+        assert!(s.next_id(XAddId::Auto).unwrap() > id(5, 3));
+
+        // the clock is behind the last ID
+        let future = now_ms() + 100_000;
+        assert_eq!(stream(&[(future, 7)]).next_id(XAddId::Auto).unwrap(), id(future, 8));
+    }
+
+    #[test]
+    fn fails_once_the_sequence_numbers_are_exhausted() {
+        let s = stream(&[(5, u64::MAX)]);
+        assert_eq!(error(s.next_id(XAddId::AutoSeq(5))), SMALLER);
+        assert_eq!(s.next_id(XAddId::AutoSeq(6)).unwrap(), id(6, 0));
+        assert_eq!(s.next_id(XAddId::Explicit(id(6, 0))).unwrap(), id(6, 0));
+
+        // `*` moves on to the next millisecond, unless there's none left
+        let future = now_ms() + 100_000;
+        assert_eq!(stream(&[(future, u64::MAX)]).next_id(XAddId::Auto).unwrap(), id(future + 1, 0));
+        assert_eq!(
+            error(stream(&[(u64::MAX, u64::MAX)]).next_id(XAddId::Auto)),
+            "ERR The stream has exhausted the last possible ID, unable to add more items",
+        );
+    }
+
+    #[test]
+    fn keeps_ids_growing_after_deletions() {
+        let mut s = stream(&[(1, 0), (2, 0), (3, 0)]);
+        assert!(s.remove(id(3, 0)));
+        assert!(!s.remove(id(3, 0)));
+        assert_eq!(s.last_id(), id(3, 0));
+        assert_eq!(error(s.next_id(XAddId::Explicit(id(2, 5)))), SMALLER);
+        assert_eq!(s.next_id(XAddId::AutoSeq(3)).unwrap(), id(3, 1));
+    }
+
+    #[test]
+    fn reads_ranges_of_entries() {
+        let s = stream(&[(1, 0), (1, 1), (2, 0), (3, 0)]);
+        assert_eq!(ids(s.range(id(1, 1), id(2, 5))), [id(1, 1), id(2, 0)]);
+        assert_eq!(ids(s.range(id(2, 0), id(1, 0))), []);
+        assert_eq!(ids(s.range(StreamId::MIN, StreamId::MAX).rev().take(2)), [id(3, 0), id(2, 0)]);
+
+        assert_eq!(ids(s.read_after(id(1, 0), Some(2))), [id(1, 1), id(2, 0)]);
+        assert_eq!(ids(s.read_after(id(1, 5), None)), [id(2, 0), id(3, 0)]);
+        assert_eq!(ids(s.read_after(StreamId::MAX, None)), []);
+        assert!(s.has_entries_after(id(2, 9)));
+        assert!(!s.has_entries_after(id(3, 0)));
+    }
+
+    #[test]
+    fn trims_within_the_limit() {
+        let ids_of = |s: &Stream| ids(s.range(StreamId::MIN, StreamId::MAX));
+        let entries = [(1, 0), (2, 0), (3, 0), (4, 0), (5, 0)];
+
+        let mut s = stream(&entries);
+        let trim = |strategy, limit| StreamTrim { strategy, limit };
+        assert_eq!(s.trim(&trim(TrimStrategy::MaxLen(2), Some(2))), 2);
+        assert_eq!(ids_of(&s), [id(3, 0), id(4, 0), id(5, 0)]);
+        assert_eq!(s.trim(&trim(TrimStrategy::MaxLen(2), Some(2))), 1);
+        assert_eq!(s.trim(&trim(TrimStrategy::MaxLen(2), None)), 0);
+
+        let mut s = stream(&entries);
+        assert_eq!(s.trim(&trim(TrimStrategy::MinId(id(4, 0)), Some(1))), 1);
+        assert_eq!(s.trim(&trim(TrimStrategy::MinId(id(4, 0)), None)), 2);
+        assert_eq!(ids_of(&s), [id(4, 0), id(5, 0)]);
+
+        let mut s = stream(&entries);
+        assert_eq!(s.trim(&trim(TrimStrategy::MaxLen(0), None)), 5);
+        assert_eq!(s.len(), 0);
+        // the emptied stream remembers its last ID
+        assert_eq!(s.last_id(), id(5, 0));
+        assert_eq!(s.entries_added, 5);
+    }
+
+    #[test]
+    fn xadd_checks_the_id_before_creating_the_stream() {
+        let db = DB::new();
+        let fields = vec![(Bytes::from("f"), Bytes::from("v"))];
+        let key = Bytes::from("s");
+        assert!(db.xadd(key.clone(), XAddId::Explicit(StreamId::MIN), fields.clone(), None, false).is_err());
+        assert!(db.xinfo_stream(b"s", None).is_err());
+        assert_eq!(db.xadd(key.clone(), XAddId::AutoSeq(1), fields.clone(), None, true).unwrap(), None);
+        assert_eq!(db.xlen(b"s").unwrap(), 0);
+
+        let trim = StreamTrim { strategy: TrimStrategy::MaxLen(1), limit: None };
+        for seq in 0..3 {
+            let added = db.xadd(key.clone(), XAddId::AutoSeq(1), fields.clone(), Some(trim), false).unwrap();
+            assert_eq!(added, Some(id(1, seq)));
+        }
+        assert_eq!(db.xlen(b"s").unwrap(), 1);
+    }
+
+    // Builds a stream of `len` entries, the Nth having the ID N-0, and a group `g` which
+    // delivered them all to the consumer `a` unless `read` is false.
+    fn stream_with_group(len: u64, read: bool) -> Stream {
+        let ids: Vec<_> = (1..=len).map(|ms| (ms, 0)).collect();
+        let mut s = stream(&ids);
+        s.groups.insert(Bytes::from("g"), ConsumerGroup::new(StreamId::MIN, Some(0)));
+        if read {
+            s.read_group(b"g", &Bytes::from("a"), None, false, now_ms());
+        }
+        s
+    }
+
+    fn lag(s: &Stream) -> Option<u64> {
+        s.lag(&s.groups[&Bytes::from("g")])
+    }
+
+    fn pending(s: &Stream) -> Vec<(StreamId, Bytes, u64)> {
+        let group = &s.groups[&Bytes::from("g")];
+        group.pending.iter().map(|(id, p)| (*id, p.consumer.clone(), p.delivery_count)).collect()
+    }
+
+    #[test]
+    fn tells_the_lag_of_groups() {
+        let mut s = stream_with_group(5, false);
+        assert_eq!(lag(&s), Some(5));
+        s.read_group(b"g", &Bytes::from("a"), Some(2), false, now_ms());
+        assert_eq!(lag(&s), Some(3));
+
+        // deleting an entry already read doesn't matter
+        assert!(s.remove(id(1, 0)));
+        assert_eq!(lag(&s), Some(3));
+        // but deleting one yet to read does, until the group reads past it
+        assert!(s.remove(id(4, 0)));
+        assert_eq!(lag(&s), None);
+        s.read_group(b"g", &Bytes::from("a"), Some(1), false, now_ms());
+        assert_eq!(lag(&s), None);
+        s.read_group(b"g", &Bytes::from("a"), None, false, now_ms());
+        assert_eq!(lag(&s), Some(0));
+        assert_eq!(s.groups[&Bytes::from("g")].entries_read, Some(5));
+    }
+
+    #[test]
+    fn counts_the_entries_added_until_an_id() {
+        let mut s = stream(&[(1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]);
+        assert_eq!(s.entries_added_until(id(5, 0)), Some(5));
+        assert_eq!(s.entries_added_until(id(6, 0)), None);
+        assert_eq!(s.entries_added_until(StreamId::MIN), Some(0));
+        assert_eq!(s.entries_added_until(id(1, 0)), Some(1));
+        // the entries in the middle can't be counted
+        assert_eq!(s.entries_added_until(id(3, 0)), None);
+
+        // trimmed entries are counted
+        s.trim(&StreamTrim { strategy: TrimStrategy::MaxLen(3), limit: None });
+        assert_eq!(s.entries_added_until(id(2, 5)), Some(2));
+        assert_eq!(s.entries_added_until(id(3, 0)), Some(3));
+
+        // deleted ones are not, unless they're all before the first entry, which makes the
+        // IDs before it count all the entries gone
+        s.remove(id(4, 0));
+        assert_eq!(s.entries_added_until(id(3, 0)), None);
+        assert_eq!(s.entries_added_until(id(5, 0)), Some(5));
+        s.remove(id(3, 0));
+        assert_eq!(s.entries_added_until(id(2, 0)), Some(4));
+        assert_eq!(s.entries_added_until(id(4, 0)), Some(4));
+        assert_eq!(s.entries_added_until(id(5, 0)), Some(5));
+
+        // an empty stream counts all the entries ever added
+        s.remove(id(5, 0));
+        assert_eq!(s.entries_added_until(id(4, 0)), Some(5));
+        assert_eq!(s.entries_added_until(id(6, 0)), None);
+    }
+
+    #[test]
+    fn autoclaims_from_a_cursor() {
+        let mut s = stream_with_group(5, true);
+        let b = Bytes::from("b");
+        let options = ClaimOptions::default();
+
+        let (next, claimed, deleted) = s.autoclaim(b"g", &b, StreamId::MIN, 2, &options, now_ms()).unwrap();
+        assert_eq!((next, ids(claimed), deleted), (id(3, 0), vec![id(1, 0), id(2, 0)], vec![]));
+
+        // a deleted entry counts as claimed, and leaves the pending entries list
+        s.remove(id(3, 0));
+        let (next, claimed, deleted) = s.autoclaim(b"g", &b, next, 2, &options, now_ms()).unwrap();
+        assert_eq!((next, ids(claimed), deleted), (id(5, 0), vec![id(4, 0)], vec![id(3, 0)]));
+
+        // 0-0 once the scan is done
+        let (next, claimed, _) = s.autoclaim(b"g", &b, next, 2, &options, now_ms()).unwrap();
+        assert_eq!((next, ids(claimed)), (StreamId::MIN, vec![id(5, 0)]));
+
+        let expected: Vec<_> = [1, 2, 4, 5].into_iter().map(|ms| (id(ms, 0), b.clone(), 2)).collect();
+        assert_eq!(pending(&s), expected);
+        assert!(s.autoclaim(b"nope", &b, StreamId::MIN, 2, &options, now_ms()).is_none());
+    }
+
+    #[test]
+    fn autoclaims_scanning_ten_entries_per_claim() {
+        let mut s = stream_with_group(25, true);
+        let b = Bytes::from("b");
+        // none of the entries is idle for long enough
+        let options = ClaimOptions { min_idle: 100_000, ..Default::default() };
+
+        let (next, claimed, _) = s.autoclaim(b"g", &b, StreamId::MIN, 1, &options, now_ms()).unwrap();
+        assert_eq!((next, claimed.len()), (id(11, 0), 0));
+        let (next, claimed, _) = s.autoclaim(b"g", &b, id(20, 0), 1, &options, now_ms()).unwrap();
+        assert_eq!((next, claimed.len()), (StreamId::MIN, 0));
+
+        // JUSTID claims without counting a delivery
+        let options = ClaimOptions { justid: true, ..Default::default() };
+        let (_, claimed, _) = s.autoclaim(b"g", &b, id(20, 0), 3, &options, now_ms()).unwrap();
+        assert_eq!(ids(claimed), [id(20, 0), id(21, 0), id(22, 0)]);
+        assert_eq!(pending(&s)[19], (id(20, 0), b, 1));
+    }
+
+    #[test]
+    fn claims_pending_entries() {
+        let mut s = stream_with_group(4, true);
+        let b = Bytes::from("b");
+        let now = now_ms();
+
+        // entries not idle for long enough are skipped
+        let options = ClaimOptions { min_idle: 100_000, ..Default::default() };
+        assert!(s.claim(b"g", &b, &[id(1, 0)], &options, now).unwrap().is_empty());
+
+        // RETRYCOUNT sets the delivery count, which is incremented otherwise
+        let options = ClaimOptions { retry_count: Some(7), ..Default::default() };
+        assert_eq!(ids(s.claim(b"g", &b, &[id(1, 0)], &options, now).unwrap()), [id(1, 0)]);
+        let options = ClaimOptions::default();
+        assert_eq!(ids(s.claim(b"g", &b, &[id(2, 0)], &options, now).unwrap()), [id(2, 0)]);
+        let options = ClaimOptions { justid: true, ..Default::default() };
+        assert_eq!(ids(s.claim(b"g", &b, &[id(3, 0)], &options, now).unwrap()), [id(3, 0)]);
+
+        // a deleted entry leaves the pending entries list
+        s.remove(id(4, 0));
+        assert!(s.claim(b"g", &b, &[id(4, 0)], &ClaimOptions::default(), now).unwrap().is_empty());
+
+        let a = Bytes::from("a");
+        let expected = [(id(1, 0), b.clone(), 7), (id(2, 0), b.clone(), 2), (id(3, 0), b.clone(), 1)];
+        assert_eq!(pending(&s), expected);
+        let group = &s.groups[&Bytes::from("g")];
+        assert!(group.consumers[&a].pending.is_empty());
+        assert_eq!(group.consumers[&b].pending.len(), 3);
+    }
+
+    #[test]
+    fn forces_the_claim_of_entries_not_pending() {
+        let mut s = stream_with_group(3, false);
+        let b = Bytes::from("b");
+        let now = now_ms();
+
+        // without FORCE, only pending entries are claimed
+        assert!(s.claim(b"g", &b, &[id(1, 0)], &ClaimOptions::default(), now).unwrap().is_empty());
+
+        let options = ClaimOptions { force: true, ..Default::default() };
+        assert_eq!(ids(s.claim(b"g", &b, &[id(1, 0), id(9, 0)], &options, now).unwrap()), [id(1, 0)]);
+        let options =
+            ClaimOptions { force: true, retry_count: Some(0), last_id: Some(id(2, 0)), ..Default::default() };
+        assert_eq!(ids(s.claim(b"g", &b, &[id(3, 0)], &options, now).unwrap()), [id(3, 0)]);
+        assert_eq!(pending(&s), [(id(1, 0), b.clone(), 2), (id(3, 0), b, 0)]);
+        assert_eq!(s.groups[&Bytes::from("g")].last_id, id(2, 0));
+
+        // a deleted entry can't be forced
+        s.remove(id(2, 0));
+        let options = ClaimOptions { force: true, ..Default::default() };
+        assert!(s.claim(b"g", &Bytes::from("c"), &[id(2, 0)], &options, now).unwrap().is_empty());
+        assert_eq!(pending(&s).len(), 2);
+    }
+}
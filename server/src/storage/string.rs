@@ -0,0 +1,343 @@
+use bytes::{Bytes, BytesMut};
+use crate::storage::db::{Entry, Keyspace, Value, DB};
+use crate::storage::DBError;
+use crate::utils;
+
+/// Maximum length of a string value, same as the default `proto-max-bulk-len` of Redis.
+pub const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+/// The options of the SET command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SetOptions {
+    /// Only set the key if it doesn't exist.
+    pub nx: bool,
+    /// Only set the key if it already exists.
+    pub xx: bool,
+    /// Return the previous string value of the key.
+    pub get: bool,
+    /// Retain the TTL of the previous value.
+    pub keep_ttl: bool,
+    /// Unix time in milliseconds at which the new value expires.
+    pub expires_at: Option<u64>,
+}
+
+/// How GETEX updates the TTL of the key.
+#[derive(Debug, Clone, Copy)]
+pub enum GetExExpiry {
+    /// Leave the TTL unchanged.
+    Keep,
+    /// Remove the TTL (PERSIST).
+    Persist,
+    /// Expire the key at the given unix time in milliseconds.
+    At(u64),
+}
+
+/// The outcome of a SET command.
+#[derive(Debug)]
+pub struct SetResult {
+    /// Whether the value was set, which fails when the NX or XX condition isn't met.
+    pub applied: bool,
+    /// The previous value of the key, only looked up with the GET option.
+    pub previous: Option<Bytes>,
+}
+
+impl DB {
+    /// Set the string value of a key honoring the SET options.
+    /// With the GET option, fails with WRONGTYPE if the key holds a non string value.
+    pub fn set_with_options(&self, key: Bytes, value: Bytes, opts: SetOptions) -> Result<SetResult, DBError> {
+        let mut data = self.write()?;
+
+        let current = data.get_mut(&key);
+        let exists = current.is_some();
+
+        let previous = match current {
+            Some(Entry { value: Value::String(s), .. }) if opts.get => Some(s.clone()),
+            Some(_) if opts.get => return Err(DBError::WrongType),
+            _ => None,
+        };
+
+        if opts.nx && exists || opts.xx && !exists {
+            return Ok(SetResult { applied: false, previous });
+        }
+
+        let expires_at = match opts.keep_ttl {
+            true => data.get_mut(&key).and_then(|entry| entry.expires_at()),
+            false => opts.expires_at,
+        };
+        data.insert(key, Entry::with_expires_at(Value::String(value), expires_at));
+
+        Ok(SetResult { applied: true, previous })
+    }
+
+    /// Get the string value of the key and delete it.
+    pub fn getdel(&self, key: &[u8]) -> Result<Option<Bytes>, DBError> {
+        let mut data = self.write()?;
+
+        match data.get_mut(key) {
+            None => Ok(None),
+            Some(Entry { value: Value::String(s), .. }) => {
+                let value = s.clone();
+                data.remove(key);
+                Ok(Some(value))
+            }
+            Some(_) => Err(DBError::WrongType),
+        }
+    }
+
+    /// Get the string value of the key and update its TTL.
+    /// A deadline in the past deletes the key after reading it.
+    pub fn getex(&self, key: &[u8], expiry: GetExExpiry) -> Result<Option<Bytes>, DBError> {
+        let mut data = self.write()?;
+
+        let value = match data.get_mut(key) {
+            None => return Ok(None),
+            Some(Entry { value: Value::String(s), .. }) => s.clone(),
+            Some(_) => return Err(DBError::WrongType),
+        };
+
+        match expiry {
+            GetExExpiry::Keep => {}
+            GetExExpiry::Persist => {
+                data.set_expires_at(key, None);
+            }
+            GetExExpiry::At(deadline) if deadline <= utils::now_ms() => {
+                data.remove(key);
+            }
+            GetExExpiry::At(deadline) => {
+                data.set_expires_at(key, Some(deadline));
+            }
+        }
+        Ok(Some(value))
+    }
+
+    /// Increment the integer stored at the key by `delta`. A missing key counts as zero.
+    /// The TTL of the key is retained. Returns the new value.
+    pub fn incr_by(&self, key: Bytes, delta: i64) -> Result<i64, DBError> {
+        let mut data = self.write()?;
+
+        let current = match data.get_mut(&key) {
+            None => 0,
+            Some(Entry { value: Value::String(s), .. }) => {
+                utils::parse_i64(s).ok_or(DBError::NotInteger)?
+            }
+            Some(_) => return Err(DBError::WrongType),
+        };
+
+        let new = current.checked_add(delta).ok_or(DBError::Overflow)?;
+        Self::replace_string(&mut data, key, Bytes::from(new.to_string()));
+        Ok(new)
+    }
+
+    /// Increment the floating point number stored at the key by `delta`.
+    /// A missing key counts as zero. The TTL of the key is retained.
+    /// Returns the new value, formatted the same way it is stored.
+    pub fn incr_by_float(&self, key: Bytes, delta: f64) -> Result<Bytes, DBError> {
+        let mut data = self.write()?;
+
+        let current = match data.get_mut(&key) {
+            None => 0.0,
+            Some(Entry { value: Value::String(s), .. }) => {
+                utils::parse_f64(s).ok_or(DBError::NotFloat)?
+            }
+            Some(_) => return Err(DBError::WrongType),
+        };
+
+        if !(current + delta).is_finite() {
+            return Err(DBError::NanOrInfinity);
+        }
+
+        let new = Bytes::from(utils::add_f64_human(current, delta));
+        Self::replace_string(&mut data, key, new.clone());
+        Ok(new)
+    }
+
+    /// Append the value to the string stored at the key, creating it if missing.
+    /// Returns the length of the string after the append.
+    pub fn append(&self, key: Bytes, value: Bytes) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        match data.get_mut(&key) {
+            None => {
+                let len = value.len();
+                data.insert(key, Entry::new(Value::String(value)));
+                Ok(len)
+            }
+            Some(Entry { value: Value::String(s), .. }) => {
+                if s.len() + value.len() > MAX_STRING_LEN {
+                    return Err(DBError::StringTooLong);
+                }
+                Ok(update_bytes(s, |buf| {
+                    buf.extend_from_slice(&value);
+                    buf.len()
+                }))
+            }
+            Some(_) => Err(DBError::WrongType),
+        }
+    }
+
+    /// Get the length of the string stored at the key, zero if missing.
+    pub fn strlen(&self, key: &[u8]) -> Result<usize, DBError> {
+        self.with_entry(key, |entry| match entry {
+            None => Ok(0),
+            Some(Entry { value: Value::String(s), .. }) => Ok(s.len()),
+            Some(_) => Err(DBError::WrongType),
+        })
+    }
+
+    /// Get the substring between the `start` and `end` offsets, both included.
+    /// Negative offsets count from the end of the string.
+    pub fn getrange(&self, key: &[u8], start: i64, end: i64) -> Result<Bytes, DBError> {
+        self.with_entry(key, |entry| {
+            let s = match entry {
+                None => return Ok(Bytes::new()),
+                Some(Entry { value: Value::String(s), .. }) => s,
+                Some(_) => return Err(DBError::WrongType),
+            };
+
+            let len = s.len() as i64;
+            if start < 0 && end < 0 && start > end {
+                return Ok(Bytes::new());
+            }
+            let start = if start < 0 { (len + start).max(0) } else { start };
+            let end = if end < 0 { (len + end).max(0) } else { end.min(len - 1) };
+            if len == 0 || start > end {
+                return Ok(Bytes::new());
+            }
+            Ok(s.slice(start as usize..=end as usize))
+        })
+    }
+
+    /// Overwrite part of the string stored at the key starting at `offset`, padding
+    /// with zero bytes if the string is shorter. Returns the length of the string.
+    pub fn setrange(&self, key: Bytes, offset: usize, value: Bytes) -> Result<usize, DBError> {
+        let mut data = self.write()?;
+
+        let current_len = match data.get_mut(&key) {
+            None => 0,
+            Some(Entry { value: Value::String(s), .. }) => s.len(),
+            Some(_) => return Err(DBError::WrongType),
+        };
+        // an empty value doesn't modify the string, nor creates the key
+        if value.is_empty() {
+            return Ok(current_len);
+        }
+        if offset + value.len() > MAX_STRING_LEN {
+            return Err(DBError::StringTooLong);
+        }
+
+        let entry = data.get_or_insert_with(key, || Value::String(Bytes::new()));
+        match &mut entry.value {
+            Value::String(s) => Ok(update_bytes(s, |buf| {
+                if buf.len() < offset + value.len() {
+                    buf.resize(offset + value.len(), 0);
+                }
+                buf[offset..offset + value.len()].copy_from_slice(&value);
+                buf.len()
+            })),
+            _ => Err(DBError::WrongType),
+        }
+    }
+
+    /// Get the values of all the keys. Missing keys and keys not holding
+    /// a string are returned as `None`.
+    pub fn mget(&self, keys: &[Bytes]) -> Result<Vec<Option<Bytes>>, DBError> {
+        let data = self.read()?;
+
+        let values = keys
+            .iter()
+            .map(|key| match data.get(key) {
+                Some(Entry { value: Value::String(s), .. }) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        Ok(values)
+    }
+
+    /// Set all the key value pairs at once, discarding their TTLs.
+    /// With `nx` no key is set if any of them already exists.
+    /// Returns true if the keys were set.
+    pub fn mset(&self, pairs: Vec<(Bytes, Bytes)>, nx: bool) -> Result<bool, DBError> {
+        let mut data = self.write()?;
+
+        if nx && pairs.iter().any(|(key, _)| data.get(key).is_some()) {
+            return Ok(false);
+        }
+
+        for (key, value) in pairs {
+            data.insert(key, Entry::new(Value::String(value)));
+        }
+        Ok(true)
+    }
+
+    // Stores the string value at the key, retaining the TTL of an existing key.
+    fn replace_string(data: &mut Keyspace, key: Bytes, value: Bytes) {
+        match data.get_mut(&key) {
+            Some(entry) => entry.value = Value::String(value),
+            None => {
+                data.insert(key, Entry::new(Value::String(value)));
+            }
+        }
+    }
+}
+
+/// Modifies a string value in place. The bytes are only copied when they are
+/// shared, e.g. by a reply that is still being written to a client.
+pub fn update_bytes<T>(s: &mut Bytes, f: impl FnOnce(&mut BytesMut) -> T) -> T {
+    let mut buf = BytesMut::from(std::mem::take(s));
+    let result = f(&mut buf);
+    *s = buf.freeze();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(db: &DB, value: &'static str, opts: SetOptions) -> SetResult {
+        db.set_with_options(Bytes::from("k"), Bytes::from(value), opts).unwrap()
+    }
+
+    #[test]
+    fn set_gets_the_previous_value_even_when_nx_fails() {
+        let db = DB::new();
+        let opts = SetOptions { nx: true, get: true, ..Default::default() };
+
+        let res = set(&db, "a", opts);
+        assert!(res.applied);
+        assert_eq!(res.previous, None);
+
+        let res = set(&db, "b", opts);
+        assert!(!res.applied);
+        assert_eq!(res.previous, Some(Bytes::from("a")));
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), Some(Bytes::from("a")));
+
+        let res = set(&db, "c", SetOptions { xx: true, get: true, ..Default::default() });
+        assert!(res.applied);
+        assert_eq!(res.previous, Some(Bytes::from("a")));
+
+        db.sadd(Bytes::from("s"), vec![Bytes::from("m")]).unwrap();
+        let opts = SetOptions { get: true, ..Default::default() };
+        let err = db.set_with_options(Bytes::from("s"), Bytes::from("v"), opts).unwrap_err();
+        assert_eq!(err.to_string(), DBError::WrongType.to_string());
+    }
+
+    #[test]
+    fn set_keeps_the_ttl_only_with_keepttl() {
+        let db = DB::new();
+        let deadline = utils::now_ms() + 100_000;
+        set(&db, "a", SetOptions { expires_at: Some(deadline), ..Default::default() });
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(Some(deadline)));
+
+        set(&db, "b", SetOptions { keep_ttl: true, ..Default::default() });
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(Some(deadline)));
+        assert_eq!(db.get(&Bytes::from("k")).unwrap(), Some(Bytes::from("b")));
+
+        set(&db, "c", SetOptions::default());
+        assert_eq!(db.expires_at(b"k").unwrap(), Some(None));
+
+        // a missing key has no TTL to keep
+        let opts = SetOptions { keep_ttl: true, ..Default::default() };
+        db.set_with_options(Bytes::from("new"), Bytes::from("v"), opts).unwrap();
+        assert_eq!(db.expires_at(b"new").unwrap(), Some(None));
+    }
+}
@@ -0,0 +1,226 @@
+/// Parses a signed 64 bit integer the way Redis does it (`string2ll`).
+///
+/// It's stricter than `str::parse`: no leading `+`, no leading zeros and no spaces,
+/// so that only the canonical representation of a number is accepted.
+pub fn parse_i64(buf: &[u8]) -> Option<i64> {
+    let digits = buf.strip_prefix(b"-").unwrap_or(buf);
+    match digits {
+        [] => return None,
+        [b'0'] => return if digits.len() == buf.len() { Some(0) } else { None },
+        [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => {}
+        _ => return None,
+    }
+
+    std::str::from_utf8(buf).ok()?.parse::<i64>().ok()
+}
+
+/// Parses a double the way Redis does it (`strtold`): `inf` and `-inf` are valid,
+/// `nan` and surrounding spaces are not.
+pub fn parse_f64(buf: &[u8]) -> Option<f64> {
+    let s = std::str::from_utf8(buf).ok()?;
+    if s.is_empty() || s.starts_with(char::is_whitespace) || s.ends_with(char::is_whitespace) {
+        return None;
+    }
+
+    match s.parse::<f64>() {
+        Ok(d) if !d.is_nan() => Some(d),
+        _ => None,
+    }
+}
+
+/// Adds two doubles and formats the sum the way Redis stores the result of INCRBYFLOAT
+/// (`%.17Lf` with the trailing zeros removed): never in exponent notation, rounded to 17
+/// decimals, e.g. `3`, `10.5` or `100000000000000000`, and `0` rather than `-0`.
+///
+/// Redis adds long doubles, whose extra precision turns `0.1 + 0.2` into `0.3` once
+/// rounded. The sum of the shortest decimal representations of the operands, computed
+/// exactly, rounds the same way.
+pub fn add_f64_human(a: f64, b: f64) -> String {
+    Decimal::from_f64(a).add(&Decimal::from_f64(b)).into_human()
+}
+
+/// A decimal number: `digits * 10^exp`, the digits being stored most significant first.
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exp: i32,
+}
+
+impl Decimal {
+    /// The number of decimals kept by `into_human`.
+    const HUMAN_DECIMALS: i32 = 17;
+
+    /// Returns the shortest decimal representation of the finite double.
+    fn from_f64(d: f64) -> Decimal {
+        // e.g. `1.5e-7`, the shortest representation that round-trips
+        let s = format!("{:e}", d.abs());
+        let (mantissa, exp) = s.split_once('e').expect("exponent notation");
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        Decimal {
+            negative: d.is_sign_negative(),
+            digits: int.bytes().chain(frac.bytes()).map(|c| c - b'0').collect(),
+            exp: exp.parse::<i32>().expect("integer exponent") - frac.len() as i32,
+        }
+    }
+
+    fn add(mut self, other: &Decimal) -> Decimal {
+        // align both on the lowest exponent, then add or subtract the digits as integers
+        let exp = self.exp.min(other.exp);
+        let mut other_digits = other.digits.clone();
+        self.digits.resize(self.digits.len() + (self.exp - exp) as usize, 0);
+        other_digits.resize(other_digits.len() + (other.exp - exp) as usize, 0);
+
+        let (negative, digits) = if self.negative == other.negative {
+            (self.negative, add_digits(&self.digits, &other_digits))
+        } else if cmp_digits(&self.digits, &other_digits).is_ge() {
+            (self.negative, sub_digits(&self.digits, &other_digits))
+        } else {
+            (other.negative, sub_digits(&other_digits, &self.digits))
+        };
+        Decimal { negative, digits, exp }
+    }
+
+    fn into_human(mut self) -> String {
+        if self.exp < -Self::HUMAN_DECIMALS {
+            // round half up to the last decimal kept
+            let dropped = (-Self::HUMAN_DECIMALS - self.exp) as usize;
+            // the first dropped digit is an implicit zero when all the digits are dropped
+            let round_up = match self.digits.len().checked_sub(dropped) {
+                Some(kept) => self.digits.get(kept).is_some_and(|digit| *digit >= 5),
+                None => false,
+            };
+            self.digits.truncate(self.digits.len().saturating_sub(dropped));
+            if round_up {
+                self.digits = add_digits(&self.digits, &[1]);
+            }
+            self.exp = -Self::HUMAN_DECIMALS;
+        }
+
+        let decimals = (-self.exp).max(0) as usize;
+        let mut digits = self.digits;
+        digits.resize(digits.len() + self.exp.max(0) as usize, 0);
+        if digits.len() <= decimals {
+            let mut padded = vec![0; decimals + 1 - digits.len()];
+            padded.extend(digits);
+            digits = padded;
+        }
+        let (int, frac) = digits.split_at(digits.len() - decimals);
+        let int = &int[int.iter().position(|d| *d != 0).unwrap_or(int.len() - 1)..];
+        let frac = &frac[..frac.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1)];
+
+        let mut s = String::new();
+        if self.negative && (int != [0] || !frac.is_empty()) {
+            s.push('-');
+        }
+        s.extend(int.iter().map(|d| (b'0' + d) as char));
+        if !frac.is_empty() {
+            s.push('.');
+            s.extend(frac.iter().map(|d| (b'0' + d) as char));
+        }
+        s
+    }
+}
+
+fn cmp_digits(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+    let a = &a[a.iter().position(|d| *d != 0).unwrap_or(a.len())..];
+    let b = &b[b.iter().position(|d| *d != 0).unwrap_or(b.len())..];
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn add_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let digit = |x: &[u8]| if i < x.len() { x[x.len() - 1 - i] } else { 0 };
+        let d = digit(a) + digit(b) + carry;
+        sum.push(d % 10);
+        carry = d / 10;
+    }
+    sum.push(carry);
+    sum.reverse();
+    sum
+}
+
+/// Subtracts `b` from `a`, which must be at least as large.
+fn sub_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for i in 0..a.len() {
+        let b = if i < b.len() { b[b.len() - 1 - i] } else { 0 };
+        let mut d = a[a.len() - 1 - i] as i8 - b as i8 - borrow;
+        borrow = (d < 0) as i8;
+        if d < 0 {
+            d += 10;
+        }
+        diff.push(d as u8);
+    }
+    diff.reverse();
+    diff
+}
+
+/// Returns the current unix time in milliseconds.
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+thread_local! {
+    // State of the xorshift generator used by `random_u64`, seeded per thread.
+    static RNG_STATE: std::cell::Cell<u64> = std::cell::Cell::new({
+        use std::hash::{BuildHasher, Hasher};
+        // RandomState is seeded from the OS, the state must never be zero
+        std::collections::hash_map::RandomState::new().build_hasher().finish() | 1
+    });
+}
+
+/// Returns a pseudo random number. Not suitable for cryptography, only used to
+/// pick random keys and elements.
+pub fn random_u64() -> u64 {
+    RNG_STATE.with(|state| {
+        // xorshift64*
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545F4914F6CDD1D)
+    })
+}
+
+/// Returns a pseudo random index in `0..len`. `len` must not be zero.
+pub fn random_index(len: usize) -> usize {
+    (random_u64() % len as u64) as usize
+}
+
+/// Shuffles the items in place (Fisher-Yates).
+pub fn shuffle<T>(items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, random_index(i + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sums_like_redis() {
+        assert_eq!(add_f64_human(0.1, 0.2), "0.3");
+        assert_eq!(add_f64_human(10.5, 0.1), "10.6");
+        assert_eq!(add_f64_human(1e17, 0.0), "100000000000000000");
+        assert_eq!(add_f64_human(-0.0, 0.0), "0");
+        assert_eq!(add_f64_human(-0.0, -0.0), "0");
+        assert_eq!(add_f64_human(3.0, 0.0), "3");
+        assert_eq!(add_f64_human(5.0e3, 0.0), "5000");
+        assert_eq!(add_f64_human(-5.0e3, 2.5), "-4997.5");
+        assert_eq!(add_f64_human(0.1, -0.3), "-0.2");
+        assert_eq!(add_f64_human(1.0, -1.0), "0");
+        assert_eq!(add_f64_human(1e-20, 0.0), "0");
+        assert_eq!(add_f64_human(-1e-20, 0.0), "0");
+        assert_eq!(add_f64_human(5e-18, 0.0), "0.00000000000000001");
+        assert_eq!(add_f64_human(1.5e-17, 0.0), "0.00000000000000002");
+        assert_eq!(add_f64_human(99.99999999999999, 1e-14), "100");
+    }
+}